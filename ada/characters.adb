@@ -0,0 +1,51 @@
+with Ada.Text_IO; use Ada.Text_IO;
+
+procedure Main is
+   type Hex_Digit is ('0', '1', '2', '3', '4', '5', '6', '7',
+                      '8', '9', 'A', 'B', 'C', 'D', 'E', 'F');
+
+   type Color is (Red, Green, Blue);
+   type Signal is (Red, Yellow, Green);
+
+   type Mixed is (None, 'x', 'X');
+
+   procedure Show (C : Color) is
+   begin
+      Put_Line ("color " & Color'Image (C));
+   end Show;
+
+   procedure Show (S : Signal) is
+   begin
+      Put_Line ("signal " & Signal'Image (S));
+   end Show;
+
+   D : Hex_Digit := 'A';
+   C : Character := 'A';
+   M : Mixed := 'x';
+   S : String := "tab";
+begin
+   Put_Line (Hex_Digit'Image (D));
+   if D > '9' then
+      Put_Line ("letter digit");
+   end if;
+   D := 'F';
+   Put_Line (Hex_Digit'Image (D));
+
+   Put_Line (Character'Image (C));
+   C := S (1);
+   Put_Line (Character'Image (C));
+   if C < 'u' then
+      Put_Line ("before u");
+   end if;
+
+   Put_Line (Mixed'Image (M));
+   M := 'X';
+   Put_Line (Mixed'Image (M));
+   M := None;
+   Put_Line (Mixed'Image (M));
+
+   Show (Color'(Red));
+   Show (Signal'(Red));
+   Show (Blue);
+   Show (Yellow);
+end Main;
@@ -0,0 +1,21 @@
+with Ada.Text_IO; use Ada.Text_IO;
+with Ada.Command_Line; use Ada.Command_Line;
+with Ada.Environment_Variables;
+
+procedure Main is
+   Count : Natural := Argument_Count;
+begin
+   Put_Line ("arguments:" & Integer'Image (Count));
+   for I in 1 .. Count loop
+      Put_Line (Argument (I));
+   end loop;
+
+   Ada.Environment_Variables.Set ("ANNABELLA_TEST", "set");
+   Put_Line (Ada.Environment_Variables.Value ("ANNABELLA_TEST"));
+   Ada.Environment_Variables.Clear ("ANNABELLA_TEST");
+   Put_Line (Ada.Environment_Variables.Value ("ANNABELLA_TEST", "cleared"));
+
+   if Count = 0 then
+      Set_Exit_Status (Failure);
+   end if;
+end Main;
@@ -0,0 +1,72 @@
+package Stacks is
+   Capacity : constant := 3;
+
+   type Stack is record
+      Top : Integer;
+      Item_1 : Integer;
+      Item_2 : Integer;
+      Item_3 : Integer;
+   end record;
+
+   procedure Clear (S : out Stack)
+     with Post => S.Top = 0;
+
+   function Size (S : Stack) return Integer
+     with Post => Size'Result >= 0 and Size'Result <= Capacity;
+
+   procedure Push (S : in out Stack; Item : Integer)
+     with Pre => Size (S) < Capacity,
+          Post => Size (S) > 0;
+end Stacks;
+
+package body Stacks is
+   procedure Clear (S : out Stack) is
+   begin
+      S.Top := 0;
+   end Clear;
+
+   function Size (S : Stack) return Integer is
+   begin
+      return S.Top;
+   end Size;
+
+   procedure Push (S : in out Stack; Item : Integer) is
+   begin
+      S.Top := S.Top + 1;
+      if S.Top = 1 then
+         S.Item_1 := Item;
+      elsif S.Top = 2 then
+         S.Item_2 := Item;
+      else
+         S.Item_3 := Item;
+      end if;
+   end Push;
+end Stacks;
+
+with Ada.Text_IO; use Ada.Text_IO;
+with Stacks; use Stacks;
+
+procedure Main is
+   pragma Assertion_Policy (Check);
+
+   S : Stack;
+
+   function Double (X : Integer) return Integer
+     with Pre => X >= 0, Post => Double'Result = 2 * X
+   is
+   begin
+      return X + X;
+   end Double;
+begin
+   Clear (S);
+   Push (S, 10);
+   Push (S, 20);
+   pragma Assert (Size (S) = 2, "two items");
+   pragma Check (Stack_Size, Size (S) <= Capacity);
+   Put_Line (Integer'Image (Size (S)));
+   Put_Line (Integer'Image (Double (21)));
+   Push (S, 30);
+   Put_Line (Integer'Image (Size (S)));
+   Push (S, 40);
+   Put_Line ("not reached");
+end Main;
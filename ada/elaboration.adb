@@ -0,0 +1,55 @@
+with Ada.Text_IO; use Ada.Text_IO;
+
+package Log is
+   pragma Elaborate_Body;
+
+   Count : Integer := 0;
+
+   procedure Trace (Message : String);
+end Log;
+
+with Ada.Text_IO; use Ada.Text_IO;
+
+package body Log is
+   procedure Trace (Message : String) is
+   begin
+      Count := Count + 1;
+      Put_Line (Message);
+   end Trace;
+begin
+   Trace ("elaborating Log");
+end Log;
+
+package Limits is
+   pragma Preelaborate;
+
+   Max : constant Integer := 100;
+end Limits;
+
+with Limits;
+with Log;
+pragma Elaborate_All (Log);
+
+package Config is
+   Level : Integer := Log.Count + 10;
+end Config;
+
+with Config;
+package body Config is
+begin
+   Log.Trace ("elaborating Config");
+   if Level > Limits.Max then
+      Level := Limits.Max;
+   end if;
+end Config;
+
+with Ada.Text_IO; use Ada.Text_IO;
+with Config;
+with Log;
+
+procedure Main is
+begin
+   Put_Line (Integer'Image (Config.Level));
+   Log.Trace ("main");
+   Put_Line (Integer'Image (Log.Count));
+end Main;
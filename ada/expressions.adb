@@ -0,0 +1,68 @@
+with Ada.Text_IO; use Ada.Text_IO;
+
+procedure Main is
+   type Color is (Red, Green, Blue);
+
+   Calls : Integer := 0;
+
+   function Counted (X : Integer) return Integer is
+   begin
+      Calls := Calls + 1;
+      return X;
+   end Counted;
+
+   function Sign (X : Integer) return Integer is
+   begin
+      return (if X > 0 then 1 elsif X < 0 then -1 else 0);
+   end Sign;
+
+   function Name (C : Color) return String is
+   begin
+      return (case C is
+                when Red => "red",
+                when Green | Blue => "green or blue");
+   end Name;
+
+   function Is_Prime (N : Integer) return Boolean is
+   begin
+      return N > 1 and then (for all D in 2 .. N - 1 => (N / D) * D /= N);
+   end Is_Prime;
+
+   function Has_Square (From : Integer; To : Integer; N : Integer) return Boolean is
+   begin
+      return (for some I in From .. To => I * I = N);
+   end Has_Square;
+
+   X : Integer := 7;
+begin
+   Print (X * (1 + 2));
+   Print (Sign (-5));
+   Print (Sign (0));
+   Print (Sign (X));
+
+   Put_Line (Name (Red));
+   Put_Line (Name (Blue));
+
+   --  only the selected dependent expression is evaluated
+   X := (if X > 5 then Counted (10) else Counted (20));
+   Print (X);
+   Print (Calls);
+
+   Put_Line (if Is_Prime (13) then "13 is prime" else "13 is not prime");
+   Put_Line (if Is_Prime (15) then "15 is prime" else "15 is not prime");
+   if Has_Square (1, 10, 49) and not Has_Square (1, 10, 50) then
+      Put_Line ("49 is a square");
+   end if;
+
+   X := (case X - 9 is when 0 => 100, when 1 => 200, when others => 300);
+   Print (X);
+
+   Print ((declare
+             Y : constant Integer := X / 2;
+           begin
+             Y + 1));
+
+   if (if X > 0 then X < 1000) then
+      Put_Line ("implication");
+   end if;
+end Main;
@@ -0,0 +1,56 @@
+-- Exception handlers are only implemented for C++: annabella --target c++
+
+with Ada.Text_IO; use Ada.Text_IO;
+
+procedure Main is
+   Parse_Error : exception;
+
+   function Parse (S : String) return Integer is
+   begin
+      if S = "" then
+         raise Parse_Error with "empty string";
+      end if;
+      return S'Length;
+   end Parse;
+
+   function Parse_Or_Zero (S : String) return Integer is
+   begin
+      return Parse (S);
+   exception
+      when Parse_Error =>
+         return 0;
+   end Parse_Or_Zero;
+
+   procedure Log_And_Reraise is
+   begin
+      raise Constraint_Error;
+   exception
+      when Program_Error | Constraint_Error =>
+         Put_Line ("logged");
+         raise;
+   end Log_And_Reraise;
+
+   X : Integer := Integer'Last;
+begin
+   Put_Line (Integer'Image (Parse_Or_Zero ("abc")));
+   Put_Line (Integer'Image (Parse_Or_Zero ("")));
+
+   begin
+      X := X + 1;
+   exception
+      when Parse_Error =>
+         Put_Line ("not this one");
+      when others =>
+         Put_Line ("overflow");
+   end;
+
+   begin
+      Log_And_Reraise;
+   exception
+      when Constraint_Error =>
+         Put_Line ("handled again");
+   end;
+
+   -- not handled, ends the program
+   Put_Line (Integer'Image (Parse ("")));
+end Main;
@@ -0,0 +1,37 @@
+with Ada.Text_IO; use Ada.Text_IO;
+with Ada.Unchecked_Conversion;
+with Interfaces; use Interfaces;
+with Interfaces.C;
+with System;
+with System.Storage_Elements; use System.Storage_Elements;
+
+procedure Main is
+   Bits : constant := System.Word_Size;
+   Bytes : constant := Bits / System.Storage_Unit;
+
+   type Color is (Red, Green, Blue);
+
+   function To_Unsigned is new Ada.Unchecked_Conversion (Color, Unsigned_32);
+
+   Answer : constant Interfaces.C.int := 42;
+   Count : Integer := -Bytes + 2 * Bytes;
+   Address : System.Address := To_Address (4096);
+   Byte : Unsigned_8 := 255;
+begin
+   Put ("Word size:");
+   Put_Line (Integer'Image (Bits));
+   Put ("Bytes per word:");
+   Put_Line (Integer'Image (Count));
+   Put ("Address:");
+   Put_Line (Integer_Address'Image (To_Integer (Address)));
+   Put ("int:");
+   Put_Line (Interfaces.C.int'Image (Answer));
+   Put ("Blue:");
+   Put_Line (Unsigned_32'Image (To_Unsigned (Blue)));
+   Byte := Byte + 1;
+   Put ("Wrapped:");
+   Put_Line (Unsigned_8'Image (Byte));
+   if System.Max_Int > 2 ** 62 then
+      Put_Line ("64 bit Max_Int");
+   end if;
+end Main;
@@ -0,0 +1,59 @@
+with Interfaces.C; use Interfaces.C;
+
+package Geometry is
+   type Point is record
+      X : int;
+      Y : int;
+   end record;
+   pragma Convention (C, Point);
+
+   function Manhattan (P : Point) return int;
+   pragma Export (C, Manhattan, "geometry_manhattan");
+
+   Calls : int := 0;
+   pragma Export (C, Calls, "geometry_calls");
+end Geometry;
+
+with Interfaces.C; use Interfaces.C;
+
+package body Geometry is
+   function Manhattan (P : Point) return int is
+      function C_Abs (Value : int) return int;
+      pragma Import (C, C_Abs, "abs");
+   begin
+      Calls := Calls + 1;
+      return C_Abs (P.X) + C_Abs (P.Y);
+   end Manhattan;
+end Geometry;
+
+with Interfaces.C; use Interfaces.C;
+with Geometry; use Geometry;
+
+package Mirror is
+   function Distance (P : Point) return int;
+   pragma Import
+     (Convention => C, Entity => Distance, External_Name => "geometry_manhattan");
+
+   Calls : int;
+   pragma Import (C, Calls);
+   pragma Interface_Name (Calls, "geometry_calls");
+end Mirror;
+
+with Ada.Text_IO; use Ada.Text_IO;
+with Interfaces.C; use Interfaces.C;
+with Geometry;
+with Mirror;
+
+procedure Main is
+   P : Geometry.Point;
+begin
+   P.X := 3;
+   P.Y := -4;
+   Put ("Manhattan:");
+   Put_Line (int'Image (Geometry.Manhattan (P)));
+   P.X := -10;
+   Put ("Distance:");
+   Put_Line (int'Image (Mirror.Distance (P)));
+   Put ("Calls:");
+   Put_Line (int'Image (Mirror.Calls));
+end Main;
@@ -0,0 +1,64 @@
+with Ada.Text_IO; use Ada.Text_IO;
+
+procedure Main is
+   type Day is (Mon, Tue, Wed, Thu, Fri, Sat, Sun);
+   subtype Weekday is Day range Mon .. Fri;
+
+   type Color is (Red, Green, Blue);
+   for Color use (Red => 1, Green => 4, Blue => 16);
+
+   type Small is range 1 .. 5;
+   type Byte is mod 256;
+
+   S : String := "abc";
+   N : Integer := 0;
+begin
+   for D in Day loop
+      Put (Day'Image (D) & " ");
+   end loop;
+   New_Line;
+
+   for D in reverse Weekday loop
+      Put (Day'Image (D) & " ");
+   end loop;
+   New_Line;
+
+   -- the codes 1, 4, 16 are stepped through with Color'Succ
+   for C in Color'Range loop
+      Put (Color'Image (C) & " ");
+   end loop;
+   New_Line;
+
+   for X in Small range 2 .. 4 loop
+      Put (Small'Image (X));
+   end loop;
+   New_Line;
+
+   for X in Natural range 0 .. 3 loop
+      N := N + X;
+   end loop;
+   Put_Line (Integer'Image (N));
+
+   for I in S'Range loop
+      Put (S (I));
+   end loop;
+   New_Line;
+
+   for C of S loop
+      C := Character'Succ (C);
+   end loop;
+   Put_Line (S);
+
+   for C of reverse S loop
+      Put (C);
+   end loop;
+   New_Line;
+
+   Put_Line (Color'Image (Color'Succ (Red)) & " " & Color'Image (Color'Pred (Blue)));
+   Put_Line (Day'Image (Day'First) & " " & Day'Image (Weekday'Last));
+   Put_Line (Byte'Image (Byte'Succ (255)) & Small'Image (Small'Pred (3)));
+
+   if (for some D in Weekday => D = Fri) then
+      Put_Line ("Fri is a weekday");
+   end if;
+end Main;
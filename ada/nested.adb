@@ -0,0 +1,70 @@
+with Ada.Text_IO; use Ada.Text_IO;
+
+procedure Main is
+   Total : Integer := 0;
+
+   procedure Count (Limit : Integer; Result : out Integer) is
+      Calls : Integer := 0;
+
+      --  reads the parameter and writes the local of the enclosing procedure
+      procedure Step (N : Integer) is
+      begin
+         Calls := Calls + 1;
+         if N < Limit then
+            Step (N + 1);
+         end if;
+      end Step;
+
+      --  two levels up, and a call of a sibling
+      procedure Run is
+         procedure Add is
+         begin
+            Total := Total + Limit;
+            Result := Calls;
+         end Add;
+      begin
+         Step (1);
+         Add;
+      end Run;
+   begin
+      Run;
+   end Count;
+
+   function Factorial (N : Integer) return Integer is
+      Result : Integer := 1;
+
+      procedure Multiply (K : Integer) is
+      begin
+         if K > 1 then
+            Result := Result * K;
+            Multiply (K - 1);
+         end if;
+      end Multiply;
+   begin
+      Multiply (N);
+      return Result;
+   end Factorial;
+
+   procedure Update (Value : in out Integer) is
+      procedure Double is
+      begin
+         Value := Value * 2;
+      end Double;
+   begin
+      for I in 1 .. 3 loop
+         Double;
+      end loop;
+   end Update;
+
+   Calls : Integer;
+   Value : Integer := 5;
+begin
+   Count (4, Calls);
+   Put_Line ("Calls:" & Integer'Image (Calls));
+   Count (2, Calls);
+   Put_Line ("Calls:" & Integer'Image (Calls));
+   Put_Line ("Total:" & Integer'Image (Total));
+   Put_Line ("Factorial:" & Integer'Image (Factorial (6)));
+   Update (Value);
+   Put_Line ("Value:" & Integer'Image (Value));
+end Main;
@@ -0,0 +1,35 @@
+with Ada.Text_IO; use Ada.Text_IO;
+with System;
+with System.OS_Lib; use System.OS_Lib;
+
+procedure Main is
+   Name : String := "os_lib.tmp";
+   Text : String := "written by System.OS_Lib";
+   Buffer : String := "................................";
+   FD : File_Descriptor := Create_File (Name, Binary);
+   Count : Integer := Write (FD, Text'Address, Text'Length);
+   Length : Long_Integer := 0;
+   Ok : Boolean := False;
+   Shell : String := Locate_Exec_On_Path ("sh");
+begin
+   Close (FD);
+
+   FD := Open_Read (Name, Binary);
+   Length := File_Length (FD);
+   Count := Read (FD, Buffer'Address, Buffer'Length);
+   Close (FD, Ok);
+   Put_Line (Buffer (1 .. Count));
+   Put_Line ("length:" & Long_Integer'Image (Length));
+
+   if Ok and Is_Regular_File (Name) and not Is_Regular_File (".") then
+      Put_Line ("regular file");
+   end if;
+   Delete_File (Name, Ok);
+   if Ok and not Is_Regular_File (Name) then
+      Put_Line ("deleted");
+   end if;
+
+   if Shell'Length > 0 and Locate_Exec_On_Path ("annabella-none")'Length = 0 then
+      Put_Line ("found sh");
+   end if;
+end Main;
@@ -0,0 +1,40 @@
+pragma Ada_2012;
+pragma Style_Checks (Off);
+
+package Shapes is
+   pragma Pure;
+
+   type Size is range 0 .. 100;
+
+   type Box is record
+      Width : Size;
+      pragma Warnings (Off);
+      Height : Size;
+   end record;
+
+   function Area (B : Box) return Integer;
+   pragma Inline (Area);
+end Shapes;
+
+package body Shapes is
+   function Area (B : Box) return Integer is
+   begin
+      return Integer (B.Width) * Integer (B.Height);
+   end Area;
+end Shapes;
+
+with Ada.Text_IO; use Ada.Text_IO;
+with Shapes; use Shapes;
+
+procedure Main is
+   pragma Suppress (Range_Check);
+   pragma Frobnicate (Main);
+
+   B : Box;
+   pragma Unreferenced (B);
+begin
+   B.Width := 6;
+   B.Height := 7;
+   pragma Debug_Policy (Check);
+   Put_Line (Integer'Image (Area (B)));
+end Main;
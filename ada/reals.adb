@@ -0,0 +1,23 @@
+with Ada.Text_IO; use Ada.Text_IO;
+
+procedure Main is
+   type Percent is delta 0.01 range 0.0 .. 100.0;
+   subtype Unit is Float range 0.0 .. 1.0;
+
+   X : Float := 1.5;
+   Y : Long_Float := -2.25E-3;
+   D : Duration := 0.1;
+   P : Percent := 12.5;
+   U : Unit := 0.5;
+   W : Wide_Character := 'A';
+begin
+   X := X * 2.0 + 1.0;
+   Put_Line (Float'Image (X));
+   Put_Line (Long_Float'Image (Y));
+   Put_Line (Duration'Image (D + 1.5));
+   Put_Line (Percent'Image (P));
+   Put_Line (Integer'Image (Integer (X)));
+   Put_Line (Integer'Image (Integer (-2.5)));
+   Put_Line (Wide_Character'Image (Wide_Character'Succ (W)));
+   Put_Line (Float'Image (U + 0.25));
+end Main;
@@ -0,0 +1,34 @@
+with Ada.Text_IO; use Ada.Text_IO;
+
+procedure Main is
+   Calls : Integer := 0;
+
+   function Is_Odd (N : Integer) return Boolean;
+
+   --  calls Is_Odd before its body
+   function Is_Even (N : Integer) return Boolean is
+   begin
+      Calls := Calls + 1;
+      if N = 0 then
+         return True;
+      end if;
+      return Is_Odd (N - 1);
+   end Is_Even;
+
+   function Is_Odd (N : Integer) return Boolean is
+   begin
+      Calls := Calls + 1;
+      if N = 0 then
+         return False;
+      end if;
+      return Is_Even (N - 1);
+   end Is_Odd;
+begin
+   if Is_Even (10) then
+      Put_Line ("10 is even");
+   end if;
+   if Is_Odd (7) then
+      Put_Line ("7 is odd");
+   end if;
+   Put_Line (Integer'Image (Calls));
+end Main;
@@ -0,0 +1,49 @@
+package Nodes is
+   type Node_Kind is (N_Empty, N_Identifier, N_Literal, N_Call);
+   for Node_Kind use (N_Empty => 0, N_Identifier => 2, N_Literal => 4, N_Call => 8);
+   for Node_Kind'Size use 8;
+
+   type Flag is range 0 .. 1;
+
+   type Count is range 0 .. 1000;
+   for Count'Size use 16;
+
+   type Node is record
+      Kind : Node_Kind;
+      Is_Static : Flag;
+      Has_Errors : Flag;
+      Uses : Count;
+   end record;
+
+   for Node use record
+      Kind at 0 range 0 .. 7;
+      Is_Static at 1 range 0 .. 0;
+      Has_Errors at 1 range 1 .. 1;
+      Uses at 2 range 0 .. 15;
+   end record;
+   for Node'Size use 32;
+   for Node'Alignment use 4;
+end Nodes;
+
+with Ada.Text_IO; use Ada.Text_IO;
+with Nodes; use Nodes;
+
+procedure Main is
+   N : Node;
+begin
+   N.Kind := N_Literal;
+   N.Is_Static := 1;
+   N.Has_Errors := 0;
+   N.Uses := 1000;
+   Put_Line (Node_Kind'Image (N.Kind));
+   if N.Kind > N_Identifier then
+      Put_Line ("after identifier");
+   end if;
+   Put_Line (Integer'Image (Node_Kind'Size));
+   Put_Line (Integer'Image (Flag'Size));
+   Put_Line (Integer'Image (Count'Size));
+   Put_Line (Integer'Image (Node'Size));
+   Print (N);
+   N := (Uses => 7, Kind => N_Call, Has_Errors => 1, Is_Static => 0);
+   Print (N);
+end Main;
@@ -0,0 +1,51 @@
+with Ada.Text_IO; use Ada.Text_IO;
+
+procedure Main is
+   procedure Check (X : Integer) is
+   begin
+      if X < 0 then
+         Put_Line ("negative");
+         return;
+      end if;
+      Put_Line ("not negative");
+   end Check;
+
+   function Square (X : Integer) return Integer is
+   begin
+      return R : Integer := X do
+         R := R * X;
+      end return;
+   end Square;
+
+   function Greeting (Name : String) return String is
+   begin
+      return S : String := "Hello, " & Name;
+   end Greeting;
+
+   function Clamp (X : Integer) return Natural is
+   begin
+      return R : Integer := X do
+         if X < 0 then
+            R := 0;
+            return;
+         end if;
+         R := R + 1;
+      end return;
+   end Clamp;
+
+   function Sign (X : Integer) return Integer is
+   begin
+      if X > 0 then
+         return 1;
+      elsif X < 0 then
+         return -1;
+      end if;
+   end Sign;
+begin
+   Check (-1);
+   Check (1);
+   Put_Line (Integer'Image (Square (7)));
+   Put_Line (Greeting ("world"));
+   Put_Line (Integer'Image (Clamp (-5)) & Integer'Image (Clamp (5)));
+   Put_Line (Integer'Image (Sign (-3)));
+end Main;
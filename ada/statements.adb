@@ -0,0 +1,45 @@
+with Ada.Text_IO; use Ada.Text_IO;
+
+procedure Main is
+   Parse_Error : exception;
+
+   function Digit (C : Character) return Integer is
+      Value : Integer := 0;
+   begin
+      for D in Character range '0' .. '9' loop
+         if D = C then
+            return Value;
+         end if;
+         Value := Value + 1;
+      end loop;
+      raise Parse_Error with "not a digit: " & C;
+   end Digit;
+
+   procedure Show (C : Character) is
+   begin
+      case C is
+         when '0' .. '9' =>
+            Put_Line ("digit " & C);
+         when others =>
+            null;
+      end case;
+   end Show;
+
+   S : String := "2024";
+   N : Integer := 0;
+begin
+   for C of S loop
+      N := N * 10 + Digit (C);
+   end loop;
+   Put_Line (Integer'Image (N));
+
+   Show ('7');
+   Show ('x');
+
+   delay 0.01;
+
+   if N < 0 then
+      raise Constraint_Error;
+   end if;
+   Put_Line (Integer'Image (Digit ('x')));
+end Main;
@@ -0,0 +1,35 @@
+with Ada.Text_IO; use Ada.Text_IO;
+
+procedure Main is
+   function Greeting (Name : String) return String is
+   begin
+      return "Hello, " & Name & '!';
+   end Greeting;
+
+   function Reverse_Of (S : String) return String is
+      Result : String := S;
+   begin
+      for I in S'First .. S'Last loop
+         Result (S'Last - I + S'First) := S (I);
+      end loop;
+      return Result;
+   end Reverse_Of;
+
+   Word : String := "annabella";
+   Part : String := Word (3 .. 6);
+begin
+   Put_Line (Greeting ("World"));
+   Put_Line ("Length:" & Integer'Image (Word'Length));
+   Put_Line ("Slice: " & Part & " from" & Integer'Image (Part'First)
+             & " to" & Integer'Image (Part'Last));
+   Put_Line ("Reversed: " & Reverse_Of (Word));
+
+   Word (1) := 'A';
+   Word (2 .. 3) := "NN";
+   Put_Line (Word);
+
+   if "abc" < "abd" and "abc" = "abc" and "ab" < "abc" and "b" > "abc" then
+      Put_Line ("Comparisons ok");
+   end if;
+   Put_Line ('x' & 'y');
+end Main;
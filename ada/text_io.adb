@@ -0,0 +1,30 @@
+with Ada.Text_IO; use Ada.Text_IO;
+with Ada.Integer_Text_IO;
+
+procedure Main is
+   type Color is (Red, Green, Blue);
+
+   package Color_IO is new Enumeration_IO (Color);
+
+   F : File_Type;
+begin
+   Put_Line ("Hello, World!");
+
+   Put ("The answer is ");
+   Ada.Integer_Text_IO.Put (42);
+   New_Line;
+
+   Put ("Favorite color: ");
+   Color_IO.Put (Green);
+   New_Line;
+
+   Put_Line (Standard_Error, "This goes to stderr");
+
+   Create (F, Out_File, "text_io.txt");
+   Put_Line (F, "Written to a file");
+   Close (F);
+
+   Open (F, In_File, "text_io.txt");
+   Put_Line (Get_Line (F));
+   Close (F);
+end Main;
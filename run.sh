@@ -2,13 +2,5 @@
 
 cargo build
 
-for file in ~/gcc-3.1.0/gcc/ada/*.ad?; do
-  case "$file" in
-    *.adt) continue ;;
-  esac
-
-  target/debug/annabella-rs "$file"
-  # break
-done
-
-echo done
+# transpile every file, going on after failures, and rank the errors
+target/debug/annabella survey ~/gcc-3.1.0/gcc/ada --json survey.json
@@ -0,0 +1,28 @@
+/* C implementation of Ada.Command_Line, see a-comlin.ads */
+
+ssize_t ANNABELLA_FUNCTION(ada::command_line, ada__command_line,
+                           argument_count___natural)(void) {
+  return annabella_argc - 1;
+}
+
+annabella_type__string
+ANNABELLA_FUNCTION(ada::command_line, ada__command_line,
+                   argument__positive___string)(
+    ssize_t number) {
+  if (number < 1 || number >= annabella_argc) {
+    throw_Constraint_Error();
+  }
+  return annabella_string_from_c(annabella_argv[number]);
+}
+
+annabella_type__string
+ANNABELLA_FUNCTION(ada::command_line, ada__command_line,
+                   command_name___string)(void) {
+  return annabella_string_from_c(annabella_argc > 0 ? annabella_argv[0] : "");
+}
+
+void ANNABELLA_FUNCTION(ada::command_line, ada__command_line,
+                        set_exit_status__exit_status_)(
+    ssize_t code) {
+  annabella_exit_status = (int)code;
+}
@@ -0,0 +1,48 @@
+/* C implementation of Ada.Environment_Variables, see a-envvar.ads */
+
+annabella_type__string
+ANNABELLA_FUNCTION(ada::environment_variables, ada__environment_variables,
+                   value__string___string)(
+    annabella_type__string name) {
+  const char *value = getenv(annabella_string_to_c(name));
+  if (!value) {
+    throw_Constraint_Error();
+  }
+  return annabella_string_copy(annabella_string_from_c(value));
+}
+
+annabella_type__string
+ANNABELLA_FUNCTION(ada::environment_variables, ada__environment_variables,
+                   value__string__string___string)(
+    annabella_type__string name, annabella_type__string fallback) {
+  const char *value = getenv(annabella_string_to_c(name));
+  if (!value) {
+    return fallback;
+  }
+  return annabella_string_copy(annabella_string_from_c(value));
+}
+
+int ANNABELLA_FUNCTION(ada::environment_variables, ada__environment_variables,
+                       exists__string___boolean)(
+    annabella_type__string name) {
+  return getenv(annabella_string_to_c(name)) != NULL;
+}
+
+void ANNABELLA_FUNCTION(ada::environment_variables, ada__environment_variables,
+                        set__string__string_)(
+    annabella_type__string name, annabella_type__string value) {
+  if (setenv(annabella_string_to_c(name), annabella_string_to_c(value), 1)) {
+    throw_Constraint_Error();
+  }
+}
+
+void ANNABELLA_FUNCTION(ada::environment_variables, ada__environment_variables,
+                        clear__string_)(
+    annabella_type__string name) {
+  unsetenv(annabella_string_to_c(name));
+}
+
+void ANNABELLA_FUNCTION(ada::environment_variables, ada__environment_variables,
+                        clear_)(void) {
+  clearenv();
+}
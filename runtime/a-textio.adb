@@ -0,0 +1,95 @@
+package body Ada.Text_IO is
+
+   function Standard_Input return File_Type is
+   begin
+      return 1;
+   end Standard_Input;
+
+   function Standard_Output return File_Type is
+   begin
+      return 2;
+   end Standard_Output;
+
+   function Standard_Error return File_Type is
+   begin
+      return 3;
+   end Standard_Error;
+
+   procedure New_Line is
+   begin
+      New_Line (Standard_Output);
+   end New_Line;
+
+   function End_Of_File return Boolean is
+   begin
+      return End_Of_File (Standard_Input);
+   end End_Of_File;
+
+   procedure Put (Item : Character) is
+   begin
+      Put (Standard_Output, Item);
+   end Put;
+
+   procedure Put (Item : String) is
+   begin
+      Put (Standard_Output, Item);
+   end Put;
+
+   procedure Put_Line (Item : String) is
+   begin
+      Put_Line (Standard_Output, Item);
+   end Put_Line;
+
+   procedure Put_Line (File : File_Type; Item : String) is
+   begin
+      Put (File, Item);
+      New_Line (File);
+   end Put_Line;
+
+   function Get_Line return String is
+   begin
+      return Get_Line (Standard_Input);
+   end Get_Line;
+
+   package body Integer_IO is
+
+      procedure Get (Item : out Num) is
+      begin
+         Get (Standard_Input, Item);
+      end Get;
+
+      procedure Get (File : File_Type; Item : out Num) is
+         Value : Integer;
+      begin
+         Get_Integer (File, Value);
+         Item := Num (Value);
+      end Get;
+
+      procedure Put (Item : Num) is
+      begin
+         Put (Standard_Output, Item);
+      end Put;
+
+      procedure Put (File : File_Type; Item : Num) is
+      begin
+         Put_Integer
+           (File, Integer (Item), Integer (Num'First), Integer (Num'Last));
+      end Put;
+
+   end Integer_IO;
+
+   package body Enumeration_IO is
+
+      procedure Put (Item : Enum) is
+      begin
+         Put (Standard_Output, Item);
+      end Put;
+
+      procedure Put (File : File_Type; Item : Enum) is
+      begin
+         Put (File, Enum'Image (Item));
+      end Put;
+
+   end Enumeration_IO;
+
+end Ada.Text_IO;
@@ -0,0 +1,139 @@
+/* C implementation of Ada.Text_IO, see a-textio.ads */
+
+#include <string.h>
+
+static FILE *annabella_text_io_files[256];
+
+static FILE *annabella_text_io_file(ssize_t file) {
+  switch (file) {
+  case 1:
+    return stdin;
+  case 2:
+    return stdout;
+  case 3:
+    return stderr;
+  }
+  if (file <= 0 || file >= 256 || !annabella_text_io_files[file]) {
+    annabella_raise("Ada.Text_IO.Status_Error");
+  }
+  return annabella_text_io_files[file];
+}
+
+static void annabella_text_io_open(ssize_t *file, int mode, const char *name) {
+  static const char *const modes[] = {"r", "w", "a"};
+  if (*file != 0) {
+    annabella_raise("Ada.Text_IO.Status_Error");
+  }
+  for (ssize_t i = 4; i < 256; i++) {
+    if (!annabella_text_io_files[i]) {
+      FILE *f = fopen(name, modes[mode]);
+      if (!f) {
+        annabella_raise("Ada.Text_IO.Name_Error");
+      }
+      annabella_text_io_files[i] = f;
+      *file = i;
+      return;
+    }
+  }
+  annabella_raise("Ada.Text_IO.Use_Error");
+}
+
+void ANNABELLA_FUNCTION(ada::text_io, ada__text_io,
+                        create__file_type__file_mode__string_)(
+    ssize_t *file, int mode, annabella_type__string name) {
+  annabella_text_io_open(file, mode == 0 ? 1 : mode,
+                         annabella_string_to_c(name));
+}
+
+void ANNABELLA_FUNCTION(ada::text_io, ada__text_io,
+                        open__file_type__file_mode__string_)(
+    ssize_t *file, int mode, annabella_type__string name) {
+  annabella_text_io_open(file, mode, annabella_string_to_c(name));
+}
+
+void ANNABELLA_FUNCTION(ada::text_io, ada__text_io,
+                        close__file_type_)(ssize_t *file) {
+  FILE *f = annabella_text_io_file(*file);
+  /* the standard files stay open, only the file object is closed */
+  if (*file > 3) {
+    fclose(f);
+    annabella_text_io_files[*file] = NULL;
+  }
+  *file = 0;
+}
+
+int ANNABELLA_FUNCTION(ada::text_io, ada__text_io,
+                       is_open__file_type___boolean)(
+    ssize_t file) {
+  return (file >= 1 && file <= 3) ||
+         (file > 3 && file < 256 && annabella_text_io_files[file]);
+}
+
+void ANNABELLA_FUNCTION(ada::text_io, ada__text_io,
+                        new_line__file_type_)(ssize_t file) {
+  fputc('\n', annabella_text_io_file(file));
+}
+
+int ANNABELLA_FUNCTION(ada::text_io, ada__text_io,
+                       end_of_file__file_type___boolean)(
+    ssize_t file) {
+  FILE *f = annabella_text_io_file(file);
+  int c = fgetc(f);
+  if (c == EOF) {
+    return 1;
+  }
+  ungetc(c, f);
+  return 0;
+}
+
+void ANNABELLA_FUNCTION(ada::text_io, ada__text_io, put__file_type__character_)(
+    ssize_t file, unsigned char item) {
+  fputc(item, annabella_text_io_file(file));
+}
+
+void ANNABELLA_FUNCTION(ada::text_io, ada__text_io, put__file_type__string_)(
+    ssize_t file, annabella_type__string item) {
+  fwrite(item.data, 1, annabella_string_length(item),
+         annabella_text_io_file(file));
+}
+
+annabella_type__string
+ANNABELLA_FUNCTION(ada::text_io, ada__text_io,
+                   get_line__file_type___string)(ssize_t file) {
+  char *line = NULL;
+  size_t size = 0;
+  ssize_t len = getline(&line, &size, annabella_text_io_file(file));
+  if (len < 0) {
+    annabella_raise("Ada.Text_IO.End_Error");
+  }
+  if (len > 0 && line[len - 1] == '\n') {
+    len--;
+  }
+  annabella_type__string result = annabella_string_allocate(1, len);
+  memcpy(result.data, line, len);
+  free(line);
+  return result;
+}
+
+/* Length of the image of an integer, with a space for the sign of positive values */
+static int annabella_text_io_width(ssize_t item) {
+  return snprintf(NULL, 0, "% ld", item);
+}
+
+void ANNABELLA_FUNCTION(ada::text_io, ada__text_io,
+                        put_integer__file_type__integer__integer__integer_)(
+    ssize_t file, ssize_t item, ssize_t first, ssize_t last) {
+  int width = annabella_text_io_width(first);
+  if (annabella_text_io_width(last) > width) {
+    width = annabella_text_io_width(last);
+  }
+  fprintf(annabella_text_io_file(file), "%*ld", width, item);
+}
+
+void ANNABELLA_FUNCTION(ada::text_io, ada__text_io,
+                        get_integer__file_type__integer_)(
+    ssize_t file, ssize_t *item) {
+  if (fscanf(annabella_text_io_file(file), "%ld", item) != 1) {
+    annabella_raise("Ada.Text_IO.Data_Error");
+  }
+}
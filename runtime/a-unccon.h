@@ -0,0 +1,12 @@
+/* C implementation of Ada.Unchecked_Conversion, see a-unccon.ads. The generic
+ * is a macro, expanded for each instance. */
+
+#include <string.h>
+
+#define annabella_generic__ada__unchecked_conversion(name, Source, Target)   \
+  Target name(Source const s) {                                              \
+    Target t;                                                                \
+    memset(&t, 0, sizeof(t));                                                \
+    memcpy(&t, &s, sizeof(s) < sizeof(t) ? sizeof(s) : sizeof(t));          \
+    return t;                                                                \
+  }
@@ -0,0 +1,8 @@
+/* Raising of exceptions, an exception ends the program since handlers are
+ * not supported in C. */
+
+void annabella_raise(const char *name) { annabella_raise_message(name, NULL); }
+
+void annabella_raise_message(const char *name, const char *message) {
+  exit(annabella_unhandled_exception(name, message));
+}
@@ -0,0 +1,20 @@
+/* Ada exceptions are C++ exceptions, caught by the handlers and main for the
+ * unhandled ones. */
+
+#include <string.h>
+
+[[noreturn]] void annabella_raise(const char *name) {
+  annabella_raise_message(name, nullptr);
+}
+
+[[noreturn]] void annabella_raise_message(const char *name,
+                                          const char *message) {
+  throw annabella_exception{name, message};
+}
+
+[[noreturn]] void annabella_reraise(void) { throw; }
+
+bool annabella_exception_is(const annabella_exception &occurrence,
+                            const char *name) {
+  return strcmp(occurrence.name, name) == 0;
+}
@@ -0,0 +1,34 @@
+/* Raising of Ada exceptions, see exceptions.c and exceptions.cpp */
+
+#ifdef __cplusplus
+struct annabella_exception {
+  const char *name;
+  const char *message;
+};
+
+[[noreturn]]
+#else
+_Noreturn
+#endif
+void annabella_raise(const char *name);
+
+/* Raise the exception `name` with a message, like `raise E with Message`. */
+#ifdef __cplusplus
+[[noreturn]]
+#else
+_Noreturn
+#endif
+void annabella_raise_message(const char *name, const char *message);
+
+/* Report an exception not handled by the program, returns the exit status.
+ * `message` is NULL for an exception raised without a message. */
+int annabella_unhandled_exception(const char *name, const char *message);
+
+#ifdef __cplusplus
+/* Raise the exception handled by the enclosing handler again, like `raise;`. */
+[[noreturn]] void annabella_reraise(void);
+
+/* Is the exception caught by a handler the exception `name`? */
+bool annabella_exception_is(const annabella_exception &occurrence,
+                            const char *name);
+#endif
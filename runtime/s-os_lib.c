@@ -0,0 +1,116 @@
+/* C implementation of System.OS_Lib, see s-os_lib.ads */
+
+#include <fcntl.h>
+#include <limits.h>
+#include <string.h>
+#include <sys/stat.h>
+#include <unistd.h>
+
+ssize_t
+ANNABELLA_FUNCTION(system::os_lib, system__os_lib,
+                   open_read__string__mode___file_descriptor)(
+    annabella_type__string name, int fmode) {
+  (void)fmode;
+  return open(annabella_string_to_c(name), O_RDONLY);
+}
+
+ssize_t
+ANNABELLA_FUNCTION(system::os_lib, system__os_lib,
+                   create_file__string__mode___file_descriptor)(
+    annabella_type__string name, int fmode) {
+  (void)fmode;
+  return open(annabella_string_to_c(name), O_WRONLY | O_CREAT | O_TRUNC, 0666);
+}
+
+ssize_t
+ANNABELLA_FUNCTION(system::os_lib, system__os_lib,
+                   read__file_descriptor__address__integer___integer)(
+    ssize_t fd, ssize_t a, ssize_t n) {
+  return read((int)fd, (void *)a, (size_t)n);
+}
+
+ssize_t
+ANNABELLA_FUNCTION(system::os_lib, system__os_lib,
+                   write__file_descriptor__address__integer___integer)(
+    ssize_t fd, ssize_t a, ssize_t n) {
+  return write((int)fd, (const void *)a, (size_t)n);
+}
+
+void ANNABELLA_FUNCTION(system::os_lib, system__os_lib,
+                        close__file_descriptor_)(ssize_t fd) {
+  close((int)fd);
+}
+
+void ANNABELLA_FUNCTION(system::os_lib, system__os_lib,
+                        close__file_descriptor__boolean_)(
+    ssize_t fd, int *status) {
+  *status = close((int)fd) == 0;
+}
+
+ssize_t
+ANNABELLA_FUNCTION(system::os_lib, system__os_lib,
+                   file_length__file_descriptor___long_integer)(
+    ssize_t fd) {
+  struct stat st;
+  if (fstat((int)fd, &st)) {
+    return -1;
+  }
+  return st.st_size;
+}
+
+void ANNABELLA_FUNCTION(system::os_lib, system__os_lib,
+                        delete_file__string__boolean_)(
+    annabella_type__string name, int *success) {
+  *success = unlink(annabella_string_to_c(name)) == 0;
+}
+
+static int annabella_os_lib_is_regular_file(const char *name) {
+  struct stat st;
+  return stat(name, &st) == 0 && S_ISREG(st.st_mode);
+}
+
+int ANNABELLA_FUNCTION(system::os_lib, system__os_lib,
+                       is_regular_file__string___boolean)(
+    annabella_type__string name) {
+  return annabella_os_lib_is_regular_file(annabella_string_to_c(name));
+}
+
+/* Absolute path of an executable regular file, or the empty string. */
+static annabella_type__string annabella_os_lib_executable(const char *path) {
+  char full[PATH_MAX];
+  if (!annabella_os_lib_is_regular_file(path) || access(path, X_OK) ||
+      !realpath(path, full)) {
+    return annabella_string_allocate(1, 0);
+  }
+  return annabella_string_copy(annabella_string_from_c(full));
+}
+
+annabella_type__string
+ANNABELLA_FUNCTION(system::os_lib, system__os_lib,
+                   locate_exec_on_path__string___string)(
+    annabella_type__string exec_name) {
+  const char *name = annabella_string_to_c(exec_name);
+  const char *path = getenv("PATH");
+  if (strchr(name, '/') || !path) {
+    return annabella_os_lib_executable(name);
+  }
+  /* like the shell, an empty entry is the current directory */
+  for (;;) {
+    const char *end = strchr(path, ':');
+    size_t length = end ? (size_t)(end - path) : strlen(path);
+    char *candidate =
+        annabella_string_allocate(1, length + 1 + strlen(name)).data;
+    if (length == 0) {
+      strcpy(candidate, name);
+    } else {
+      memcpy(candidate, path, length);
+      candidate[length] = '/';
+      strcpy(candidate + length + 1, name);
+    }
+    annabella_type__string result = annabella_os_lib_executable(candidate);
+    if (annabella_string_length(result) > 0 || !end) {
+      return result;
+    }
+    path = end + 1;
+  }
+}
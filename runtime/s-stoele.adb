@@ -0,0 +1,13 @@
+package body System.Storage_Elements is
+
+   function To_Address (Value : Integer_Address) return Address is
+   begin
+      return Address (Value);
+   end To_Address;
+
+   function To_Integer (Value : Address) return Integer_Address is
+   begin
+      return Integer_Address (Value);
+   end To_Integer;
+
+end System.Storage_Elements;
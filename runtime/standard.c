@@ -0,0 +1,199 @@
+/* C implementation of the predefined operations of package Standard, see
+ * standard.h */
+
+#include <string.h>
+
+void throw_Constraint_Error(void) { annabella_raise("Constraint_Error"); }
+void throw_Program_Error(void) { annabella_raise("Program_Error"); }
+
+/* Like GNAT, see exceptions.h */
+int annabella_unhandled_exception(const char *name, const char *message) {
+  fprintf(stderr, "raised %s : %s\n", name,
+          message ? message : "unhandled exception");
+  return 1;
+}
+
+/* Arguments and exit status of the C `main` function, for Ada.Command_Line */
+int annabella_argc;
+char **annabella_argv;
+int annabella_exit_status;
+
+/* Secondary stack, see standard.h */
+static annabella_ss_chunk *annabella_ss_first;
+static annabella_ss_mark_t annabella_ss_top;
+
+void *annabella_ss_allocate(size_t size) {
+  size = (size + 15) & ~(size_t)15;
+  annabella_ss_chunk **next = &annabella_ss_first;
+  if (annabella_ss_top.chunk) {
+    if (annabella_ss_top.used + size <= annabella_ss_top.chunk->size) {
+      void *data = annabella_ss_top.chunk->data + annabella_ss_top.used;
+      annabella_ss_top.used += size;
+      return data;
+    }
+    next = &annabella_ss_top.chunk->next;
+  }
+  /* reuse the following chunk if it is large enough, else insert a new one */
+  if (!*next || (*next)->size < size) {
+    size_t chunk_size = size < 65536 ? 65536 : size;
+    annabella_ss_chunk *chunk =
+        (annabella_ss_chunk *)malloc(sizeof(annabella_ss_chunk) + chunk_size);
+    if (!chunk) {
+      annabella_raise("Storage_Error");
+    }
+    chunk->next = *next;
+    chunk->size = chunk_size;
+    *next = chunk;
+  }
+  annabella_ss_top.chunk = *next;
+  annabella_ss_top.used = size;
+  return annabella_ss_top.chunk->data;
+}
+
+annabella_ss_mark_t annabella_ss_mark(void) { return annabella_ss_top; }
+
+void annabella_ss_release(annabella_ss_mark_t mark) { annabella_ss_top = mark; }
+
+ssize_t annabella_string_length(annabella_type__string s) {
+  return s.last < s.first ? 0 : s.last - s.first + 1;
+}
+
+/* Storage for the result of a string operation on the secondary stack,
+ * NUL terminated for C. */
+annabella_type__string annabella_string_allocate(ssize_t first, ssize_t last) {
+  ssize_t length = last < first ? 0 : last - first + 1;
+  annabella_type__string s = {(char *)annabella_ss_allocate(length + 1), first,
+                              last};
+  s.data[length] = '\0';
+  return s;
+}
+
+annabella_type__string annabella_string_from_c(const char *str) {
+  annabella_type__string s = {(char *)str, 1, (ssize_t)strlen(str)};
+  return s;
+}
+
+char *annabella_string_to_c(annabella_type__string s) {
+  ssize_t length = annabella_string_length(s);
+  char *str = annabella_string_allocate(1, length).data;
+  memcpy(str, s.data, length);
+  return str;
+}
+
+annabella_type__string annabella_string_copy(annabella_type__string s) {
+  annabella_type__string copy = annabella_string_allocate(s.first, s.last);
+  memcpy(copy.data, s.data, annabella_string_length(s));
+  return copy;
+}
+
+/* Release the secondary stack of a returning function, keeping its result. */
+annabella_type__string annabella_string_return(annabella_ss_mark_t mark,
+                                               annabella_type__string s) {
+  ssize_t length = annabella_string_length(s);
+  annabella_ss_release(mark);
+  annabella_type__string result = {(char *)annabella_ss_allocate(length + 1),
+                                   s.first, s.last};
+  memmove(result.data, s.data, length);
+  result.data[length] = '\0';
+  return result;
+}
+
+annabella_type__string annabella_string_from_element(char c) {
+  annabella_type__string s = annabella_string_allocate(1, 1);
+  s.data[0] = c;
+  return s;
+}
+
+char *annabella_string_element(annabella_type__string s, ssize_t index) {
+  if (index < s.first || index > s.last) {
+    throw_Constraint_Error();
+  }
+  return &s.data[index - s.first];
+}
+
+annabella_type__string annabella_string_slice(annabella_type__string s,
+                                              ssize_t first, ssize_t last) {
+  if (first <= last && (first < s.first || last > s.last)) {
+    throw_Constraint_Error();
+  }
+  annabella_type__string slice = {s.data + (first - s.first), first, last};
+  return slice;
+}
+
+/* Array assignment copies the elements, the lengths must match. */
+void annabella_string_assign(annabella_type__string target,
+                             annabella_type__string source) {
+  ssize_t length = annabella_string_length(source);
+  if (annabella_string_length(target) != length) {
+    throw_Constraint_Error();
+  }
+  memmove(target.data, source.data, length);
+}
+
+/* The result starts at the lower bound of the left operand, or of the right
+ * operand if the left one is empty (RM 4.5.3). */
+annabella_type__string annabella_string_concat(annabella_type__string lhs,
+                                               annabella_type__string rhs) {
+  ssize_t lhs_length = annabella_string_length(lhs);
+  ssize_t rhs_length = annabella_string_length(rhs);
+  if (lhs_length == 0) {
+    return rhs;
+  }
+  annabella_type__string result =
+      annabella_string_allocate(lhs.first, lhs.first + lhs_length + rhs_length - 1);
+  memcpy(result.data, lhs.data, lhs_length);
+  memcpy(result.data + lhs_length, rhs.data, rhs_length);
+  return result;
+}
+
+int annabella_string_compare(annabella_type__string lhs,
+                             annabella_type__string rhs) {
+  ssize_t lhs_length = annabella_string_length(lhs);
+  ssize_t rhs_length = annabella_string_length(rhs);
+  int cmp = memcmp(lhs.data, rhs.data,
+                   lhs_length < rhs_length ? lhs_length : rhs_length);
+  if (cmp != 0) {
+    return cmp;
+  }
+  return (lhs_length > rhs_length) - (lhs_length < rhs_length);
+}
+
+/* UTF-8 encoding of a character of the Basic Multilingual Plane, returns the
+ * number of bytes. */
+static int annabella_utf8_encode(unsigned short c, char *out) {
+  if (c < 0x80) {
+    out[0] = (char)c;
+    return 1;
+  }
+  if (c < 0x800) {
+    out[0] = (char)(0xC0 | c >> 6);
+    out[1] = (char)(0x80 | (c & 0x3F));
+    return 2;
+  }
+  out[0] = (char)(0xE0 | c >> 12);
+  out[1] = (char)(0x80 | (c >> 6 & 0x3F));
+  out[2] = (char)(0x80 | (c & 0x3F));
+  return 3;
+}
+
+/* The image of a Wide_Character after Latin-1: the character in quotes, or
+ * `Hex_hhhhhhhh` for surrogates, private use and the noncharacters FFFE and
+ * FFFF, which are not graphic characters (RM 3.5(27.1)). */
+annabella_type__string annabella_wide_character_image(unsigned short c) {
+  annabella_type__string image = annabella_string_allocate(1, 12);
+  if ((c >= 0xD800 && c <= 0xF8FF) || c >= 0xFFFE) {
+    image.last = snprintf(image.data, 13, "Hex_%08X", c);
+    return image;
+  }
+  image.data[0] = '\'';
+  int length = annabella_utf8_encode(c, image.data + 1);
+  image.data[length + 1] = '\'';
+  image.last = length + 2;
+  return image;
+}
+
+void annabella_wide_character_print(unsigned short c) {
+  char encoded[3];
+  int length = annabella_utf8_encode(c, encoded);
+  printf("%.*s\n", length, encoded);
+}
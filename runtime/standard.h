@@ -0,0 +1,75 @@
+/* Types and functions of the C runtime used by the generated code */
+
+/* Secondary stack for function results of unknown size (like GNAT's
+ * System.Secondary_Stack). Subprograms mark it on entry and statements
+ * release it at their end. Chunks are kept for reuse, so memory released
+ * by a mark stays valid until it is allocated again. */
+typedef struct annabella_ss_chunk {
+  struct annabella_ss_chunk *next;
+  size_t size;
+  char data[];
+} annabella_ss_chunk;
+
+typedef struct {
+  annabella_ss_chunk *chunk;
+  size_t used;
+} annabella_ss_mark_t;
+
+void *annabella_ss_allocate(size_t size);
+annabella_ss_mark_t annabella_ss_mark(void);
+void annabella_ss_release(annabella_ss_mark_t mark);
+
+/* An unconstrained String, `data` points to the element at index `first`. */
+typedef struct {
+  char *data;
+  ssize_t first;
+  ssize_t last;
+} annabella_type__string;
+
+#ifdef __cplusplus
+[[noreturn]]
+#else
+_Noreturn
+#endif
+void throw_Constraint_Error(void);
+#ifdef __cplusplus
+[[noreturn]]
+#else
+_Noreturn
+#endif
+void throw_Program_Error(void);
+
+/* Name of the subprogram `name` of a package implemented in C. In C++ the
+ * declarations of the package are in the namespace `annabella::ns`, in C their
+ * name includes the package `path`. */
+#ifdef __cplusplus
+#define ANNABELLA_FUNCTION(ns, path, name) annabella::ns::function__##name
+#else
+#define ANNABELLA_FUNCTION(ns, path, name) annabella_function__##path##__##name
+#endif
+
+/* Arguments and exit status of the C `main` function, see standard.c */
+extern int annabella_argc;
+extern char **annabella_argv;
+extern int annabella_exit_status;
+
+ssize_t annabella_string_length(annabella_type__string s);
+annabella_type__string annabella_string_allocate(ssize_t first, ssize_t last);
+annabella_type__string annabella_string_from_c(const char *str);
+char *annabella_string_to_c(annabella_type__string s);
+annabella_type__string annabella_string_copy(annabella_type__string s);
+annabella_type__string annabella_string_return(annabella_ss_mark_t mark,
+                                               annabella_type__string s);
+annabella_type__string annabella_string_from_element(char c);
+char *annabella_string_element(annabella_type__string s, ssize_t index);
+annabella_type__string annabella_string_slice(annabella_type__string s,
+                                              ssize_t first, ssize_t last);
+void annabella_string_assign(annabella_type__string target,
+                             annabella_type__string source);
+annabella_type__string annabella_string_concat(annabella_type__string lhs,
+                                               annabella_type__string rhs);
+int annabella_string_compare(annabella_type__string lhs,
+                             annabella_type__string rhs);
+
+annabella_type__string annabella_wide_character_image(unsigned short c);
+void annabella_wide_character_print(unsigned short c);
@@ -0,0 +1,282 @@
+use std::iter;
+
+use crate::{
+    parser::{CompilationUnit, Item},
+    tokenizer::{Ident, Span, Spanned},
+    Result,
+};
+
+use super::{
+    c,
+    output::{Elaborate, Output, UnitPart},
+    Context, Type,
+};
+
+/// The call of the main subprogram in the C `main` function.
+///
+/// Like `gnatbind`, any library level procedure without parameters can be the main
+/// subprogram, or a function without parameters returning `Integer` as exit status.
+pub(super) fn main_subprogram(
+    units: &[CompilationUnit],
+    name: Option<&str>,
+    ctx: &mut Context,
+) -> Result<Vec<c::Stmt>> {
+    let ident = match name {
+        Some(name) => Ident {
+            name: name.to_lowercase().into(),
+            span: Span::call_site(),
+        },
+        None => units
+            .iter()
+            .rev()
+            .find_map(|unit| match &unit.item {
+                Item::Function(item) if item.spec.name.parent().is_none() => {
+                    Some(item.spec.name.last().clone())
+                }
+                _ => None,
+            })
+            .ok_or_else(|| {
+                Span::call_site()
+                    .unrecoverable_error("no subprogram body for the main subprogram")
+                    .bind()
+            })?,
+    };
+    let integer = Type::standard_integer(ctx)?;
+    let value = ctx
+        .get_expr_value(&ident)
+        .map_err(|err| match name {
+            Some(name) => Span::call_site()
+                .unrecoverable_error(format!("main subprogram `{name}` not found"))
+                .bind(),
+            None => err,
+        })?
+        .filter_distinct(&ident, |value| {
+            matches!(
+                value.ty.as_function(),
+                Some(ty) if ty.args.is_empty()
+                    && (ty.return_type.is_void() || integer.can_assign(&ty.return_type)),
+            )
+        })
+        .map_err(|_| {
+            ident
+                .unrecoverable_error(
+                    "main subprogram must be a procedure without parameters \
+                 or a function returning Integer",
+                )
+                .bind()
+        })?;
+    let call = value.code.call([]);
+    let ty = value
+        .ty
+        .as_function()
+        .expect("main subprogram is a function");
+    Ok(vec![if ty.return_type.is_void() {
+        call.into()
+    } else {
+        c::Stmt::return_(call)
+    }])
+}
+
+/// A part of a library unit, by index in [`Output::units`].
+type Node = (usize, UnitPart);
+
+/// The part `before` must be elaborated before the part `after`.
+struct Edge {
+    before: Node,
+    after: Node,
+    reason: &'static str,
+    span: Option<Span>,
+    /// The parts between `before` and `after`, for `pragma Elaborate_All` on a unit
+    /// depending on `before`.
+    via: Vec<Node>,
+}
+
+/// Compute the order in which the parts of all library units are elaborated, like `gnatbind`.
+///
+/// A part is elaborated after the specifications of the units it depends on, and a body
+/// after its specification. `pragma Elaborate` (and `Elaborate_All`) also requires the
+/// body of the named unit (and of all units it depends on), and `pragma Elaborate_Body`
+/// elaborates the body of a unit right after its specification. Preelaborated units and
+/// bodies are elaborated as early as possible.
+pub(super) fn elaboration_order(output: &Output) -> Result<Vec<Node>> {
+    check_preelaborate(output)?;
+
+    let mut edges = Vec::new();
+    for (index, unit) in output.units.iter().enumerate() {
+        edges.push(Edge {
+            before: (index, UnitPart::Spec),
+            after: (index, UnitPart::Body),
+            reason: "body",
+            span: None,
+            via: Vec::new(),
+        });
+        for (part, deps) in [
+            (UnitPart::Spec, &unit.spec_deps),
+            (UnitPart::Body, &unit.body_deps),
+        ] {
+            for &dep in deps {
+                let with = unit
+                    .withs
+                    .iter()
+                    .find(|with| with.unit == dep && with.part == part);
+                edges.push(Edge {
+                    before: (dep, UnitPart::Spec),
+                    after: (index, part),
+                    reason: if with.is_some() { "with" } else { "use" },
+                    span: with.map(|with| with.span),
+                    via: Vec::new(),
+                });
+            }
+        }
+        for with in &unit.withs {
+            let Some((elaborate, span)) = with.elaborate else {
+                continue;
+            };
+            let (reason, bodies) = match elaborate {
+                Elaborate::Unit => ("pragma Elaborate", vec![(with.unit, Vec::new())]),
+                Elaborate::All => ("pragma Elaborate_All", dependencies(output, with.unit)),
+            };
+            for (dep, via) in bodies {
+                edges.push(Edge {
+                    before: (dep, UnitPart::Body),
+                    after: (index, with.part),
+                    reason,
+                    span: Some(span),
+                    via: via.into_iter().map(|unit| (unit, UnitPart::Body)).collect(),
+                });
+            }
+        }
+    }
+
+    // everything elaborated after the specification also waits for the body
+    for (index, unit) in output.units.iter().enumerate() {
+        let Some(span) = unit.elaborate_body else {
+            continue;
+        };
+        let spec = (index, UnitPart::Spec);
+        let body = (index, UnitPart::Body);
+        let after = edges
+            .iter()
+            .filter(|edge| edge.before == spec && edge.after != body)
+            .map(|edge| edge.after)
+            .collect::<Vec<_>>();
+        for node in after {
+            edges.push(Edge {
+                before: body,
+                after: node,
+                reason: "pragma Elaborate_Body",
+                span: Some(span),
+                via: Vec::new(),
+            });
+        }
+    }
+
+    sort(output, &edges)
+}
+
+/// A preelaborated unit may only depend on preelaborated units.
+fn check_preelaborate(output: &Output) -> Result<()> {
+    for unit in &output.units {
+        if unit.preelaborate.is_none() {
+            continue;
+        }
+        for with in &unit.withs {
+            let dep = &output.units[with.unit];
+            if dep.preelaborate.is_none() {
+                return Err(with.span.unrecoverable_error(format!(
+                    "preelaborated unit `{}` depends on `{}`, which is not preelaborated",
+                    unit.name, dep.name
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The unit `index` and all units it depends on, each with the units depending on it
+/// up to `index`.
+fn dependencies(output: &Output, index: usize) -> Vec<(usize, Vec<usize>)> {
+    let mut units = vec![(index, Vec::new())];
+    let mut i = 0;
+    while let Some((unit, via)) = units.get(i).cloned() {
+        let code = &output.units[unit];
+        for &dep in code.spec_deps.iter().chain(&code.body_deps) {
+            if !units.iter().any(|&(other, _)| other == dep) {
+                units.push((dep, iter::once(unit).chain(via.iter().copied()).collect()));
+            }
+        }
+        i += 1;
+    }
+    units
+}
+
+/// Topological sort of the unit parts, or an error showing an elaboration cycle.
+fn sort(output: &Output, edges: &[Edge]) -> Result<Vec<Node>> {
+    let nodes = (0..output.units.len())
+        .flat_map(|index| [(index, UnitPart::Spec), (index, UnitPart::Body)])
+        .collect::<Vec<_>>();
+    let mut order = Vec::<Node>::new();
+    while order.len() < nodes.len() {
+        let is_ready = |node: &&Node| {
+            !order.contains(node)
+                && edges
+                    .iter()
+                    .all(|edge| edge.after != **node || order.contains(&edge.before))
+        };
+        let next = nodes.iter().filter(is_ready).min_by_key(|&&(index, part)| {
+            let preelaborate = output.units[index].preelaborate.is_some();
+            (!preelaborate, part == UnitPart::Spec, index)
+        });
+        match next {
+            Some(&node) => order.push(node),
+            None => return Err(cycle_error(output, edges, &order)),
+        }
+    }
+    Ok(order)
+}
+
+/// Each remaining part waits for another remaining part, following them backwards
+/// leads to a cycle.
+fn cycle_error(output: &Output, edges: &[Edge], order: &[Node]) -> crate::Error {
+    let waiting = |node: Node| {
+        edges
+            .iter()
+            .find(|edge| edge.after == node && !order.contains(&edge.before))
+            .expect("remaining part waits for another one")
+    };
+    let start = edges
+        .iter()
+        .find(|edge| !order.contains(&edge.after))
+        .expect("a part is not elaborated")
+        .after;
+    let mut visited = vec![start];
+    let mut path = Vec::new();
+    let cycle = loop {
+        let edge = waiting(visited[visited.len() - 1]);
+        path.push(edge);
+        if let Some(i) = visited.iter().position(|&node| node == edge.before) {
+            break path.split_off(i).into_iter().rev().collect::<Vec<_>>();
+        }
+        visited.push(edge.before);
+    };
+
+    let name = |(index, part): Node| {
+        let part = match part {
+            UnitPart::Spec => "spec",
+            UnitPart::Body => "body",
+        };
+        format!("{part} of `{}`", output.units[index].name)
+    };
+    let mut msg = format!("elaboration cycle: {}", name(cycle[0].before));
+    for edge in &cycle {
+        for &node in &edge.via {
+            msg.push_str(&format!(" -> {}", name(node)));
+        }
+        msg.push_str(&format!(" -> {} ({})", name(edge.after), edge.reason));
+    }
+    let span = cycle
+        .iter()
+        .find_map(|edge| edge.span)
+        .unwrap_or_else(Span::call_site);
+    span.unrecoverable_error(msg).bind()
+}
@@ -0,0 +1,465 @@
+//! A small typed AST of the generated C (and C++) code.
+//!
+//! The codegen builds declarations, statements and expressions with the constructors
+//! below, the [`print`] module turns them into formatted source code. Expressions are
+//! parenthesized by the printer according to the C precedence rules, so the codegen
+//! never has to add parentheses itself.
+
+use std::{fmt, rc::Rc};
+
+mod print;
+
+pub use self::print::Printer;
+
+/// A C identifier.
+///
+/// The declarations of a package are in the C++ namespace of the package, like
+/// `annabella::ada::text_io`. In C their name includes the name of the package.
+#[derive(Clone)]
+pub struct Ident {
+    name: Rc<str>,
+    namespace: Option<Rc<Namespaced>>,
+}
+
+/// The C++ namespace of an identifier, and its name in the namespace.
+#[derive(Debug)]
+struct Namespaced {
+    namespace: Vec<Box<str>>,
+    name: Box<str>,
+}
+
+impl Ident {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.into(),
+            namespace: None,
+        }
+    }
+
+    /// An identifier named `name` in C, and `local` in the C++ `namespace`.
+    pub fn namespaced(name: &str, namespace: Vec<Box<str>>, local: &str) -> Self {
+        Self {
+            name: name.into(),
+            namespace: Some(Rc::new(Namespaced {
+                namespace,
+                name: local.into(),
+            })),
+        }
+    }
+
+    /// The name in C.
+    pub fn as_str(&self) -> &str {
+        &self.name
+    }
+
+    /// The C++ namespace of the identifier, empty for the global namespace.
+    pub fn namespace(&self) -> &[Box<str>] {
+        self.namespace
+            .as_ref()
+            .map_or(&[], |namespaced| &namespaced.namespace)
+    }
+}
+
+impl PartialEq for Ident {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+    }
+}
+
+impl Eq for Ident {}
+
+impl fmt::Display for Ident {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.name)
+    }
+}
+
+impl fmt::Debug for Ident {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&self.name, f)
+    }
+}
+
+/// A type name with optional `const` qualifiers and pointers, like `char const *const`.
+#[derive(Debug, Clone)]
+pub struct Type {
+    name: Ident,
+    is_const: bool,
+
+    /// One entry per pointer level, `true` for a `const` pointer.
+    pointers: Vec<bool>,
+}
+
+impl Type {
+    pub fn named(name: &str) -> Self {
+        Self::from(Ident::new(name))
+    }
+
+    pub fn void() -> Self {
+        Self::named("void")
+    }
+
+    pub fn char() -> Self {
+        Self::named("char")
+    }
+
+    pub fn name(&self) -> &Ident {
+        &self.name
+    }
+
+    /// Qualify the type (or the outermost pointer) with `const`.
+    pub fn const_(mut self) -> Self {
+        match self.pointers.last_mut() {
+            Some(is_const) => *is_const = true,
+            None => self.is_const = true,
+        }
+        self
+    }
+
+    pub fn pointer(mut self) -> Self {
+        self.pointers.push(false);
+        self
+    }
+}
+
+impl From<Ident> for Type {
+    fn from(name: Ident) -> Self {
+        Self {
+            name,
+            is_const: false,
+            pointers: Vec::new(),
+        }
+    }
+}
+
+/// A top level declaration.
+#[derive(Debug, Clone)]
+pub enum Decl {
+    Typedef(Type, Ident, TypeAttributes),
+    TypedefStruct(Vec<VarDecl>, Ident, TypeAttributes),
+    Function(Function),
+    Var(VarDecl),
+
+    /// Declaration of a variable defined in another file.
+    Extern(VarDecl),
+
+    /// Invocation of a macro of the runtime that expands to declarations.
+    Macro(Expr),
+}
+
+/// GCC attributes of a typedef, from representation clauses.
+#[derive(Debug, Clone, Default)]
+pub struct TypeAttributes {
+    /// No padding between the fields of a struct.
+    pub packed: bool,
+    pub aligned: Option<u64>,
+}
+
+impl TypeAttributes {
+    pub fn is_empty(&self) -> bool {
+        !self.packed && self.aligned.is_none()
+    }
+}
+
+/// A function definition, or a prototype if it has no body.
+#[derive(Debug, Clone)]
+pub struct Function {
+    pub return_type: Type,
+    pub name: Ident,
+    pub params: Vec<Param>,
+    pub body: Option<Vec<Stmt>>,
+
+    /// C language linkage in C++, for functions shared with C code.
+    pub extern_c: bool,
+}
+
+impl Function {
+    /// The declaration of the function without its body.
+    pub fn prototype(&self) -> Self {
+        Self {
+            return_type: self.return_type.clone(),
+            name: self.name.clone(),
+            params: self.params.clone(),
+            body: None,
+            extern_c: self.extern_c,
+        }
+    }
+}
+
+/// A function parameter, unnamed in prototypes.
+#[derive(Debug, Clone)]
+pub struct Param {
+    pub ty: Type,
+    pub name: Option<Ident>,
+}
+
+impl Param {
+    pub fn new(ty: Type, name: Ident) -> Self {
+        Self {
+            ty,
+            name: Some(name),
+        }
+    }
+
+    pub fn unnamed(ty: Type) -> Self {
+        Self { ty, name: None }
+    }
+}
+
+/// A variable (or record field) declaration.
+#[derive(Debug, Clone)]
+pub struct VarDecl {
+    pub is_static: bool,
+
+    /// C language linkage in C++, for objects shared with C code.
+    pub extern_c: bool,
+    pub ty: Type,
+    pub name: Ident,
+
+    /// Array declarator, with an optional size.
+    pub array: Option<Option<Expr>>,
+
+    /// Width of a bit-field.
+    pub bits: Option<u32>,
+    pub init: Option<Expr>,
+}
+
+impl VarDecl {
+    pub fn new(ty: Type, name: Ident) -> Self {
+        Self {
+            is_static: false,
+            extern_c: false,
+            ty,
+            name,
+            array: None,
+            bits: None,
+            init: None,
+        }
+    }
+
+    pub fn static_(mut self) -> Self {
+        self.is_static = true;
+        self
+    }
+
+    pub fn extern_c(mut self) -> Self {
+        self.extern_c = true;
+        self
+    }
+
+    pub fn array(mut self, size: Option<Expr>) -> Self {
+        self.array = Some(size);
+        self
+    }
+
+    /// Unnamed bit-field of `bits` padding bits in a struct.
+    pub fn padding(ty: Type, bits: u32) -> Self {
+        Self::new(ty, Ident::new("")).bits(bits)
+    }
+
+    pub fn bits(mut self, bits: u32) -> Self {
+        self.bits = Some(bits);
+        self
+    }
+
+    pub fn init(mut self, init: Expr) -> Self {
+        self.init = Some(init);
+        self
+    }
+}
+
+impl From<VarDecl> for Stmt {
+    fn from(decl: VarDecl) -> Self {
+        Self::Decl(decl)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Stmt {
+    Decl(VarDecl),
+    Expr(Expr),
+    Block(Vec<Stmt>),
+    If {
+        cond: Expr,
+        then: Vec<Stmt>,
+        else_: Option<Vec<Stmt>>,
+    },
+    While {
+        cond: Expr,
+        body: Vec<Stmt>,
+    },
+    Return(Option<Expr>),
+    Break,
+    Goto(Ident),
+    Label(Ident),
+
+    /// C++ only, catches exceptions of type `ty` by const reference.
+    TryCatch {
+        body: Vec<Stmt>,
+        ty: Type,
+        name: Ident,
+        handler: Vec<Stmt>,
+    },
+}
+
+impl Stmt {
+    pub fn if_(cond: Expr, then: Vec<Stmt>) -> Self {
+        Self::If {
+            cond,
+            then,
+            else_: None,
+        }
+    }
+
+    pub fn if_else(cond: Expr, then: Vec<Stmt>, else_: Vec<Stmt>) -> Self {
+        Self::If {
+            cond,
+            then,
+            else_: Some(else_),
+        }
+    }
+
+    pub fn return_(expr: Expr) -> Self {
+        Self::Return(Some(expr))
+    }
+}
+
+impl From<Expr> for Stmt {
+    fn from(expr: Expr) -> Self {
+        Self::Expr(expr)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnaryOp {
+    Neg,
+    Plus,
+    Not,
+    BitNot,
+    Deref,
+    AddrOf,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryOp {
+    Mul,
+    Div,
+    Rem,
+    Add,
+    Sub,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+    BitAnd,
+    BitXor,
+    BitOr,
+    And,
+    Or,
+}
+
+#[derive(Debug, Clone)]
+pub enum Expr {
+    Ident(Ident),
+    Int(i128),
+    Float(f64),
+    Str(String),
+    Char(char),
+    Call(Box<Expr>, Vec<Expr>),
+    Index(Box<Expr>, Box<Expr>),
+    Member(Box<Expr>, Ident),
+    Unary(UnaryOp, Box<Expr>),
+    Cast(Type, Box<Expr>),
+    Binary(BinaryOp, Box<Expr>, Box<Expr>),
+    Cond(Box<Expr>, Box<Expr>, Box<Expr>),
+
+    /// Assignment, or compound assignment like `+=`.
+    Assign(Option<BinaryOp>, Box<Expr>, Box<Expr>),
+
+    /// `(T){ ... }` in C, `T{ ... }` in C++.
+    Compound(Type, Vec<Expr>),
+
+    /// Braced initializer of an array or struct variable.
+    InitList(Vec<Expr>),
+
+    /// Designated initializer `.name = value` of a struct field, in a braced initializer.
+    Designated(Ident, Box<Expr>),
+
+    /// GNU statement expression `({ ... })`, the value of the last statement is the
+    /// value of the expression.
+    StmtExpr(Vec<Stmt>),
+}
+
+impl Expr {
+    pub fn ident(name: &str) -> Self {
+        Self::Ident(Ident::new(name))
+    }
+
+    pub fn int(value: impl Into<i128>) -> Self {
+        Self::Int(value.into())
+    }
+
+    pub fn float(value: f64) -> Self {
+        Self::Float(value)
+    }
+
+    pub fn str(value: impl Into<String>) -> Self {
+        Self::Str(value.into())
+    }
+
+    pub fn designated(name: Ident, value: Expr) -> Self {
+        Self::Designated(name, Box::new(value))
+    }
+
+    pub fn call(self, args: impl IntoIterator<Item = Expr>) -> Self {
+        Self::Call(Box::new(self), args.into_iter().collect())
+    }
+
+    pub fn index(self, index: Expr) -> Self {
+        Self::Index(Box::new(self), Box::new(index))
+    }
+
+    pub fn member(self, field: Ident) -> Self {
+        Self::Member(Box::new(self), field)
+    }
+
+    pub fn unary(op: UnaryOp, expr: Expr) -> Self {
+        Self::Unary(op, Box::new(expr))
+    }
+
+    pub fn deref(self) -> Self {
+        Self::unary(UnaryOp::Deref, self)
+    }
+
+    pub fn addr_of(self) -> Self {
+        Self::unary(UnaryOp::AddrOf, self)
+    }
+
+    pub fn cast(self, ty: Type) -> Self {
+        Self::Cast(ty, Box::new(self))
+    }
+
+    pub fn binary(self, op: BinaryOp, rhs: Expr) -> Self {
+        Self::Binary(op, Box::new(self), Box::new(rhs))
+    }
+
+    pub fn cond(self, then: Expr, else_: Expr) -> Self {
+        Self::Cond(Box::new(self), Box::new(then), Box::new(else_))
+    }
+
+    pub fn assign(self, value: Expr) -> Self {
+        Self::Assign(None, Box::new(self), Box::new(value))
+    }
+
+    pub fn assign_op(self, op: BinaryOp, value: Expr) -> Self {
+        Self::Assign(Some(op), Box::new(self), Box::new(value))
+    }
+}
+
+impl From<Ident> for Expr {
+    fn from(ident: Ident) -> Self {
+        Self::Ident(ident)
+    }
+}
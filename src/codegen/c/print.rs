@@ -0,0 +1,581 @@
+//! Formatting of the C AST, with the layout of `clang-format`s LLVM style.
+
+use std::fmt::{self, Write};
+
+use super::{
+    BinaryOp, Decl, Expr, Function, Ident, Param, Stmt, Type, TypeAttributes, UnaryOp, VarDecl,
+};
+
+use crate::codegen::Language;
+
+const INDENT: &str = "  ";
+
+/// Operator precedence, higher values bind stronger.
+mod prec {
+    pub const ASSIGN: u8 = 2;
+    pub const COND: u8 = 3;
+    pub const OR: u8 = 4;
+    pub const AND: u8 = 5;
+    pub const BIT_OR: u8 = 6;
+    pub const BIT_XOR: u8 = 7;
+    pub const BIT_AND: u8 = 8;
+    pub const EQUALITY: u8 = 9;
+    pub const RELATIONAL: u8 = 10;
+    pub const ADDITIVE: u8 = 12;
+    pub const MULTIPLICATIVE: u8 = 13;
+    pub const UNARY: u8 = 14;
+    pub const POSTFIX: u8 = 15;
+    pub const PRIMARY: u8 = 16;
+}
+
+pub struct Printer<'a> {
+    out: &'a mut dyn Write,
+    language: Language,
+    indent: usize,
+
+    /// The C++ namespace of the previous declarations, empty if it is closed.
+    namespace: Vec<Box<str>>,
+}
+
+/// The C++ namespace containing the namespaces of the packages.
+const NAMESPACE: &str = "annabella";
+
+impl<'a> Printer<'a> {
+    pub fn new(out: &'a mut dyn Write, language: Language) -> Self {
+        Self {
+            out,
+            language,
+            indent: 0,
+            namespace: Vec::new(),
+        }
+    }
+
+    /// Close the C++ namespace of the previous declarations, before the code written
+    /// without the printer.
+    pub fn close_namespace(&mut self) -> fmt::Result {
+        if self.namespace.is_empty() {
+            return Ok(());
+        }
+        let namespace = std::mem::take(&mut self.namespace);
+        writeln!(
+            self.out,
+            "}} // namespace {NAMESPACE}::{}",
+            namespace.join("::")
+        )
+    }
+
+    /// Put the following declarations in a C++ namespace.
+    fn open_namespace(&mut self, namespace: &[Box<str>]) -> fmt::Result {
+        if self.language != Language::Cxx || *self.namespace == *namespace {
+            return Ok(());
+        }
+        self.close_namespace()?;
+        if !namespace.is_empty() {
+            writeln!(
+                self.out,
+                "namespace {NAMESPACE}::{} {{",
+                namespace.join("::")
+            )?;
+            self.namespace = namespace.to_vec();
+        }
+        Ok(())
+    }
+
+    /// The name of a declaration, without its namespace in C++.
+    fn declared<'b>(&self, ident: &'b Ident) -> &'b str {
+        match &ident.namespace {
+            Some(namespaced) if self.language == Language::Cxx => &namespaced.name,
+            _ => ident.as_str(),
+        }
+    }
+
+    /// A reference to a declaration, qualified by its namespace in C++.
+    fn reference(&mut self, ident: &Ident) -> fmt::Result {
+        match &ident.namespace {
+            Some(namespaced) if self.language == Language::Cxx => {
+                write!(self.out, "{NAMESPACE}::")?;
+                for name in &namespaced.namespace {
+                    write!(self.out, "{name}::")?;
+                }
+                self.out.write_str(&namespaced.name)
+            }
+            _ => self.out.write_str(ident.as_str()),
+        }
+    }
+
+    /// Separate top level declarations.
+    pub fn blank_line(&mut self) -> fmt::Result {
+        self.out.write_char('\n')
+    }
+
+    fn line_start(&mut self) -> fmt::Result {
+        for _ in 0..self.indent {
+            self.out.write_str(INDENT)?;
+        }
+        Ok(())
+    }
+
+    pub fn decl(&mut self, decl: &Decl) -> fmt::Result {
+        let namespace = match decl {
+            Decl::Typedef(_, name, _) | Decl::TypedefStruct(_, name, _) => name.namespace(),
+            Decl::Function(function) => function.name.namespace(),
+            Decl::Var(decl) | Decl::Extern(decl) => decl.name.namespace(),
+            Decl::Macro(_) => &[],
+        };
+        self.open_namespace(namespace)?;
+        match decl {
+            Decl::Typedef(ty, name, attributes) => {
+                self.out.write_str("typedef ")?;
+                self.declarator(ty, Some(self.declared(name)))?;
+                self.type_attributes(attributes)?;
+                self.out.write_str(";\n")
+            }
+            Decl::TypedefStruct(fields, name, attributes) => {
+                self.out.write_str("typedef struct")?;
+                self.type_attributes(attributes)?;
+                self.out.write_str(" {\n")?;
+                self.indent += 1;
+                for field in fields {
+                    self.line_start()?;
+                    self.var_decl(field)?;
+                    self.out.write_str(";\n")?;
+                }
+                self.indent -= 1;
+                writeln!(self.out, "}} {};", self.declared(name))
+            }
+            Decl::Function(function) => self.function(function),
+            // `extern "C" T name;` only declares the object, the block form defines it
+            Decl::Var(decl) if decl.extern_c && self.language == Language::Cxx => {
+                self.out.write_str("extern \"C\" { ")?;
+                self.var_decl(decl)?;
+                self.out.write_str("; }\n")
+            }
+            Decl::Var(decl) => {
+                self.var_decl(decl)?;
+                self.out.write_str(";\n")
+            }
+            Decl::Extern(decl) => {
+                if decl.extern_c && self.language == Language::Cxx {
+                    self.out.write_str("extern \"C\" ")?;
+                } else {
+                    self.out.write_str("extern ")?;
+                }
+                self.var_decl(decl)?;
+                self.out.write_str(";\n")
+            }
+            Decl::Macro(expr) => {
+                self.expr(expr, 0)?;
+                self.out.write_char('\n')
+            }
+        }
+    }
+
+    fn function(&mut self, function: &Function) -> fmt::Result {
+        if function.extern_c && self.language == Language::Cxx {
+            self.out.write_str("extern \"C\" ")?;
+        }
+        self.declarator(&function.return_type, Some(self.declared(&function.name)))?;
+        self.out.write_char('(')?;
+        if function.params.is_empty() {
+            self.out.write_str("void")?;
+        }
+        for (i, Param { ty, name }) in function.params.iter().enumerate() {
+            if i > 0 {
+                self.out.write_str(", ")?;
+            }
+            self.declarator(ty, name.as_ref().map(|name| name.as_str()))?;
+        }
+        self.out.write_char(')')?;
+        match &function.body {
+            Some(body) => {
+                self.out.write_char(' ')?;
+                self.block(body)?;
+                self.out.write_char('\n')
+            }
+            None => self.out.write_str(";\n"),
+        }
+    }
+
+    /// A type followed by an optional name, `char const *const name`.
+    fn declarator(&mut self, ty: &Type, name: Option<&str>) -> fmt::Result {
+        self.reference(&ty.name)?;
+        if ty.is_const {
+            self.out.write_str(" const")?;
+        }
+        if !ty.pointers.is_empty() {
+            self.out.write_char(' ')?;
+        }
+        for (i, &is_const) in ty.pointers.iter().enumerate() {
+            self.out.write_char('*')?;
+            if is_const {
+                self.out.write_str("const")?;
+                if i + 1 < ty.pointers.len() {
+                    self.out.write_char(' ')?;
+                }
+            }
+        }
+        if let Some(name) = name {
+            if ty.pointers.last() != Some(&false) {
+                self.out.write_char(' ')?;
+            }
+            self.out.write_str(name)?;
+        }
+        Ok(())
+    }
+
+    fn var_decl(&mut self, decl: &VarDecl) -> fmt::Result {
+        if decl.is_static {
+            self.out.write_str("static ")?;
+        }
+        // the name of padding bit-fields is empty
+        let name = Some(self.declared(&decl.name)).filter(|name| !name.is_empty());
+        self.declarator(&decl.ty, name)?;
+        if let Some(size) = &decl.array {
+            self.out.write_char('[')?;
+            if let Some(size) = size {
+                self.expr(size, 0)?;
+            }
+            self.out.write_char(']')?;
+        }
+        if let Some(bits) = decl.bits {
+            write!(self.out, " : {bits}")?;
+        }
+        if let Some(init) = &decl.init {
+            self.out.write_str(" = ")?;
+            self.expr(init, prec::ASSIGN)?;
+        }
+        Ok(())
+    }
+
+    /// Like ` __attribute__((packed, aligned(4)))`, nothing without attributes.
+    fn type_attributes(&mut self, attributes: &TypeAttributes) -> fmt::Result {
+        if attributes.is_empty() {
+            return Ok(());
+        }
+        let mut list = Vec::new();
+        if attributes.packed {
+            list.push("packed".to_string());
+        }
+        if let Some(aligned) = attributes.aligned {
+            list.push(format!("aligned({aligned})"));
+        }
+        write!(self.out, " __attribute__(({}))", list.join(", "))
+    }
+
+    /// A braced list of statements, starting at the current position.
+    fn block(&mut self, stmts: &[Stmt]) -> fmt::Result {
+        self.out.write_str("{\n")?;
+        self.indent += 1;
+        for stmt in stmts {
+            self.stmt(stmt)?;
+        }
+        self.indent -= 1;
+        self.line_start()?;
+        self.out.write_char('}')
+    }
+
+    pub fn stmt(&mut self, stmt: &Stmt) -> fmt::Result {
+        self.line_start()?;
+        match stmt {
+            Stmt::Decl(decl) => {
+                self.var_decl(decl)?;
+                self.out.write_char(';')?;
+            }
+            Stmt::Expr(expr) => {
+                self.expr(expr, 0)?;
+                self.out.write_char(';')?;
+            }
+            Stmt::Block(stmts) => self.block(stmts)?,
+            Stmt::If { .. } => self.if_(stmt)?,
+            Stmt::While { cond, body } => {
+                self.out.write_str("while (")?;
+                self.expr(cond, 0)?;
+                self.out.write_str(") ")?;
+                self.block(body)?;
+            }
+            Stmt::Return(None) => self.out.write_str("return;")?,
+            Stmt::Return(Some(expr)) => {
+                self.out.write_str("return ")?;
+                self.expr(expr, 0)?;
+                self.out.write_char(';')?;
+            }
+            Stmt::Break => self.out.write_str("break;")?,
+            Stmt::Goto(label) => write!(self.out, "goto {label};")?,
+            // the empty statement allows a label at the end of a block
+            Stmt::Label(label) => write!(self.out, "{label}:;")?,
+            Stmt::TryCatch {
+                body,
+                ty,
+                name,
+                handler,
+            } => {
+                self.out.write_str("try ")?;
+                self.block(body)?;
+                self.out.write_str(" catch (")?;
+                self.declarator(&ty.clone().const_(), None)?;
+                write!(self.out, " &{name}) ")?;
+                self.block(handler)?;
+            }
+        }
+        self.out.write_char('\n')
+    }
+
+    /// An `if` statement, with `else if` for an `else` branch containing only an `if`.
+    fn if_(&mut self, mut stmt: &Stmt) -> fmt::Result {
+        loop {
+            let Stmt::If { cond, then, else_ } = stmt else {
+                unreachable!("not an if statement")
+            };
+            self.out.write_str("if (")?;
+            self.expr(cond, 0)?;
+            self.out.write_str(") ")?;
+            self.block(then)?;
+            match else_.as_deref() {
+                None => return Ok(()),
+                Some([else_if @ Stmt::If { .. }]) => {
+                    self.out.write_str(" else ")?;
+                    stmt = else_if;
+                }
+                Some(else_) => {
+                    self.out.write_str(" else ")?;
+                    return self.block(else_);
+                }
+            }
+        }
+    }
+
+    /// An expression, parenthesized if it binds weaker than `min_prec`.
+    fn expr(&mut self, expr: &Expr, min_prec: u8) -> fmt::Result {
+        let parens = precedence(expr) < min_prec;
+        if parens {
+            self.out.write_char('(')?;
+        }
+        match expr {
+            Expr::Ident(ident) => self.reference(ident)?,
+            // the most negative value has no literal in C
+            Expr::Int(value) if *value == i64::MIN.into() => {
+                write!(self.out, "({} - 1)", i64::MIN + 1)?
+            }
+            Expr::Int(value) => write!(self.out, "{value}")?,
+            // the debug format always has a decimal point or an exponent, the universal
+            // reals out of range are rejected before
+            Expr::Float(value) => {
+                assert!(value.is_finite(), "float literal not finite: {value}");
+                write!(self.out, "{value:?}")?
+            }
+            Expr::Str(value) => write_str_literal(self.out, value)?,
+            Expr::Char(value) => write_char_literal(self.out, *value)?,
+            Expr::Call(function, args) => {
+                self.expr(function, prec::POSTFIX)?;
+                self.out.write_char('(')?;
+                self.list(args)?;
+                self.out.write_char(')')?;
+            }
+            Expr::Index(expr, index) => {
+                self.expr(expr, prec::POSTFIX)?;
+                self.out.write_char('[')?;
+                self.expr(index, 0)?;
+                self.out.write_char(']')?;
+            }
+            Expr::Member(expr, field) => {
+                self.expr(expr, prec::POSTFIX)?;
+                write!(self.out, ".{field}")?;
+            }
+            Expr::Unary(op, operand) => {
+                self.out.write_str(match op {
+                    UnaryOp::Neg => "-",
+                    UnaryOp::Plus => "+",
+                    UnaryOp::Not => "!",
+                    UnaryOp::BitNot => "~",
+                    UnaryOp::Deref => "*",
+                    UnaryOp::AddrOf => "&",
+                })?;
+                // `- -x` or `&&x` would be read as different tokens
+                let nested = matches!(**operand, Expr::Unary(..)) || is_negative(operand);
+                self.expr(operand, if nested { prec::PRIMARY } else { prec::UNARY })?;
+            }
+            Expr::Cast(ty, expr) => {
+                self.out.write_char('(')?;
+                self.declarator(ty, None)?;
+                self.out.write_char(')')?;
+                self.expr(expr, prec::UNARY)?;
+            }
+            Expr::Binary(op, lhs, rhs) => {
+                let prec = binary_precedence(*op);
+                self.operand(*op, lhs, prec)?;
+                write!(self.out, " {} ", binary_symbol(*op))?;
+                self.operand(*op, rhs, prec + 1)?;
+            }
+            Expr::Cond(cond, then, else_) => {
+                self.expr(cond, prec::OR)?;
+                self.out.write_str(" ? ")?;
+                self.expr(then, 0)?;
+                self.out.write_str(" : ")?;
+                self.expr(else_, prec::COND)?;
+            }
+            Expr::Assign(op, target, value) => {
+                self.expr(target, prec::UNARY)?;
+                match op {
+                    Some(op) => write!(self.out, " {}= ", binary_symbol(*op))?,
+                    None => self.out.write_str(" = ")?,
+                }
+                self.expr(value, prec::ASSIGN)?;
+            }
+            Expr::Compound(ty, values) => {
+                match self.language {
+                    Language::C => {
+                        self.out.write_char('(')?;
+                        self.declarator(ty, None)?;
+                        self.out.write_char(')')?;
+                    }
+                    Language::Cxx => self.declarator(ty, None)?,
+                }
+                self.init_list(values)?;
+            }
+            Expr::InitList(values) => self.init_list(values)?,
+            Expr::Designated(name, value) => {
+                write!(self.out, ".{name} = ")?;
+                self.expr(value, prec::ASSIGN)?;
+            }
+            Expr::StmtExpr(stmts) => {
+                self.out.write_char('(')?;
+                self.block(stmts)?;
+                self.out.write_char(')')?;
+            }
+        }
+        if parens {
+            self.out.write_char(')')?;
+        }
+        Ok(())
+    }
+
+    /// Operand of a binary operator, mixed bitwise and logical operators or chained
+    /// comparisons are parenthesized for clarity (like `-Wparentheses` suggests).
+    fn operand(&mut self, op: BinaryOp, operand: &Expr, min_prec: u8) -> fmt::Result {
+        let clarify = match operand {
+            Expr::Binary(inner, ..) => {
+                let prec = binary_precedence(op);
+                let inner_prec = binary_precedence(*inner);
+                match prec {
+                    prec::BIT_OR | prec::BIT_XOR | prec::BIT_AND => *inner != op,
+                    prec::OR => inner_prec == prec::AND,
+                    prec::EQUALITY | prec::RELATIONAL => {
+                        inner_prec == prec::EQUALITY || inner_prec == prec::RELATIONAL
+                    }
+                    _ => false,
+                }
+            }
+            _ => false,
+        };
+        self.expr(operand, if clarify { prec::PRIMARY } else { min_prec })
+    }
+
+    fn list(&mut self, exprs: &[Expr]) -> fmt::Result {
+        for (i, expr) in exprs.iter().enumerate() {
+            if i > 0 {
+                self.out.write_str(", ")?;
+            }
+            self.expr(expr, prec::ASSIGN)?;
+        }
+        Ok(())
+    }
+
+    fn init_list(&mut self, exprs: &[Expr]) -> fmt::Result {
+        self.out.write_char('{')?;
+        self.list(exprs)?;
+        self.out.write_char('}')
+    }
+}
+
+fn precedence(expr: &Expr) -> u8 {
+    match expr {
+        Expr::Int(value) if *value == i64::MIN.into() => prec::PRIMARY,
+        Expr::Int(_) | Expr::Float(_) if is_negative(expr) => prec::UNARY,
+        Expr::Ident(_)
+        | Expr::Int(_)
+        | Expr::Float(_)
+        | Expr::Str(_)
+        | Expr::Char(_)
+        | Expr::InitList(_)
+        | Expr::StmtExpr(_) => prec::PRIMARY,
+        Expr::Call(..) | Expr::Index(..) | Expr::Member(..) | Expr::Compound(..) => prec::POSTFIX,
+        Expr::Unary(..) | Expr::Cast(..) => prec::UNARY,
+        Expr::Binary(op, ..) => binary_precedence(*op),
+        Expr::Cond(..) => prec::COND,
+        Expr::Assign(..) | Expr::Designated(..) => prec::ASSIGN,
+    }
+}
+
+fn is_negative(expr: &Expr) -> bool {
+    match expr {
+        Expr::Int(value) => *value < 0,
+        Expr::Float(value) => value.is_sign_negative(),
+        _ => false,
+    }
+}
+
+fn binary_precedence(op: BinaryOp) -> u8 {
+    match op {
+        BinaryOp::Mul | BinaryOp::Div | BinaryOp::Rem => prec::MULTIPLICATIVE,
+        BinaryOp::Add | BinaryOp::Sub => prec::ADDITIVE,
+        BinaryOp::Lt | BinaryOp::Le | BinaryOp::Gt | BinaryOp::Ge => prec::RELATIONAL,
+        BinaryOp::Eq | BinaryOp::Ne => prec::EQUALITY,
+        BinaryOp::BitAnd => prec::BIT_AND,
+        BinaryOp::BitXor => prec::BIT_XOR,
+        BinaryOp::BitOr => prec::BIT_OR,
+        BinaryOp::And => prec::AND,
+        BinaryOp::Or => prec::OR,
+    }
+}
+
+fn binary_symbol(op: BinaryOp) -> &'static str {
+    match op {
+        BinaryOp::Mul => "*",
+        BinaryOp::Div => "/",
+        BinaryOp::Rem => "%",
+        BinaryOp::Add => "+",
+        BinaryOp::Sub => "-",
+        BinaryOp::Lt => "<",
+        BinaryOp::Le => "<=",
+        BinaryOp::Gt => ">",
+        BinaryOp::Ge => ">=",
+        BinaryOp::Eq => "==",
+        BinaryOp::Ne => "!=",
+        BinaryOp::BitAnd => "&",
+        BinaryOp::BitXor => "^",
+        BinaryOp::BitOr => "|",
+        BinaryOp::And => "&&",
+        BinaryOp::Or => "||",
+    }
+}
+
+/// Bytes outside of printable ASCII are written as octal escapes.
+fn write_str_literal(out: &mut dyn Write, value: &str) -> fmt::Result {
+    out.write_char('"')?;
+    let mut prev = 0;
+    for byte in value.bytes() {
+        match byte {
+            b'"' => out.write_str("\\\"")?,
+            b'\\' => out.write_str("\\\\")?,
+            b'\n' => out.write_str("\\n")?,
+            b'\t' => out.write_str("\\t")?,
+            // `??` starts a trigraph
+            b'?' if prev == b'?' => out.write_str("\\?")?,
+            b' '..=b'~' => out.write_char(byte.into())?,
+            _ => write!(out, "\\{byte:03o}")?,
+        }
+        prev = byte;
+    }
+    out.write_char('"')
+}
+
+/// Characters of the Latin-1 range are written as octal escapes.
+fn write_char_literal(out: &mut dyn Write, value: char) -> fmt::Result {
+    match value {
+        '\'' => out.write_str("'\\''"),
+        '\\' => out.write_str("'\\\\'"),
+        ' '..='~' => write!(out, "'{value}'"),
+        '\0'..='\u{ff}' => write!(out, "'\\{:03o}'", value as u32),
+        _ => write!(out, "{}", value as u32),
+    }
+}
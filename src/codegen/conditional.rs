@@ -0,0 +1,226 @@
+use crate::{
+    parser::{ExprCase, ExprDeclare, ExprIf, ExprQuantified, Item, Quantifier},
+    tokenizer::{Ident, Span, Spanned},
+    Error, Result,
+};
+
+use super::{
+    c, stmt::generate_for_loop, CodeGenExpr, CodeGenStmt, Context, DynamicExprValue, ExprValue,
+    Permission, SingleExprValue, Type,
+};
+
+impl CodeGenExpr for ExprIf {
+    /// A chain of conditional operators, only the selected dependent expression is
+    /// evaluated.
+    fn generate(&self, ctx: &mut Context) -> Result<ExprValue> {
+        let mut conds = vec![self.cond.generate_to_boolean(ctx)?];
+        let mut dependent = vec![(self.expr.span(), self.expr.generate(ctx)?)];
+        for elsif in &self.elsifs {
+            conds.push(elsif.cond.generate_to_boolean(ctx)?);
+            dependent.push((elsif.expr.span(), elsif.expr.generate(ctx)?));
+        }
+        match &self.else_ {
+            Some((_, expr)) => dependent.push((expr.span(), expr.generate(ctx)?)),
+            // without `else` the expression is of type Boolean, `else True`
+            None => {
+                let boolean = Type::boolean(ctx)?;
+                let true_ = Ident {
+                    name: "true".into(),
+                    span: self.span(),
+                };
+                let true_ = ctx.get_expr_value(&true_)?.implicit_dereference(ctx)?;
+                for (span, value) in &mut dependent {
+                    *value = value.clone().filter_type(span, &boolean)?.into();
+                }
+                dependent.push((self.span(), true_.filter_type(self, &boolean)?.into()));
+            }
+        }
+        generate_dependent(self.span(), dependent, move |mut codes| {
+            let else_ = codes.pop().unwrap();
+            conds
+                .iter()
+                .zip(codes)
+                .rev()
+                .fold(else_, |else_, (cond, then)| {
+                    c::Expr::Cond(cond.clone().into(), then.into(), else_.into())
+                })
+        })
+    }
+}
+
+impl CodeGenExpr for ExprCase {
+    /// The selecting expression is evaluated once into `case_expr` in a statement
+    /// expression, followed by a chain of conditional operators. The choices must
+    /// cover all values, so the last alternative needs no condition.
+    fn generate(&self, ctx: &mut Context) -> Result<ExprValue> {
+        let expr = self
+            .expr
+            .generate(ctx)?
+            .distinct(&self.expr, "ambiguous case expression")?;
+        let ty = expr.ty;
+        let decl = c::VarDecl::new(ty.c_type(), c::Ident::new("case_expr")).init(expr.code);
+
+        let mut conds = Vec::new();
+        let mut dependent = Vec::new();
+        for alt in self.alternatives.iter() {
+            let cond = alt
+                .choices
+                .iter()
+                .map(|choice| choice.generate(&ty, ctx))
+                .reduce(|lhs, rhs| Ok(lhs?.binary(c::BinaryOp::Or, rhs?)))
+                .expect("case alternative without choices")?;
+            conds.push(cond);
+            dependent.push((alt.expr.span(), alt.expr.generate(ctx)?));
+        }
+        generate_dependent(self.span(), dependent, move |mut codes| {
+            let last = codes.pop().unwrap();
+            let chain = conds
+                .iter()
+                .zip(codes)
+                .rev()
+                .fold(last, |else_, (cond, then)| {
+                    c::Expr::Cond(cond.clone().into(), then.into(), else_.into())
+                });
+            c::Expr::StmtExpr(vec![decl.clone().into(), chain.into()])
+        })
+    }
+}
+
+impl CodeGenExpr for ExprQuantified {
+    /// A loop in a statement expression, which ends at the first value of the loop
+    /// parameter deciding the result.
+    fn generate(&self, ctx: &mut Context) -> Result<ExprValue> {
+        let boolean = Type::boolean(ctx)?;
+        // `for all` is true unless the predicate is false for a value, `for some` is
+        // false unless the predicate is true for a value
+        let all = matches!(self.quantifier, Quantifier::All(_));
+        let result = c::Expr::ident("quantified");
+        let decide = |predicate: c::Expr| {
+            let found = if all {
+                c::Expr::unary(c::UnaryOp::Not, predicate)
+            } else {
+                predicate
+            };
+            vec![c::Stmt::if_(
+                found,
+                vec![
+                    result.clone().assign(c::Expr::int(i32::from(!all))).into(),
+                    c::Stmt::Break,
+                ],
+            )]
+        };
+
+        let mut sub_ctx = ctx.subscope(ctx.return_type());
+        let loop_ = generate_for_loop(&self.param, &mut sub_ctx, |ctx| {
+            Ok(decide(self.predicate.generate_to_boolean(ctx)?))
+        })?;
+
+        Ok(SingleExprValue {
+            ty: boolean.clone(),
+            perm: Permission::Read,
+            code: c::Expr::StmtExpr(vec![
+                c::VarDecl::new(boolean.c_type(), c::Ident::new("quantified"))
+                    .init(c::Expr::int(i32::from(all)))
+                    .into(),
+                loop_,
+                result.into(),
+            ]),
+            value: None,
+            subprogram: None,
+        }
+        .into())
+    }
+}
+
+impl CodeGenExpr for ExprDeclare {
+    /// The declarations and the body expression in a statement expression.
+    fn generate(&self, ctx: &mut Context) -> Result<ExprValue> {
+        let mut sub_ctx = ctx.subscope(ctx.return_type());
+        let mut decls = Vec::new();
+        for item in &self.items {
+            match item {
+                Item::Variable(_) | Item::Pragma(_) => decls.extend(item.generate(&mut sub_ctx)?),
+                _ => {
+                    return Err(item.unrecoverable_error(
+                        "only object declarations are allowed in a declare expression",
+                    ));
+                }
+            }
+        }
+        let value = self.expr.generate(&mut sub_ctx)?;
+        generate_dependent(self.span(), vec![(self.expr.span(), value)], move |codes| {
+            let mut stmts = decls.clone();
+            stmts.extend(codes.into_iter().map(c::Stmt::from));
+            c::Expr::StmtExpr(stmts)
+        })
+    }
+}
+
+/// The value of a conditional expression with the values of its `dependent`
+/// expressions, one for each type all of them can have. `combine` builds the code of
+/// the conditional expression from the code of the dependent expressions.
+///
+/// A type other than a universal type is preferred, like `(if C then 1 else X)` is of
+/// the type of `X`. If all dependent expressions need the type from the context (like
+/// aggregates), so does the conditional expression.
+fn generate_dependent(
+    span: Span,
+    dependent: Vec<(Span, ExprValue)>,
+    combine: impl Fn(Vec<c::Expr>) -> c::Expr + 'static,
+) -> Result<ExprValue> {
+    let mut types = Vec::<Type>::new();
+    for value in dependent.iter().map(|(_, value)| value) {
+        let values = match value {
+            ExprValue::Distinct(value) => std::slice::from_ref(value),
+            ExprValue::Ambiguous(values) => values.as_slice(),
+            ExprValue::Dynamic(_) => continue,
+        };
+        for value in values {
+            if !types
+                .iter()
+                .any(|ty| ty.can_assign(&value.ty) && value.ty.can_assign(ty))
+            {
+                types.push(value.ty.clone());
+            }
+        }
+    }
+    let with_type = move |ty: &Type| {
+        let codes = dependent
+            .iter()
+            .map(|(span, value)| Ok(value.clone().filter_type(span, ty)?.with_check(ty)))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(SingleExprValue {
+            ty: ty.clone(),
+            perm: Permission::Read,
+            code: combine(codes),
+            value: None,
+            subprogram: None,
+        })
+    };
+
+    if types.is_empty() {
+        return Ok(DynamicExprValue::new(span, move |ty| Ok(with_type(ty)?.into())).into());
+    }
+
+    let mut best_err: Option<Error> = None;
+    let mut values = Vec::new();
+    for ty in &types {
+        match with_type(ty) {
+            Ok(value) => values.push(value),
+            Err(err) => {
+                if best_err
+                    .as_ref()
+                    .is_none_or(|best| err.rank() > best.rank())
+                {
+                    best_err = Some(err);
+                }
+            }
+        }
+    }
+    if values.iter().any(|value| !value.ty.is_universal_integer()) {
+        values.retain(|value| !value.ty.is_universal_integer());
+    }
+    ExprValue::new(values).ok_or_else(|| {
+        best_err.unwrap_or_else(|| span.unrecoverable_error("no common type of the expressions"))
+    })
+}
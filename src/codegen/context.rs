@@ -1,100 +1,603 @@
 use std::{
-    fmt,
+    borrow::Cow,
+    cell::Cell,
+    collections::{BTreeMap, BTreeSet, VecDeque},
     ops::{Deref, DerefMut},
+    path::PathBuf,
+    rc::Rc,
 };
 
-use super::{CCode, Scope, Type};
+use crate::{
+    parser::Item,
+    tokenizer::{Ident, Span},
+    Diagnostics, Error, ErrorKind, Result,
+};
+
+use super::{
+    c,
+    contract::{AssertionPolicy, Contract},
+    output::{Elaborate, Output, UnitCode, UnitPart, With},
+    pragma::Linkage,
+    ExprValue, Language, Path, Scope, SingleExprValue, Target, Type, Value,
+};
 
 pub struct Base {
     inner: Inner,
 }
 
 impl Base {
-    pub fn context(&mut self) -> Context {
+    pub fn context(&mut self) -> Context<'_> {
         Context {
             inner: &mut self.inner,
             scope: Default::default(),
             return_type: None,
+            return_object: None,
+            postcondition: None,
+            function_result: None,
+            handler: false,
+            path: Path::default(),
+            global: true,
+            frame: None,
         }
     }
+
+    pub fn into_output(self) -> Output {
+        self.inner.output
+    }
 }
 
 pub struct Context<'a> {
     inner: &'a mut Inner,
     scope: Scope<'a>,
     return_type: Option<Type>,
+
+    /// The object of the enclosing extended return statement, returned by `return;`.
+    return_object: Option<c::Expr>,
+
+    /// Postcondition checks of the current subprogram, before it returns.
+    postcondition: Option<Rc<Vec<c::Stmt>>>,
+
+    /// The function and the value of its `'Result` attribute, in its postcondition.
+    function_result: Option<(Ident, SingleExprValue)>,
+
+    /// In an exception handler, where `raise;` raises the handled exception again.
+    handler: bool,
+    path: Path,
+    global: bool,
+    frame: Option<Rc<Frame>>,
+}
+
+/// Frame of a subprogram body.
+///
+/// A subprogram declaring nested subprograms stores pointers to its parameters and objects
+/// in a local array `annabella_frame`. Nested subprograms get this array as the additional
+/// parameter `annabella_link` (their static link) and access the objects of the enclosing
+/// subprograms through it. Slot 0 holds the static link of the subprogram itself.
+#[derive(Debug)]
+pub struct Frame {
+    /// Nesting level, 1 for library level subprograms.
+    level: usize,
+
+    /// Number of allocated slots, `None` if the subprogram declares no nested subprograms.
+    slots: Option<Cell<usize>>,
+}
+
+/// Location of an object in the frame of the subprogram declaring it.
+#[derive(Debug, Clone, Copy)]
+pub struct FrameSlot {
+    pub level: usize,
+    pub index: usize,
+}
+
+impl Frame {
+    /// The frame array of the enclosing subprogram at `level`, seen from a subprogram
+    /// at `current` level.
+    pub fn link(current: usize, level: usize) -> c::Expr {
+        assert!(
+            (1..=current).contains(&level),
+            "no frame at level {level} in level {current}"
+        );
+        if level == current {
+            return c::Expr::ident("annabella_frame");
+        }
+        let mut link = c::Expr::ident("annabella_link");
+        for _ in level + 1..current {
+            link = link
+                .index(c::Expr::int(0))
+                .cast(c::Type::void().pointer().pointer());
+        }
+        link
+    }
 }
 
 #[derive(Default)]
 struct Inner {
-    includes: Vec<&'static str>,
-    // types: Vec<CCode>,
-    functions: Vec<CCode>,
-    main: Vec<CCode>,
+    output: Output,
+
+    /// Index and part of the library unit receiving the generated code.
+    current_unit: Option<(usize, UnitPart)>,
+    search_path: Vec<PathBuf>,
+    loaded_units: BTreeSet<String>,
+
+    /// Sources of library unit bodies, loaded after the specifications they depend on.
+    pending_bodies: Option<VecDeque<(PathBuf, String)>>,
+
+    /// C names and conventions of the imported and exported subprograms, by mangled name.
+    linkages: BTreeMap<Box<str>, Linkage>,
+
+    /// Contracts of the subprogram declarations, by mangled name.
+    contracts: BTreeMap<Box<str>, Rc<Contract>>,
+
+    /// The default assertion policy and the policies of assertion kinds like `pre`.
+    assertion_policy: AssertionPolicy,
+    assertion_policies: BTreeMap<Box<str>, AssertionPolicy>,
+    target: Target,
+    diagnostics: Diagnostics,
 }
 
 impl Context<'_> {
-    pub fn base() -> Base {
+    pub fn base(target: Target, language: Language, diagnostics: Diagnostics) -> Base {
         Base {
-            inner: Inner::default(),
+            inner: Inner {
+                output: Output {
+                    language,
+                    ..Default::default()
+                },
+                target,
+                diagnostics,
+                ..Default::default()
+            },
         }
     }
 
     pub fn push_include(&mut self, include: &'static str) {
-        self.inner.includes.push(include);
+        let includes = &mut self.inner.output.includes;
+        if !includes.contains(&include) {
+            includes.push(include);
+        }
+    }
+
+    /// Generate the code of a part of the library unit `name` (in lowercase) in `f`.
+    ///
+    /// The unit is created on first use, its files are named after `file_name`.
+    pub fn in_unit<R>(
+        &mut self,
+        name: &str,
+        file_name: &str,
+        part: UnitPart,
+        f: impl FnOnce(&mut Self) -> R,
+    ) -> R {
+        let index = self.unit_index(name).unwrap_or_else(|| {
+            let units = &mut self.inner.output.units;
+            units.push(UnitCode::new(name.into(), file_name.into()));
+            units.len() - 1
+        });
+        let outer = self.inner.current_unit.replace((index, part));
+        let result = f(self);
+        self.inner.current_unit = outer;
+        result
+    }
+
+    /// Name and part of the library unit receiving the generated code.
+    pub fn current_unit(&self) -> Option<(&str, UnitPart)> {
+        let (index, part) = self.inner.current_unit?;
+        Some((&self.inner.output.units[index].name, part))
+    }
+
+    /// Record that the current unit uses the unit `name`, in its specification or only
+    /// in its body.
+    pub fn add_dependency(&mut self, name: &str, in_spec: bool) {
+        let Some(index) = self.unit_index(name) else {
+            return;
+        };
+        let (current, _) = self.inner.current_unit.expect("no current unit");
+        let unit = &mut self.inner.output.units[current];
+        if index == current || unit.spec_deps.contains(&index) {
+            return;
+        }
+        if in_spec {
+            unit.body_deps.retain(|&dep| dep != index);
+            unit.spec_deps.push(index);
+        } else if !unit.body_deps.contains(&index) {
+            unit.body_deps.push(index);
+        }
+    }
+
+    /// Record a `with` clause of the current unit for the loaded unit `name`.
+    pub fn add_with(&mut self, name: &str, span: Span) {
+        let (_, part) = self.unit();
+        self.add_dependency(name, part == UnitPart::Spec);
+        let unit = self.unit_index(name).expect("unit is loaded");
+        self.unit().0.withs.push(With {
+            unit,
+            part,
+            span,
+            elaborate: None,
+        });
+    }
+
+    /// Apply `pragma Elaborate` or `pragma Elaborate_All` to the `with` clause of the
+    /// current unit for `name`.
+    pub(super) fn set_elaborate(&mut self, name: &str, elaborate: Elaborate, span: Span) {
+        let index = self.unit_index(name);
+        let (unit, part) = self.unit();
+        let with = unit
+            .withs
+            .iter_mut()
+            .find(|with| Some(with.unit) == index && with.part == part)
+            .expect("unit in a `with` clause");
+        with.elaborate = Some((elaborate, span));
+    }
+
+    /// The C name and convention of the subprogram with the mangled name `name`, if it
+    /// is imported or exported.
+    pub(super) fn linkage(&self, name: &c::Ident) -> Option<&Linkage> {
+        self.inner.linkages.get(name.as_str())
+    }
+
+    pub(super) fn set_linkage(&mut self, name: &c::Ident, linkage: Linkage) {
+        self.inner.linkages.insert(name.as_str().into(), linkage);
+    }
+
+    /// The contract of the subprogram declaration with the mangled name `name`.
+    pub(super) fn contract(&self, name: &c::Ident) -> Option<Rc<Contract>> {
+        self.inner.contracts.get(name.as_str()).cloned()
+    }
+
+    pub(super) fn set_contract(&mut self, name: &c::Ident, contract: Rc<Contract>) {
+        self.inner.contracts.insert(name.as_str().into(), contract);
+    }
+
+    /// The assertion policy for the assertion `kind`, like `assert` or `pre`.
+    pub fn assertion_policy(&self, kind: &str) -> AssertionPolicy {
+        self.inner
+            .assertion_policies
+            .get(kind)
+            .copied()
+            .unwrap_or(self.inner.assertion_policy)
+    }
+
+    /// Set the policy of the assertion `kind`, or of all kinds.
+    pub fn set_assertion_policy(&mut self, kind: Option<&str>, policy: AssertionPolicy) {
+        match kind {
+            Some(kind) => {
+                self.inner.assertion_policies.insert(kind.into(), policy);
+            }
+            None => {
+                self.inner.assertion_policy = policy;
+                self.inner.assertion_policies.clear();
+            }
+        }
+    }
+
+    /// Report a warning, the code at `span` is ignored.
+    pub fn warn(&mut self, span: Span, msg: impl Into<Cow<'static, str>>) {
+        self.inner.output.warnings.push(Error {
+            span,
+            msg: msg.into(),
+            kind: ErrorKind::Unimplemented,
+            recoverable: true,
+            labels: Vec::new(),
+            notes: Vec::new(),
+            node: None,
+            cascade: false,
+            arity: false,
+        });
+    }
+
+    /// The errors of the declarations and statements which are left out.
+    pub fn diagnostics(&self) -> &Diagnostics {
+        &self.inner.diagnostics
+    }
+
+    /// Generate a declaration or a statement of kind `node`, its error is reported and
+    /// its code left out so that the following ones are still generated.
+    pub fn recover(
+        &mut self,
+        node: &'static str,
+        generate: impl FnOnce(&mut Self) -> Result<Vec<c::Stmt>>,
+    ) -> Vec<c::Stmt> {
+        generate(self).unwrap_or_else(|err| {
+            self.inner.diagnostics.report(err.in_node(node));
+            Vec::new()
+        })
+    }
+
+    /// Generate a declaration, after an error its name is declared as poisoned so that
+    /// its uses do not report that it is not in scope.
+    pub fn recover_item(
+        &mut self,
+        item: &Item,
+        generate: impl FnOnce(&mut Self) -> Result<Vec<c::Stmt>>,
+    ) -> Vec<c::Stmt> {
+        generate(self).unwrap_or_else(|err| {
+            self.inner.diagnostics.report(err.in_node(item.kind()));
+            if let Some(name) = item.defining_name() {
+                self.poison(name);
+            }
+            Vec::new()
+        })
+    }
+
+    /// Declare `name` as poisoned after an error of its declaration, unless it is
+    /// already declared.
+    pub fn poison(&mut self, name: &Ident) {
+        self.scope.get_or_insert(name, || Value::Poisoned);
+    }
+
+    /// Record `pragma Elaborate_Body` for the current unit.
+    pub fn set_elaborate_body(&mut self, span: Span) {
+        self.unit().0.elaborate_body = Some(span);
+    }
+
+    /// Record `pragma Preelaborate` for the current unit.
+    pub fn set_preelaborate(&mut self, span: Span) {
+        self.unit().0.preelaborate = Some(span);
+    }
+
+    /// Record that the current unit uses all units created before it.
+    pub fn add_preceding_dependencies(&mut self) {
+        let (current, _) = self.inner.current_unit.expect("no current unit");
+        let unit = &mut self.inner.output.units[current];
+        unit.body_deps.retain(|&dep| dep >= current);
+        for index in 1..current {
+            if !unit.spec_deps.contains(&index) {
+                unit.spec_deps.push(index);
+            }
+        }
+    }
+
+    fn unit_index(&self, name: &str) -> Option<usize> {
+        self.inner
+            .output
+            .units
+            .iter()
+            .position(|unit| unit.name == name)
     }
 
-    pub fn push_type(&mut self, code: CCode) {
-        // self.inner.types.push(code);
-        self.inner.functions.push(code);
+    fn unit(&mut self) -> (&mut UnitCode, UnitPart) {
+        let (current, part) = self.inner.current_unit.expect("no current unit");
+        (&mut self.inner.output.units[current], part)
     }
 
-    pub fn push_function(&mut self, code: CCode) {
-        self.inner.functions.push(code);
+    /// Add hand written C types and prototypes of the runtime library.
+    pub fn push_runtime_header(&mut self, code: &'static str) {
+        self.unit().0.runtime_headers.push(code);
     }
 
-    pub fn push_main(&mut self, code: CCode) {
-        self.inner.main.push(code);
+    /// Add hand written C code of the runtime library.
+    pub fn push_runtime(&mut self, code: &'static str) {
+        self.unit().0.runtime.push(code);
     }
 
-    pub fn subscope(&mut self, return_type: Option<Type>) -> Context {
+    pub fn push_search_path(&mut self, path: PathBuf) {
+        self.inner.search_path.push(path);
+    }
+
+    pub fn search_path(&self) -> &[PathBuf] {
+        &self.inner.search_path
+    }
+
+    pub fn target(&self) -> &Target {
+        &self.inner.target
+    }
+
+    pub fn language(&self) -> Language {
+        self.inner.output.language
+    }
+
+    pub fn is_unit_loaded(&self, name: &str) -> bool {
+        self.inner.loaded_units.contains(name)
+    }
+
+    /// Mark a library unit as loaded, returns `false` if it was already loaded.
+    pub fn mark_unit_loaded(&mut self, name: String) -> bool {
+        self.inner.loaded_units.insert(name)
+    }
+
+    /// Start loading library units, returns `false` if they are already being loaded.
+    pub fn start_loading_units(&mut self) -> bool {
+        let pending = &mut self.inner.pending_bodies;
+        let is_first = pending.is_none();
+        pending.get_or_insert_with(VecDeque::new);
+        is_first
+    }
+
+    /// Load the body of a library unit once all specifications are loaded.
+    pub fn defer_body(&mut self, path: PathBuf, source: String) {
+        let pending = self.inner.pending_bodies.as_mut();
+        pending.expect("loading units").push_back((path, source));
+    }
+
+    /// Next pending body, `None` when all units are loaded.
+    pub fn next_pending_body(&mut self) -> Option<(PathBuf, String)> {
+        let body = self.inner.pending_bodies.as_mut()?.pop_front();
+        if body.is_none() {
+            self.inner.pending_bodies = None;
+        }
+        body
+    }
+
+    pub fn push_type(&mut self, decl: c::Decl) {
+        let (unit, part) = self.unit();
+        unit.part_mut(part).types.push(decl);
+    }
+
+    /// Add a function, its prototype is declared after the types of the unit.
+    pub fn push_function(&mut self, decl: c::Decl) {
+        let (unit, part) = self.unit();
+        match decl {
+            c::Decl::Function(function) if function.body.is_none() => {
+                unit.push_prototype(part, function);
+            }
+            c::Decl::Function(function) => {
+                unit.push_prototype(part, function.prototype());
+                unit.bodies.push(c::Decl::Function(function));
+            }
+            decl => unit.bodies.push(decl),
+        }
+    }
+
+    /// Add the code generated for items at file scope, which only declares objects.
+    pub fn push_declarations(&mut self, stmts: Vec<c::Stmt>) {
+        for stmt in stmts {
+            let c::Stmt::Decl(decl) = stmt else {
+                unreachable!("statement at file scope: {stmt:?}")
+            };
+            let (unit, part) = self.unit();
+            unit.part_mut(part).globals.push(decl);
+        }
+    }
+
+    /// Add elaboration code of the current unit, or the call of the main subprogram.
+    pub fn push_main(&mut self, stmts: impl IntoIterator<Item = c::Stmt>) {
+        match self.inner.current_unit {
+            Some((index, part)) => {
+                let unit = &mut self.inner.output.units[index];
+                unit.part_mut(part).elaboration.extend(stmts);
+            }
+            None => self.inner.output.main.extend(stmts),
+        }
+    }
+
+    pub fn subscope(&mut self, return_type: Option<Type>) -> Context<'_> {
         Context {
             inner: self.inner,
             scope: self.scope.subscope(),
             return_type,
+            return_object: self.return_object.clone(),
+            postcondition: self.postcondition.clone(),
+            function_result: self.function_result.clone(),
+            handler: self.handler,
+            path: self.path.clone(),
+            global: false,
+            frame: self.frame.clone(),
+        }
+    }
+
+    /// Create a subscope for the declarations of the package `name`.
+    pub fn package_scope(&mut self, path: Vec<Ident>) -> Context<'_> {
+        Context {
+            inner: self.inner,
+            scope: self.scope.subscope(),
+            return_type: None,
+            return_object: None,
+            postcondition: None,
+            function_result: None,
+            handler: false,
+            path: Path::package(path),
+            global: true,
+            frame: None,
         }
     }
 
+    /// Start the body of the subprogram `name`, nested in the current subprogram if any.
+    ///
+    /// Only subprograms declaring nested subprograms need a frame array.
+    pub fn enter_subprogram(&mut self, name: &Ident, has_nested: bool) {
+        self.path.push(name.clone());
+        self.return_object = None;
+        self.handler = false;
+        self.frame = Some(Rc::new(Frame {
+            level: self.level() + 1,
+            slots: has_nested.then(|| Cell::new(1)),
+        }));
+    }
+
+    /// Nesting level of the current subprogram, 0 outside of subprograms.
+    pub fn level(&self) -> usize {
+        self.frame.as_ref().map_or(0, |frame| frame.level)
+    }
+
+    /// Number of slots of the frame array, `None` if the subprogram needs no frame array.
+    pub fn frame_size(&self) -> Option<usize> {
+        self.frame.as_ref()?.slots.as_ref().map(Cell::get)
+    }
+
+    /// Make an object of the current subprogram accessible to nested subprograms.
+    ///
+    /// Returns the slot and the code storing the address of the object in the frame array.
+    pub fn frame_slot(&self, name: &c::Expr) -> (Option<FrameSlot>, Option<c::Stmt>) {
+        let Some(frame) = &self.frame else {
+            return (None, None);
+        };
+        let Some(slots) = &frame.slots else {
+            return (None, None);
+        };
+        let index = slots.get();
+        slots.set(index + 1);
+        let slot = FrameSlot {
+            level: frame.level,
+            index,
+        };
+        let store = c::Expr::ident("annabella_frame")
+            .index(c::Expr::int(index as i128))
+            .assign(name.clone().addr_of().cast(c::Type::void().pointer()));
+        (Some(slot), Some(store.into()))
+    }
+
+    /// Lookup an identifier used in an expression, see [`Scope::get_expr_value`].
+    pub(super) fn get_expr_value(&self, ident: &Ident) -> Result<ExprValue> {
+        self.scope.get_expr_value(ident, self.level())
+    }
+
     pub fn return_type(&self) -> Option<Type> {
         self.return_type.clone()
     }
-}
 
-impl fmt::Display for Context<'_> {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        for include in &self.inner.includes {
-            writeln!(f, "#include {include}")?;
-        }
-        writeln!(f)?;
+    pub fn return_object(&self) -> Option<c::Expr> {
+        self.return_object.clone()
+    }
+
+    /// Set the object of the extended return statement of this context.
+    pub fn set_return_object(&mut self, object: c::Expr) {
+        self.return_object = Some(object);
+    }
 
-        // for type_ in &self.inner.types {
-        //     type_.fmt(f)?;
-        // }
-        // writeln!(f)?;
+    pub fn in_handler(&self) -> bool {
+        self.handler
+    }
 
-        for function in &self.inner.functions {
-            function.fmt(f)?;
-            writeln!(f)?;
-        }
+    /// Start the statements of an exception handler.
+    pub fn enter_handler(&mut self) {
+        self.handler = true;
+    }
 
-        let main = &self.inner.main;
-        c_code! {
-            int main() {
-                #(#main)*
-                return 0;
-            }
-        }
-        .fmt(f)
+    pub fn postcondition(&self) -> Option<Rc<Vec<c::Stmt>>> {
+        self.postcondition.clone()
+    }
+
+    /// Set the postcondition checks of the subprogram body of this context.
+    pub fn set_postcondition(&mut self, checks: Vec<c::Stmt>) {
+        self.postcondition = Some(checks)
+            .filter(|checks| !checks.is_empty())
+            .map(Rc::new);
+    }
+
+    /// The value of `F'Result` for the function `F`, only in its postcondition.
+    pub(super) fn function_result(&self, function: &Ident) -> Option<&SingleExprValue> {
+        self.function_result
+            .as_ref()
+            .filter(|(name, _)| name == function)
+            .map(|(_, value)| value)
+    }
+
+    pub(super) fn set_function_result(&mut self, function: &Ident, value: SingleExprValue) {
+        self.function_result = Some((function.clone(), value));
+    }
+
+    /// Full name of the enclosing package, used to build unique C identifiers.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Whether declarations are generated at file scope, outside of any C function.
+    pub fn is_global(&self) -> bool {
+        self.global
+    }
+
+    pub fn into_values(self) -> BTreeMap<Box<str>, Value> {
+        self.scope.into_values()
     }
 }
 
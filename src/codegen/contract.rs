@@ -0,0 +1,162 @@
+use std::rc::Rc;
+
+use crate::{
+    parser::{AspectSpecification, Expr, FunctionSpec},
+    tokenizer::{Span, Spanned},
+    Result,
+};
+
+use super::{c, CodeGenExpr, Context, Permission, SingleExprValue, Type};
+
+/// Whether assertions, preconditions and postconditions are checked.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AssertionPolicy {
+    #[default]
+    Check,
+    Ignore,
+}
+
+impl AssertionPolicy {
+    pub(super) fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "check" => Some(Self::Check),
+            "ignore" => Some(Self::Ignore),
+            _ => None,
+        }
+    }
+}
+
+/// Preconditions and postconditions of a subprogram, from the aspects of its
+/// declaration and of its body.
+#[derive(Debug, Default)]
+pub(super) struct Contract {
+    pre: Vec<Expr>,
+    post: Vec<Expr>,
+}
+
+impl Contract {
+    /// The contract from the aspects, other aspects are ignored with a warning.
+    pub fn new(aspects: Option<&AspectSpecification>, ctx: &mut Context) -> Result<Self> {
+        let mut contract = Self::default();
+        for aspect in aspects.into_iter().flat_map(AspectSpecification::iter) {
+            let conditions = match &*aspect.name.name {
+                "pre" => &mut contract.pre,
+                "post" => &mut contract.post,
+                // aspects without effect on the generated code
+                "inline" | "inline_always" | "no_return" | "unreferenced" => continue,
+                _ => {
+                    ctx.warn(
+                        aspect.name.span(),
+                        format!("unknown aspect `{}` ignored", aspect.name),
+                    );
+                    continue;
+                }
+            };
+            let value = aspect
+                .value()
+                .ok_or_else(|| aspect.name.unrecoverable_error("expected a condition"))?;
+            conditions.push(value.clone());
+        }
+        Ok(contract)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pre.is_empty() && self.post.is_empty()
+    }
+
+    /// Check the preconditions on entry of the subprogram body.
+    pub fn generate_pre(&self, ctx: &mut Context) -> Result<Vec<c::Stmt>> {
+        let mut stmts = Vec::new();
+        for cond in &self.pre {
+            stmts.extend(generate_check("pre", "precondition", cond, None, ctx)?);
+        }
+        Ok(stmts)
+    }
+
+    /// Check the postconditions before the subprogram `spec` returns, where
+    /// `spec'Result` is the variable `annabella_result`.
+    pub fn generate_post(&self, spec: &FunctionSpec, ctx: &mut Context) -> Result<Vec<c::Stmt>> {
+        let mut sub_ctx = ctx.subscope(ctx.return_type());
+        if let Some(ty) = sub_ctx.return_type() {
+            sub_ctx.set_function_result(
+                spec.ident(),
+                SingleExprValue {
+                    ty,
+                    perm: Permission::Read,
+                    code: c::Expr::ident("annabella_result"),
+                    value: None,
+                    subprogram: None,
+                },
+            );
+        }
+        let mut stmts = Vec::new();
+        for cond in &self.post {
+            stmts.extend(generate_check(
+                "post",
+                "postcondition",
+                cond,
+                None,
+                &mut sub_ctx,
+            )?);
+        }
+        Ok(stmts)
+    }
+}
+
+/// The contract of a subprogram body from its aspects, or else the contract of the
+/// declaration of the subprogram with the mangled name `name`.
+pub(super) fn subprogram_contract(
+    name: &c::Ident,
+    aspects: Option<&AspectSpecification>,
+    ctx: &mut Context,
+) -> Result<Rc<Contract>> {
+    let contract = Contract::new(aspects, ctx)?;
+    Ok(match ctx.contract(name) {
+        Some(declared) if contract.is_empty() => declared,
+        _ => Rc::new(contract),
+    })
+}
+
+/// Raise `Assertion_Error` if the condition `cond` of the assertion `kind` is false.
+///
+/// The message is `message`, or else the source text of the condition with its location.
+pub(super) fn generate_check(
+    kind: &str,
+    label: &str,
+    cond: &Expr,
+    message: Option<&Expr>,
+    ctx: &mut Context,
+) -> Result<Vec<c::Stmt>> {
+    if ctx.assertion_policy(kind) == AssertionPolicy::Ignore {
+        return Ok(Vec::new());
+    }
+    let check = cond.generate_to_boolean(ctx)?;
+    let message = match message {
+        Some(message) => {
+            let message = message.generate_with_type_and_check(&Type::string(), ctx)?;
+            c::Expr::ident("annabella_string_to_c").call([message])
+        }
+        None => c::Expr::str(failure_message(label, cond.span())),
+    };
+    Ok(vec![c::Stmt::If {
+        cond: c::Expr::unary(c::UnaryOp::Not, check),
+        then: vec![c::Expr::ident("annabella_raise_message")
+            .call([c::Expr::str("Ada.Assertions.Assertion_Error"), message])
+            .into()],
+        else_: None,
+    }])
+}
+
+/// Like `precondition `X > 0` failed at main.adb:4`.
+fn failure_message(label: &str, span: Span) -> String {
+    let mut message = format!("{label} failed");
+    if let Some(source) = span.source() {
+        message = format!("{label} `{source}` failed");
+    }
+    let file = span.filepath();
+    let file = file.as_ref().and_then(|path| path.file_name());
+    if let (Some(file), Some((line, ..))) = (file, span.lines().first()) {
+        message.push_str(&format!(" at {}:{line}", file.to_string_lossy()));
+    }
+    message
+}
@@ -1,32 +1,47 @@
-use std::iter;
+use std::{iter, rc::Rc};
 
 use crate::{
     parser::{
-        AggregateExpr, BaseName, BinaryOp, ComponentChoices, Expr, ExprBinary, ExprLit,
-        ExprShortCircuit, FunctionCall, LitChar, LitNumber, LitStr, Name, QualifiedExpr,
-        QualifiedExprValue, QualifiedExprValueExpr, RecordComponentAssociationList,
-        SelectedComponent, ShortCircuitOp,
+        AggregateExpr, AttributeReference, BaseName, BinaryOp, ComponentChoices, Expr, ExprBinary,
+        ExprLit, ExprShortCircuit, ExprUnary, FunctionCall, LitChar, LitNumber, LitStr, Name,
+        Parenthesized, QualifiedExpr, QualifiedExprValue, QualifiedExprValueExpr,
+        RecordComponentAssociationList, SelectedComponent, SelectorName, ShortCircuitOp, Slice,
+        UnaryOp,
     },
     tokenizer::{Ident, Span, Spanned},
     Result,
 };
 
 use super::{
-    ArgumentMode, CCode, CodeGenExpr, CompileTimeValue, Context, DynamicExprValue, ExprValue,
-    Permission, SingleExprValue, Type,
+    c, ArgumentMode, ArgumentType, CodeGenExpr, CompileTimeValue, Context, DynamicExprValue,
+    ExprValue, Frame, FunctionType, PackageValue, Permission, SingleExprValue, Type, Value,
 };
 
 impl CodeGenExpr for Expr {
-    #[expect(unused_variables)]
     fn generate(&self, ctx: &mut Context) -> Result<ExprValue> {
         match self {
             Self::Lit(expr) => expr.generate(ctx),
             Self::Name(expr) => expr.generate(ctx)?.implicit_dereference(ctx),
             Self::Qualified(expr) => expr.generate(ctx),
             Self::Aggregate(expr) => expr.generate(ctx),
-            Self::Unary(expr) => todo!(),
+            Self::Unary(expr) => expr.generate(ctx),
             Self::Binary(expr) => expr.generate(ctx),
             Self::ShortCircuit(expr) => expr.generate(ctx),
+            Self::Paren(expr) => match expr.generate(ctx)? {
+                // a parenthesized name is a value, not a variable
+                ExprValue::Dynamic(value) => Ok(value.into()),
+                value => value.flat_map(|value| {
+                    Ok(SingleExprValue {
+                        perm: Permission::Read,
+                        ..value
+                    }
+                    .into())
+                }),
+            },
+            Self::If(expr) => expr.generate(ctx),
+            Self::Case(expr) => expr.generate(ctx),
+            Self::Quantified(expr) => expr.generate(ctx),
+            Self::Declare(expr) => expr.generate(ctx),
         }
     }
 }
@@ -44,39 +59,117 @@ impl CodeGenExpr for ExprLit {
 impl CodeGenExpr for LitStr {
     fn generate(&self, _ctx: &mut Context) -> Result<ExprValue> {
         let str = self.str();
+        let ty = Type::string();
+        let len = str.len();
+        // C++ has no compound literals
+        let code = c::Expr::Compound(
+            ty.c_type(),
+            vec![
+                c::Expr::str(&*str).cast(c::Type::char().pointer()),
+                c::Expr::int(1),
+                c::Expr::int(len as i128),
+            ],
+        );
         Ok(SingleExprValue {
-            ty: Type::string(),
+            code,
+            ty,
             perm: Permission::Read,
-            code: c_code! { #str },
             value: Some(CompileTimeValue::String(str)),
+            subprogram: None,
         }
         .into())
     }
 }
 
 impl CodeGenExpr for LitChar {
-    fn generate(&self, _ctx: &mut Context) -> Result<ExprValue> {
+    /// A literal of `Character` or `Wide_Character`, or of any visible enumeration type
+    /// with the literal.
+    fn generate(&self, ctx: &mut Context) -> Result<ExprValue> {
         let char = self.char();
-        Ok(SingleExprValue {
-            ty: Type::character(),
-            perm: Permission::Read,
-            code: c_code! { #char },
-            value: Some(CompileTimeValue::Character(char)),
+        // characters outside of ASCII would be negative as C `char`
+        let code = if char.is_ascii() {
+            c::Expr::Char(char)
+        } else {
+            c::Expr::int(i128::from(u32::from(char)))
+        };
+        let mut values = vec![
+            SingleExprValue {
+                ty: Type::character(),
+                perm: Permission::Read,
+                code: code.clone(),
+                value: Some(CompileTimeValue::Character(char)),
+                subprogram: None,
+            },
+            SingleExprValue {
+                ty: Type::wide_character(),
+                perm: Permission::Read,
+                code,
+                value: Some(CompileTimeValue::Character(char)),
+                subprogram: None,
+            },
+        ];
+        if let Ok(literals) = ctx.get_expr_value(&self.ident()) {
+            match literals.implicit_dereference(ctx)? {
+                ExprValue::Distinct(value) => values.push(value),
+                ExprValue::Ambiguous(literals) => values.extend(literals),
+                ExprValue::Dynamic(_) => unreachable!(),
+            }
         }
-        .into())
+        Ok(ExprValue::new(values).unwrap())
     }
 }
 
 impl CodeGenExpr for LitNumber {
     fn generate(&self, _ctx: &mut Context) -> Result<ExprValue> {
-        let num = self.number();
-        Ok(SingleExprValue {
+        if self.is_based() {
+            return Err(self.unimplemented_error("based literals not yet implemented"));
+        }
+        if self.is_real() {
+            let value = self.number();
+            if !f64::is_finite(value) {
+                return Err(self.unrecoverable_error("value out of range of universal real"));
+            }
+            return Ok(SingleExprValue::universal_real(value).into());
+        }
+        Ok(SingleExprValue::universal_integer(self.number()).into())
+    }
+}
+
+impl SingleExprValue {
+    fn universal_integer(value: i64) -> Self {
+        Self {
             ty: Type::integer(),
             perm: Permission::Read,
-            code: c_code! { #num },
-            value: Some(CompileTimeValue::Integer(num)),
+            code: c::Expr::int(value),
+            value: Some(CompileTimeValue::Integer(value)),
+            subprogram: None,
+        }
+    }
+
+    fn universal_real(value: f64) -> Self {
+        Self {
+            ty: Type::real(),
+            perm: Permission::Read,
+            code: c::Expr::float(value),
+            value: Some(CompileTimeValue::Real(value)),
+            subprogram: None,
+        }
+    }
+}
+
+impl ExprValue {
+    fn as_universal_integer(&self) -> Option<&SingleExprValue> {
+        match self {
+            Self::Distinct(value) if value.ty.is_universal_integer() => Some(value),
+            _ => None,
+        }
+    }
+
+    fn as_universal_real(&self) -> Option<&SingleExprValue> {
+        match self {
+            Self::Distinct(value) if value.ty.is_universal_real() => Some(value),
+            _ => None,
         }
-        .into())
     }
 }
 
@@ -85,7 +178,9 @@ impl CodeGenExpr for Name {
         match self {
             Self::Base(name) => name.generate(ctx),
             Self::Select(name) => name.generate(ctx),
+            Self::Slice(name) => name.generate(ctx),
             Self::FunctionCall(name) => name.generate(ctx),
+            Self::Attribute(name) => name.generate(ctx),
         }
     }
 
@@ -93,17 +188,70 @@ impl CodeGenExpr for Name {
         match self {
             Self::Base(name) => name.generate_type(ctx),
             Self::Select(name) => name.generate_type(ctx),
+            Self::Slice(name) => name.generate_type(ctx),
             Self::FunctionCall(name) => name.generate_type(ctx),
+            Self::Attribute(name) => name.generate_type(ctx),
         }
     }
 }
 
-impl CodeGenExpr for BaseName {
-    fn generate(&self, ctx: &mut Context) -> Result<ExprValue> {
+impl Name {
+    /// Resolve the name if it denotes a package.
+    fn resolve_package(&self, ctx: &Context) -> Result<Option<Rc<PackageValue>>> {
+        Ok(match self {
+            Self::Base(BaseName::Ident(ident)) => match ctx.get(ident) {
+                Ok(Value::Package(package)) => Some(package.clone()),
+                _ => None,
+            },
+            Self::Select(name) => match (name.prefix.resolve_package(ctx)?, &name.name) {
+                (Some(package), SelectorName::Ident(ident)) => package.find_package(ident),
+                (None, _) => None,
+            },
+            Self::Slice(_) | Self::FunctionCall(_) | Self::Attribute(_) => None,
+        })
+    }
+
+    /// Resolve the name if it denotes a type.
+    fn resolve_type(&self, ctx: &Context) -> Result<Option<Type>> {
         Ok(match self {
-            BaseName::Ident(ident) => ctx.get(ident)?.expr_value(),
+            Self::Base(BaseName::Ident(ident)) => ctx.get(ident).ok().and_then(Type::from_value),
+            Self::Select(name) => match (name.prefix.resolve_package(ctx)?, &name.name) {
+                (Some(package), SelectorName::Ident(ident)) => {
+                    package.get(ident).ok().and_then(Type::from_value)
+                }
+                (None, _) => None,
+            },
+            Self::Slice(_) | Self::FunctionCall(_) | Self::Attribute(_) => None,
         })
     }
+}
+
+impl Name {
+    /// Resolve the name if it denotes an exception, returns its expanded name.
+    pub(super) fn resolve_exception(&self, ctx: &Context) -> Result<Option<Box<str>>> {
+        let exception = |value: &Value| match value {
+            Value::Exception(exception) => Some(exception.name.clone()),
+            _ => None,
+        };
+        Ok(match self {
+            Self::Base(BaseName::Ident(ident)) => ctx.get(ident).ok().and_then(exception),
+            Self::Select(name) => match (name.prefix.resolve_package(ctx)?, &name.name) {
+                (Some(package), SelectorName::Ident(ident)) => {
+                    package.get(ident).ok().and_then(exception)
+                }
+                (None, _) => None,
+            },
+            Self::Slice(_) | Self::FunctionCall(_) | Self::Attribute(_) => None,
+        })
+    }
+}
+
+impl CodeGenExpr for BaseName {
+    fn generate(&self, ctx: &mut Context) -> Result<ExprValue> {
+        match self {
+            BaseName::Ident(ident) => ctx.get_expr_value(ident),
+        }
+    }
 
     fn generate_type(&self, ctx: &mut Context) -> Result<Type> {
         Ok(match self {
@@ -121,6 +269,7 @@ impl CodeGenExpr for QualifiedExpr {
             perm: Permission::Read,
             code,
             value: None,
+            subprogram: None,
         }
         .into())
     }
@@ -169,7 +318,7 @@ impl CodeGenExpr for RecordComponentAssociationList {
                 return Err(span.unrecoverable_error("expected to be a record"));
             };
 
-            let mut values: Vec<Option<CCode>> = vec![None; record.fields.len()];
+            let mut values: Vec<Option<c::Expr>> = vec![None; record.fields.len()];
             for (i, (choices, span, expr)) in associations.iter().enumerate() {
                 let expr = |ty| Ok(expr.clone().filter_type(span, ty)?.with_check(ty));
                 match choices {
@@ -193,20 +342,28 @@ impl CodeGenExpr for RecordComponentAssociationList {
                     }
                 }
             }
-            let values = iter::zip(record.fields.values(), values).map(|(field, v)| {
-                let ty = &field.ty;
-                v.unwrap_or_else(|| c_code! { (#ty){} })
-            });
+            // in the order of the struct for C++, the missing fields are zero
+            let values = record
+                .c_order
+                .iter()
+                .filter_map(|&i| {
+                    let field = &record.fields[i];
+                    let value = values[i].take()?;
+                    // C++ doesn't allow narrowing conversions to the bit-field type
+                    let value = match &field.bit_field {
+                        Some(ty) => value.cast(ty.clone()),
+                        None => value,
+                    };
+                    Some(c::Expr::designated(field.ident.clone(), value))
+                })
+                .collect();
 
             Ok(SingleExprValue {
                 ty: ty.clone(),
                 perm: Permission::Read,
-                code: c_code! {
-                    (#ty){
-                        #(#values,)*
-                    }
-                },
+                code: c::Expr::Compound(ty.c_type(), values),
                 value: None,
+                subprogram: None,
             }
             .into())
         })
@@ -216,10 +373,277 @@ impl CodeGenExpr for RecordComponentAssociationList {
 
 impl CodeGenExpr for SelectedComponent {
     fn generate(&self, ctx: &mut Context) -> Result<ExprValue> {
+        if let Some(package) = self.prefix.resolve_package(ctx)? {
+            let SelectorName::Ident(ident) = &self.name;
+            return package.get(ident)?.expr_value(ident, ctx.level());
+        }
         self.prefix
             .generate(ctx)?
             .flat_map(|prefix| prefix.ty.select(&prefix, &self.name))
     }
+
+    fn generate_type(&self, ctx: &mut Context) -> Result<Type> {
+        let Some(package) = self.prefix.resolve_package(ctx)? else {
+            return Err(self.unrecoverable_error("not a type name"));
+        };
+        let SelectorName::Ident(ident) = &self.name;
+        Type::from_value(package.get(ident)?)
+            .ok_or_else(|| ident.unrecoverable_error("not a type name"))
+    }
+}
+
+impl CodeGenExpr for Slice {
+    fn generate(&self, ctx: &mut Context) -> Result<ExprValue> {
+        let prefix = generate_string_prefix(&self.prefix, ctx)?;
+        let integer = Type::standard_integer(ctx)?;
+        let first = self
+            .range
+            .start
+            .generate_with_type_and_check(&integer, ctx)?;
+        let last = self.range.end.generate_with_type_and_check(&integer, ctx)?;
+        Ok(SingleExprValue {
+            ty: prefix.ty.clone(),
+            perm: prefix.perm,
+            code: c::Expr::ident("annabella_string_slice").call([prefix.code, first, last]),
+            value: None,
+            subprogram: None,
+        }
+        .into())
+    }
+}
+
+pub(super) fn generate_string_prefix(prefix: &Name, ctx: &mut Context) -> Result<SingleExprValue> {
+    prefix
+        .generate(ctx)?
+        .implicit_dereference(ctx)?
+        .filter_type(prefix, &Type::string())
+}
+
+/// Indexed component of a string, `S (I)`.
+fn generate_indexed_component(
+    prefix: SingleExprValue,
+    args: &Parenthesized<Expr>,
+    ctx: &mut Context,
+) -> Result<ExprValue> {
+    let mut iter = args.iter();
+    let (Some(index), None) = (iter.next(), iter.next()) else {
+        return Err(args.unrecoverable_error("expected exactly one index"));
+    };
+    let index = index.generate_with_type_and_check(&Type::standard_integer(ctx)?, ctx)?;
+    Ok(SingleExprValue {
+        ty: Type::character(),
+        perm: prefix.perm,
+        code: c::Expr::ident("annabella_string_element")
+            .call([prefix.code, index])
+            .cast(Type::character().c_type().pointer())
+            .deref(),
+        value: None,
+        subprogram: None,
+    }
+    .into())
+}
+
+impl CodeGenExpr for AttributeReference {
+    fn generate(&self, ctx: &mut Context) -> Result<ExprValue> {
+        match &*self.designator.name {
+            "first" | "last" if self.prefix.resolve_type(ctx)?.is_some() => {
+                let (ty, first, last) = self.generate_range(ctx)?;
+                Ok(SingleExprValue {
+                    ty,
+                    perm: Permission::Read,
+                    code: if &*self.designator.name == "first" {
+                        first
+                    } else {
+                        last
+                    },
+                    value: None,
+                    subprogram: None,
+                }
+                .into())
+            }
+            "length" | "first" | "last" => {
+                let prefix = generate_string_prefix(&self.prefix, ctx)?;
+                let code = match &*self.designator.name {
+                    "length" => c::Expr::ident("annabella_string_length").call([prefix.code]),
+                    "first" => prefix.code.member(c::Ident::new("first")),
+                    _ => prefix.code.member(c::Ident::new("last")),
+                };
+                Ok(SingleExprValue {
+                    ty: Type::standard_integer(ctx)?,
+                    perm: Permission::Read,
+                    code,
+                    value: None,
+                    subprogram: None,
+                }
+                .into())
+            }
+            "address" => {
+                let ty = Type::system_address(self, ctx)?;
+                // a variable or the characters of a string
+                let prefix = self
+                    .prefix
+                    .generate(ctx)?
+                    .filter_distinct(&*self.prefix, |value| {
+                        value.ty.is_string() || value.perm.has_address()
+                    })?;
+                let code = if prefix.ty.is_string() {
+                    prefix.code.member(c::Ident::new("data"))
+                } else {
+                    prefix.code.addr_of()
+                };
+                Ok(SingleExprValue {
+                    code: code.cast(ty.c_type()),
+                    ty,
+                    perm: Permission::Read,
+                    value: None,
+                    subprogram: None,
+                }
+                .into())
+            }
+            "image" => {
+                let ty = self.prefix.generate_type(ctx)?;
+                let Some(image) = ty.image() else {
+                    return Err(self.unrecoverable_error("type has no image attribute"));
+                };
+                Ok(SingleExprValue {
+                    ty: Type::function(FunctionType {
+                        args: vec![ArgumentType {
+                            ty: ty.clone(),
+                            mode: ArgumentMode::In,
+                        }],
+                        return_type: Type::string(),
+                        static_link: None,
+                    }),
+                    perm: Permission::Read,
+                    code: image.clone(),
+                    value: None,
+                    subprogram: None,
+                }
+                .into())
+            }
+            "succ" | "pred" => {
+                let ty = self.prefix.generate_type(ctx)?;
+                let function = if &*self.designator.name == "succ" {
+                    ty.succ()
+                } else {
+                    ty.pred()
+                };
+                let Some(function) = function else {
+                    return Err(self.unrecoverable_error("not a discrete type"));
+                };
+                Ok(SingleExprValue {
+                    ty: Type::function(FunctionType {
+                        args: vec![ArgumentType {
+                            ty: ty.clone(),
+                            mode: ArgumentMode::In,
+                        }],
+                        return_type: ty.clone(),
+                        static_link: None,
+                    }),
+                    perm: Permission::Read,
+                    code: function.clone(),
+                    value: None,
+                    subprogram: None,
+                }
+                .into())
+            }
+            "size" => {
+                let ty = self.prefix.generate_type(ctx)?;
+                let Some(size) = ty.static_size() else {
+                    return Err(self.unimplemented_error(
+                        "attribute `Size` of a type without static size not yet implemented",
+                    ));
+                };
+                Ok(SingleExprValue::universal_integer(size).into())
+            }
+            "result" => {
+                let function = match &*self.prefix {
+                    Name::Base(BaseName::Ident(ident)) => ctx.function_result(ident),
+                    _ => None,
+                };
+                let value = function.cloned().ok_or_else(|| {
+                    self.unrecoverable_error(
+                        "attribute `Result` is only allowed in the postcondition of the function",
+                    )
+                })?;
+                Ok(value.into())
+            }
+            _ => Err(self
+                .designator
+                .unimplemented_error("attribute not yet implemented")),
+        }
+    }
+}
+
+impl AttributeReference {
+    /// The type and the first and last value of `'Range`, `'First` and `'Last` of a
+    /// discrete subtype or a string.
+    pub(super) fn generate_range(&self, ctx: &mut Context) -> Result<(Type, c::Expr, c::Expr)> {
+        if let Some(ty) = self.prefix.resolve_type(ctx)? {
+            let Some((first, last)) = ty.bounds() else {
+                return Err(self.unimplemented_error(
+                    "range of a type without static bounds not yet implemented",
+                ));
+            };
+            return Ok((ty, first, last));
+        }
+        let prefix = generate_string_prefix(&self.prefix, ctx)?;
+        Ok((
+            Type::standard_integer(ctx)?,
+            prefix.code.clone().member(c::Ident::new("first")),
+            prefix.code.member(c::Ident::new("last")),
+        ))
+    }
+}
+
+/// Explicit conversion of a single value to the type `ty`.
+fn generate_type_conversion(
+    name: &Name,
+    ty: Type,
+    args: &Parenthesized<Expr>,
+    ctx: &mut Context,
+) -> Result<ExprValue> {
+    let mut iter = args.iter();
+    let (Some(arg), None) = (iter.next(), iter.next()) else {
+        return Err(name.unrecoverable_error("type conversion expects exactly one argument"));
+    };
+    arg.generate(ctx)?.flat_map(|value| {
+        if !ty.can_convert(&value.ty) {
+            return Err(arg.unrecoverable_error("invalid type conversion"));
+        }
+        // real values are rounded to the nearest integer, away from zero at halfway
+        let code = if value.ty.is_real() && !ty.is_real() {
+            value
+                .code
+                .clone()
+                .binary(c::BinaryOp::Lt, c::Expr::float(0.0))
+                .cond(
+                    value
+                        .code
+                        .clone()
+                        .binary(c::BinaryOp::Sub, c::Expr::float(0.5)),
+                    value.code.binary(c::BinaryOp::Add, c::Expr::float(0.5)),
+                )
+        } else {
+            value.code
+        };
+        let code = SingleExprValue {
+            ty: value.ty,
+            perm: Permission::Read,
+            code: code.cast(ty.c_type()),
+            value: None,
+            subprogram: None,
+        }
+        .with_check(&ty);
+        Ok(SingleExprValue {
+            ty: ty.clone(),
+            perm: Permission::Read,
+            code,
+            value: None,
+            subprogram: None,
+        }
+        .into())
+    })
 }
 
 pub(super) fn generate_function_call<'a, A, E>(
@@ -245,40 +669,63 @@ where
             match arg_num.cmp(&ty_num) {
                 std::cmp::Ordering::Less => {
                     return Err(name
-                        .unrecoverable_error(format!("missing arguments: {arg_num} of {ty_num}")))
+                        .unrecoverable_error(format!("missing arguments: {arg_num} of {ty_num}"))
+                        .arity())
                 }
                 std::cmp::Ordering::Equal => {}
                 std::cmp::Ordering::Greater => {
                     return Err(args.nth(ty_num).unwrap().unrecoverable_error(format!(
                         "unexpected argument: {arg_num} of {ty_num}"
-                    )))
+                    ))
+                    .arity())
                 }
             }
 
             args.zip(&ty.args)
                 .map(|(arg, arg_ty)| {
-                    let code = arg.generate_with_type_and_check(&arg_ty.ty, ctx)?;
+                    let value = arg.generate(ctx)?.filter_type(arg, &arg_ty.ty)?;
+                    if value.perm == Permission::BitField
+                        && matches!(arg_ty.mode, ArgumentMode::Out | ArgumentMode::InOut)
+                    {
+                        return Err(arg.unimplemented_error(
+                            "component with a component clause as `out` parameter not yet implemented",
+                        ));
+                    }
+                    let code = value.with_check(&arg_ty.ty);
                     Ok(match arg_ty.mode {
                         ArgumentMode::In => code,
-                        ArgumentMode::Out | ArgumentMode::InOut => c_code! { & #code },
+                        ArgumentMode::Out | ArgumentMode::InOut => code.addr_of(),
                     })
                 })
                 .collect::<Result<Vec<_>>>()?
         };
+        let args = args.into_iter().chain(static_link(ty, ctx));
         Ok(SingleExprValue {
             ty: ty.return_type.clone(),
             perm: Permission::Read,
-            code: c_code! {
-                #f(#(#args),*)
-            },
+            code: f.code.clone().call(args),
             value: None,
+            subprogram: f.subprogram,
         }
         .into())
     })
 }
 
+/// The frame of the enclosing subprogram passed to a nested subprogram.
+fn static_link(ty: &FunctionType, ctx: &Context) -> Option<c::Expr> {
+    ty.static_link.map(|level| Frame::link(ctx.level(), level))
+}
+
 impl CodeGenExpr for FunctionCall {
     fn generate(&self, ctx: &mut Context) -> Result<ExprValue> {
+        if let Some(ty) = self.name.resolve_type(ctx)? {
+            return generate_type_conversion(&self.name, ty, &self.args, ctx);
+        }
+        if let ExprValue::Distinct(prefix) = self.name.generate(ctx)? {
+            if prefix.ty.is_string() {
+                return generate_indexed_component(prefix, &self.args, ctx);
+            }
+        }
         generate_function_call(&self.name, self.args.iter(), ctx)
     }
 }
@@ -309,6 +756,18 @@ impl ExprBinary {
 
 impl CodeGenExpr for ExprBinary {
     fn generate(&self, ctx: &mut Context) -> Result<ExprValue> {
+        let lhs = self.lhs.generate(ctx)?;
+        let rhs = self.rhs.generate(ctx)?;
+        if let (Some(lhs), Some(rhs)) = (lhs.as_universal_integer(), rhs.as_universal_integer()) {
+            if let Some(value) = self.generate_universal(lhs, rhs, ctx)? {
+                return Ok(value.into());
+            }
+        }
+        if let (Some(lhs), Some(rhs)) = (lhs.as_universal_real(), rhs.as_universal_real()) {
+            if let Some(value) = self.generate_universal_real(lhs, rhs, ctx)? {
+                return Ok(value.into());
+            }
+        }
         generate_function_call(
             &Name::Base(BaseName::Ident(self.op_ident())),
             [&*self.lhs, &*self.rhs].into_iter(),
@@ -317,6 +776,214 @@ impl CodeGenExpr for ExprBinary {
     }
 }
 
+impl ExprBinary {
+    /// Operations on universal integers (literals and named numbers) don't resolve
+    /// to the operators of a specific integer type.
+    fn generate_universal(
+        &self,
+        lhs: &SingleExprValue,
+        rhs: &SingleExprValue,
+        ctx: &mut Context,
+    ) -> Result<Option<SingleExprValue>> {
+        let constant = match (&lhs.value, &rhs.value) {
+            (Some(CompileTimeValue::Integer(lhs)), Some(CompileTimeValue::Integer(rhs))) => {
+                Some((*lhs, *rhs))
+            }
+            _ => None,
+        };
+        let fold = |f: fn(i64, i64) -> Option<i64>| -> Result<Option<i64>> {
+            constant
+                .map(|(lhs, rhs)| {
+                    f(lhs, rhs).ok_or_else(|| {
+                        self.unrecoverable_error("value out of range of universal integer")
+                    })
+                })
+                .transpose()
+        };
+        let binary = |op| lhs.code.clone().binary(op, rhs.code.clone());
+        let (value, code) = match self.op {
+            BinaryOp::Add(_) => (fold(i64::checked_add)?, binary(c::BinaryOp::Add)),
+            BinaryOp::Sub(_) => (fold(i64::checked_sub)?, binary(c::BinaryOp::Sub)),
+            BinaryOp::Mul(_) => (fold(i64::checked_mul)?, binary(c::BinaryOp::Mul)),
+            BinaryOp::Div(_) => (fold(i64::checked_div)?, binary(c::BinaryOp::Div)),
+            BinaryOp::Rem(_) => (fold(i64::checked_rem)?, binary(c::BinaryOp::Rem)),
+            BinaryOp::Mod(_) => (
+                fold(|lhs, rhs| lhs.checked_rem(rhs)?.checked_add(rhs)?.checked_rem(rhs))?,
+                binary(c::BinaryOp::Rem)
+                    .binary(c::BinaryOp::Add, rhs.code.clone())
+                    .binary(c::BinaryOp::Rem, rhs.code.clone()),
+            ),
+            BinaryOp::Pow(_) if constant.is_some() => (
+                fold(|lhs, rhs| lhs.checked_pow(rhs.try_into().ok()?))?,
+                // unused, the value is always folded
+                c::Expr::int(0),
+            ),
+            BinaryOp::Eq(_)
+            | BinaryOp::Ne(_)
+            | BinaryOp::Lt(_)
+            | BinaryOp::Le(_)
+            | BinaryOp::Gt(_)
+            | BinaryOp::Ge(_) => {
+                let op = match self.op {
+                    BinaryOp::Eq(_) => c::BinaryOp::Eq,
+                    BinaryOp::Ne(_) => c::BinaryOp::Ne,
+                    BinaryOp::Lt(_) => c::BinaryOp::Lt,
+                    BinaryOp::Le(_) => c::BinaryOp::Le,
+                    BinaryOp::Gt(_) => c::BinaryOp::Gt,
+                    _ => c::BinaryOp::Ge,
+                };
+                return Ok(Some(SingleExprValue {
+                    ty: Type::boolean(ctx)?,
+                    perm: Permission::Read,
+                    code: binary(op),
+                    value: None,
+                    subprogram: None,
+                }));
+            }
+            _ => return Ok(None),
+        };
+        Ok(Some(match value {
+            Some(value) => SingleExprValue::universal_integer(value),
+            None => SingleExprValue {
+                ty: Type::integer(),
+                perm: Permission::Read,
+                code,
+                value: None,
+                subprogram: None,
+            },
+        }))
+    }
+}
+
+impl ExprBinary {
+    /// Operations on universal reals, like [`Self::generate_universal`]. Real literals
+    /// are static, so the value is always folded.
+    fn generate_universal_real(
+        &self,
+        lhs: &SingleExprValue,
+        rhs: &SingleExprValue,
+        ctx: &mut Context,
+    ) -> Result<Option<SingleExprValue>> {
+        let (Some(CompileTimeValue::Real(lhs)), Some(CompileTimeValue::Real(rhs))) =
+            (&lhs.value, &rhs.value)
+        else {
+            return Ok(None);
+        };
+        let (lhs, rhs) = (*lhs, *rhs);
+        let value = match self.op {
+            BinaryOp::Add(_) => lhs + rhs,
+            BinaryOp::Sub(_) => lhs - rhs,
+            BinaryOp::Mul(_) => lhs * rhs,
+            BinaryOp::Div(_) if rhs == 0.0 => {
+                return Err(self.unrecoverable_error("division by zero"))
+            }
+            BinaryOp::Div(_) => lhs / rhs,
+            BinaryOp::Eq(_)
+            | BinaryOp::Ne(_)
+            | BinaryOp::Lt(_)
+            | BinaryOp::Le(_)
+            | BinaryOp::Gt(_)
+            | BinaryOp::Ge(_) => {
+                let value = match self.op {
+                    BinaryOp::Eq(_) => lhs == rhs,
+                    BinaryOp::Ne(_) => lhs != rhs,
+                    BinaryOp::Lt(_) => lhs < rhs,
+                    BinaryOp::Le(_) => lhs <= rhs,
+                    BinaryOp::Gt(_) => lhs > rhs,
+                    _ => lhs >= rhs,
+                };
+                return Ok(Some(SingleExprValue {
+                    ty: Type::boolean(ctx)?,
+                    perm: Permission::Read,
+                    code: c::Expr::int(value),
+                    value: Some(CompileTimeValue::Boolean(value)),
+                    subprogram: None,
+                }));
+            }
+            _ => return Ok(None),
+        };
+        if !value.is_finite() {
+            return Err(self.unrecoverable_error("value out of range of universal real"));
+        }
+        Ok(Some(SingleExprValue::universal_real(value)))
+    }
+}
+
+impl ExprUnary {
+    fn op_ident(&self) -> Ident {
+        match self.op {
+            UnaryOp::Abs(op) => op.operator_symbol(),
+            UnaryOp::Not(op) => op.operator_symbol(),
+            UnaryOp::Add(op) => op.operator_symbol(),
+            UnaryOp::Sub(op) => op.operator_symbol(),
+        }
+    }
+}
+
+impl CodeGenExpr for ExprUnary {
+    fn generate(&self, ctx: &mut Context) -> Result<ExprValue> {
+        let expr = self.expr.generate(ctx)?;
+        if let Some(SingleExprValue {
+            value: Some(CompileTimeValue::Real(value)),
+            ..
+        }) = expr.as_universal_real()
+        {
+            let value = match self.op {
+                UnaryOp::Add(_) => *value,
+                UnaryOp::Sub(_) => -value,
+                UnaryOp::Abs(_) => value.abs(),
+                UnaryOp::Not(_) => {
+                    return Err(self.unrecoverable_error("universal real has no `not` operator"))
+                }
+            };
+            return Ok(SingleExprValue::universal_real(value).into());
+        }
+        if let Some(value) = expr.as_universal_integer() {
+            let constant = match value.value {
+                Some(CompileTimeValue::Integer(value)) => Some(value),
+                _ => None,
+            };
+            let (constant, code) = match self.op {
+                UnaryOp::Add(_) => (constant, value.code.clone()),
+                UnaryOp::Sub(_) => (
+                    constant.map(i64::wrapping_neg),
+                    c::Expr::unary(c::UnaryOp::Neg, value.code.clone()),
+                ),
+                UnaryOp::Abs(_) => (
+                    constant.map(i64::wrapping_abs),
+                    value
+                        .code
+                        .clone()
+                        .binary(c::BinaryOp::Lt, c::Expr::int(0))
+                        .cond(
+                            c::Expr::unary(c::UnaryOp::Neg, value.code.clone()),
+                            value.code.clone(),
+                        ),
+                ),
+                UnaryOp::Not(_) => {
+                    return Err(self.unrecoverable_error("universal integer has no `not` operator"))
+                }
+            };
+            return Ok(match constant {
+                Some(value) => SingleExprValue::universal_integer(value),
+                None => SingleExprValue {
+                    ty: Type::integer(),
+                    perm: Permission::Read,
+                    code,
+                    value: None,
+                    subprogram: None,
+                },
+            }
+            .into());
+        }
+        generate_function_call(
+            &Name::Base(BaseName::Ident(self.op_ident())),
+            iter::once(&*self.expr),
+            ctx,
+        )
+    }
+}
+
 impl CodeGenExpr for ExprShortCircuit {
     fn generate(&self, ctx: &mut Context) -> Result<ExprValue> {
         let boolean = Type::boolean(ctx)?;
@@ -324,29 +991,31 @@ impl CodeGenExpr for ExprShortCircuit {
         let lhs = self.lhs.generate_with_type_and_check(&boolean, ctx)?;
         let rhs = self.rhs.generate_with_type_and_check(&boolean, ctx)?;
         let op = match self.op {
-            ShortCircuitOp::And(_) => c_code! { && },
-            ShortCircuitOp::Or(_) => c_code! { || },
+            ShortCircuitOp::And(_) => c::BinaryOp::And,
+            ShortCircuitOp::Or(_) => c::BinaryOp::Or,
         };
 
         Ok(SingleExprValue {
             ty: boolean,
             perm: Permission::Read,
-            code: c_code! { #lhs #op #rhs },
+            code: lhs.binary(op, rhs),
             value: None,
+            subprogram: None,
         }
         .into())
     }
 }
 
 impl ExprValue {
-    fn implicit_dereference(self, _ctx: &mut Context) -> Result<ExprValue> {
+    pub(super) fn implicit_dereference(self, ctx: &mut Context) -> Result<ExprValue> {
         self.flat_map(|value| {
             Ok(match value.ty.as_function() {
                 Some(f) if f.args.is_empty() => SingleExprValue {
                     ty: f.return_type.clone(),
                     perm: Permission::Read,
-                    code: c_code! { #value() },
+                    code: value.code.clone().call(static_link(f, ctx)),
                     value: None,
+                    subprogram: value.subprogram,
                 }
                 .into(),
                 Some(_) => {
@@ -1,24 +1,66 @@
-use std::fmt::{self, Write};
-
-use proc_macro2::{Ident as CIdent, Span};
+use std::{fmt, ops::Deref};
 
 use crate::{parser::token::Token, tokenizer::Ident};
 
-use super::Type;
+use super::{c, Type, UnitPart};
+
+/// Full name of the enclosing packages and subprograms, used to build unique C
+/// identifiers. The leading packages are the C++ namespace of the declarations.
+#[derive(Debug, Clone, Default)]
+pub struct Path {
+    idents: Vec<Ident>,
+    packages: usize,
+}
+
+impl Path {
+    /// The path of the declarations of a package.
+    pub fn package(idents: Vec<Ident>) -> Self {
+        let packages = idents.len();
+        Self { idents, packages }
+    }
+
+    /// Enter a subprogram.
+    pub fn push(&mut self, ident: Ident) {
+        self.idents.push(ident);
+    }
 
+    /// The same path for declarations which are not in a namespace, like the local
+    /// variables of a subprogram.
+    pub fn local(&self) -> Self {
+        Self {
+            idents: self.idents.clone(),
+            packages: 0,
+        }
+    }
+}
+
+impl Deref for Path {
+    type Target = [Ident];
+
+    fn deref(&self) -> &[Ident] {
+        &self.idents
+    }
+}
+
+/// A C identifier and its name in the C++ namespace of its package.
 pub struct IdentBuilder {
     str: String,
+    local: String,
+    namespace: Vec<Box<str>>,
 }
 
 impl IdentBuilder {
     fn base() -> Self {
         Self {
             str: "annabella_".into(),
+            local: String::new(),
+            namespace: Vec::new(),
         }
     }
 
     fn push_str(&mut self, s: &str) -> &mut Self {
         self.str.push_str(s);
+        self.local.push_str(s);
         self
     }
 
@@ -31,8 +73,19 @@ impl IdentBuilder {
     }
 
     fn debug(&mut self, fmt: impl fmt::Debug) -> &mut Self {
-        self.push_str("__");
-        write!(self.str, "{fmt:?}").unwrap();
+        self.push_str("__").push_str(&format!("{fmt:?}"))
+    }
+
+    fn path(&mut self, path: &Path) -> &mut Self {
+        for (i, ident) in path.iter().enumerate() {
+            if i < path.packages {
+                self.str.push_str("__");
+                self.str.push_str(&ident.name);
+                self.namespace.push(ident.name.clone());
+            } else {
+                self.ident(ident);
+            }
+        }
         self
     }
 
@@ -43,46 +96,84 @@ impl IdentBuilder {
         self
     }
 
-    fn build(&self) -> CIdent {
-        CIdent::new(&self.str, Span::call_site())
+    fn build(&self) -> c::Ident {
+        if self.namespace.is_empty() {
+            c::Ident::new(&self.str)
+        } else {
+            c::Ident::namespaced(&self.str, self.namespace.clone(), &self.local)
+        }
     }
 
-    // fn build(&self) -> CIdent {
-    //     use std::sync::atomic::AtomicUsize;
-    //     static COUNTER: AtomicUsize = AtomicUsize::new(0);
-    //     let n = COUNTER.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
-    //     CIdent::new(&format!("annabella_{n}"), Span::call_site())
-    // }
-
     fn start(kind: &str) -> Self {
         let mut this = Self::base();
         this.push_str(kind);
         this
     }
 
-    pub fn constraint_check(ty: &Ident) -> CIdent {
-        Self::start("constraint").ident(ty).build()
+    pub fn constraint_check(path: &Path, ty: &Ident) -> c::Ident {
+        Self::start("constraint").path(path).ident(ty).build()
     }
 
-    pub fn enum_value(name: &Ident, value: &Ident) -> CIdent {
-        Self::start("enum").ident(name).ident(value).build()
+    /// The function of an enumeration literal, character literals like `'A'` by their
+    /// code point.
+    pub fn enum_value(path: &Path, name: &Ident, value: &Ident) -> c::Ident {
+        let mut builder = Self::start("enum");
+        builder.path(path).ident(name);
+        match value.name.strip_prefix('\'') {
+            Some(literal) => {
+                let c = literal.chars().next().unwrap();
+                builder.push_str(&format!("__char_{:x}", u32::from(c)));
+            }
+            None => {
+                builder.ident(value);
+            }
+        }
+        builder.build()
     }
 
-    pub fn op_function(op: impl Token, ty: &Type) -> CIdent {
+    pub fn op_function(path: &Path, op: impl Token, ty: &Type) -> c::Ident {
         let _ = op;
-        Self::start("op").debug(op).ty(ty).build()
+        Self::start("op").path(path).debug(op).ty(ty).build()
+    }
+
+    /// Operator with operands of different types.
+    pub fn binary_op_function(path: &Path, op: impl Token, lhs: &Type, rhs: &Type) -> c::Ident {
+        Self::start("op")
+            .path(path)
+            .debug(op)
+            .ty(lhs)
+            .ty(rhs)
+            .build()
+    }
+
+    pub fn unary_op_function(path: &Path, op: impl Token, ty: &Type) -> c::Ident {
+        Self::start("unary_op").path(path).debug(op).ty(ty).build()
+    }
+
+    pub fn print(path: &Path, ty: &Type) -> c::Ident {
+        Self::start("print").path(path).ty(ty).build()
+    }
+
+    pub fn image(path: &Path, ty: &Ident) -> c::Ident {
+        Self::start("image").path(path).ident(ty).build()
+    }
+
+    pub fn succ(path: &Path, ty: &Ident) -> c::Ident {
+        Self::start("succ").path(path).ident(ty).build()
     }
 
-    pub fn print(ty: &Type) -> CIdent {
-        Self::start("print").ty(ty).build()
+    pub fn pred(path: &Path, ty: &Ident) -> c::Ident {
+        Self::start("pred").path(path).ident(ty).build()
     }
 
     pub fn function<'a>(
+        path: &Path,
         name: &Ident,
         args: impl Iterator<Item = &'a Ident>,
         return_type: Option<&Ident>,
-    ) -> CIdent {
+    ) -> c::Ident {
         Self::start("function")
+            .path(path)
             .ident(name)
             .args(args)
             .push_str("_")
@@ -90,20 +181,40 @@ impl IdentBuilder {
             .build()
     }
 
-    pub fn type_(name: &Ident) -> CIdent {
-        // CIdent::new(&name.name, Span::call_site())
-        Self::start("type").ident(name).build()
+    /// C macro of the runtime implementing a generic subprogram.
+    pub fn generic(path: &[Ident], name: &Ident) -> c::Ident {
+        let mut builder = Self::start("generic");
+        for ident in path {
+            builder.ident(ident);
+        }
+        builder.ident(name).build()
     }
 
-    pub fn label(label: &Ident) -> CIdent {
+    pub fn type_(path: &Path, name: &Ident) -> c::Ident {
+        Self::start("type").path(path).ident(name).build()
+    }
+
+    pub fn label(label: &Ident) -> c::Ident {
         Self::start("label").ident(label).build()
     }
 
-    pub fn variable(name: &Ident) -> CIdent {
-        Self::start("variable").ident(name).build()
+    pub fn variable(path: &Path, name: &Ident) -> c::Ident {
+        Self::start("variable").path(path).ident(name).build()
+    }
+
+    /// Procedure elaborating a part of the library unit `unit`, like `ada.text_io`.
+    pub fn elaboration(unit: &str, part: UnitPart) -> c::Ident {
+        let mut this = Self::start(match part {
+            UnitPart::Spec => "elaborate_spec",
+            UnitPart::Body => "elaborate_body",
+        });
+        for name in unit.split('.') {
+            this.push_str("__").push_str(name);
+        }
+        this.build()
     }
 
-    pub fn field(name: &Ident) -> CIdent {
+    pub fn field(name: &Ident) -> c::Ident {
         Self::start("field").ident(name).build()
     }
 }
@@ -1,142 +1,551 @@
+use std::{iter, rc::Rc};
+
 use crate::{
-    parser::{Function, Item, ParamMode, Variable},
+    parser::{
+        ExceptionDeclaration, ExceptionHandlers, Function, FunctionDeclaration, FunctionSpec, Item,
+        NumberDeclaration, ParamMode, SkippedItem, Stmt, UseClause, Variable,
+    },
+    tokenizer::Spanned,
     Result,
 };
 
 use super::{
-    ArgumentType, CCode, CodeGenStmt, Context, FunctionType, FunctionValue, IdentBuilder,
-    Permission, Type, Value, VariableValue,
+    c,
+    contract::{subprogram_contract, Contract},
+    package::set_generic_body,
+    pragma::{declare_interfaces, Linkage},
+    representation::declare_representations,
+    stmt::generate_handled_stmts,
+    ArgumentType, CodeGenExpr, CodeGenStmt, Context, Convention, ExceptionValue, FunctionType,
+    FunctionValue, GenericBody, IdentBuilder, Path, Permission, Type, Value, VariableValue,
 };
 
 impl CodeGenStmt for Item {
-    fn generate(&self, ctx: &mut Context) -> Result<CCode> {
+    fn generate(&self, ctx: &mut Context) -> Result<Vec<c::Stmt>> {
         match self {
             Self::Function(item) => item.generate(ctx),
+            Self::FunctionDeclaration(item) => item.generate(ctx),
             Self::Type(item) => item.generate(ctx),
             Self::Subtype(item) => item.generate(ctx),
             Self::Variable(item) => item.generate(ctx),
+            Self::Number(item) => item.generate(ctx),
+            Self::Exception(item) => item.generate(ctx),
+            Self::Package(item) => item.generate(ctx),
+            Self::PackageBody(item) => item.generate(ctx),
+            Self::Instantiation(item) => item.generate(ctx),
+            Self::Generic(item) => item.generate(ctx),
+            Self::Use(item) => item.generate(ctx),
+            Self::Pragma(item) => item.generate(ctx),
+            Self::Representation(item) => item.generate(ctx),
+            Self::Skipped(item) => item.generate(ctx),
         }
     }
 }
 
-impl Function {
-    fn c_name(&self) -> CCode {
-        let ident = IdentBuilder::function(
-            &self.name,
+impl CodeGenStmt for SkippedItem {
+    /// The syntax error was reported by the parser, only declare the name as poisoned.
+    fn generate(&self, ctx: &mut Context) -> Result<Vec<c::Stmt>> {
+        ctx.poison(&self.name);
+        Ok(Vec::new())
+    }
+}
+
+impl FunctionSpec {
+    fn mangled_name(&self, ctx: &Context) -> c::Ident {
+        IdentBuilder::function(
+            ctx.path(),
+            self.ident(),
             self.args().map(|arg| &arg.ty),
             self.return_type(),
-        );
-        c_code! {
-            #ident
+        )
+    }
+
+    /// The C name and convention, the mangled name unless the subprogram is imported
+    /// or exported.
+    pub(super) fn c_linkage(&self, ctx: &Context) -> Linkage {
+        let name = self.mangled_name(ctx);
+        ctx.linkage(&name).cloned().unwrap_or(Linkage {
+            name,
+            convention: Convention::Ada,
+            static_link: ctx.level() > 0,
+        })
+    }
+
+    /// Apply the interfacing pragmas of the declarative part to the subprogram, returns
+    /// its C name and convention.
+    fn declare_linkage(&self, ctx: &mut Context) -> Linkage {
+        let name = self.mangled_name(ctx);
+        if let Some(interface) = ctx.interface(self.ident()) {
+            let linkage = Linkage {
+                name: interface
+                    .external_name(self.ident())
+                    .unwrap_or_else(|| name.clone()),
+                convention: interface.convention,
+                static_link: ctx.level() > 0 && !interface.import,
+            };
+            ctx.set_linkage(&name, linkage);
         }
+        self.c_linkage(ctx)
+    }
+
+    fn c_return_type(&self, convention: Convention, ctx: &mut Context) -> Result<c::Type> {
+        Ok(if let Some(ty) = self.return_type() {
+            Type::from_ident(ty, ctx)?.c_type_with(convention, ctx)
+        } else {
+            c::Type::void()
+        })
+    }
+
+    /// The C declaration of the subprogram.
+    pub(super) fn c_prototype(&self, linkage: &Linkage, ctx: &mut Context) -> Result<c::Function> {
+        let mut params = self
+            .args()
+            .map(|arg| {
+                let ty = Type::from_ident(&arg.ty, ctx)?;
+                Ok(c::Param::unnamed(c_param_type(
+                    &ty,
+                    &arg.mode,
+                    linkage.convention,
+                    ctx,
+                )))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        if linkage.static_link {
+            params.push(c::Param::unnamed(c::Type::void().pointer().pointer()));
+        }
+        Ok(c::Function {
+            return_type: self.c_return_type(linkage.convention, ctx)?,
+            name: linkage.name.clone(),
+            params,
+            body: None,
+            extern_c: linkage.convention == Convention::C,
+        })
+    }
+
+    fn function_type(&self, linkage: &Linkage, ctx: &Context) -> Result<Type> {
+        let args = self
+            .args()
+            .map(|arg| {
+                let ty = Type::from_ident(&arg.ty, ctx)?;
+                Ok(ArgumentType {
+                    ty,
+                    mode: (&arg.mode).into(),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let return_type = if let Some(ty) = self.return_type() {
+            Type::from_ident(ty, ctx)?
+        } else {
+            Type::void()
+        };
+
+        // subprograms nested in a subprogram get the frame of the enclosing subprogram
+        let static_link = Some(ctx.level()).filter(|_| linkage.static_link);
+
+        Ok(Type::function(FunctionType {
+            args,
+            return_type,
+            static_link,
+        }))
+    }
+
+    pub(super) fn insert(&self, linkage: &Linkage, ctx: &mut Context) -> Result<()> {
+        if self.name.parent().is_some() {
+            return Err(self
+                .name
+                .unimplemented_error("child subprograms not yet implemented"));
+        }
+        let ty = self.function_type(linkage, ctx)?;
+        ctx.insert(
+            self.ident(),
+            Value::Function(
+                FunctionValue::new(linkage.name.clone(), ty).with_declaration(self.ident().span),
+            ),
+        )
+    }
+}
+
+/// Parameters of mode `in` are passed by value, with the C type of the convention.
+/// Parameters of mode `out` and `in out` are passed as pointer to the object.
+fn c_param_type(ty: &Type, mode: &ParamMode, convention: Convention, ctx: &mut Context) -> c::Type {
+    match mode {
+        ParamMode::In(_) => ty.c_type_with(convention, ctx),
+        ParamMode::Out(_) | ParamMode::InOut(_) => ty.c_type().pointer(),
+    }
+}
+
+impl CodeGenStmt for FunctionDeclaration {
+    fn generate(&self, ctx: &mut Context) -> Result<Vec<c::Stmt>> {
+        let spec = &self.spec;
+        let linkage = spec.declare_linkage(ctx);
+        let contract = Contract::new(self.aspects.as_ref(), ctx)?;
+        if !contract.is_empty() {
+            ctx.set_contract(&spec.mangled_name(ctx), Rc::new(contract));
+        }
+
+        // Declarations without a body in Ada are provided by the C runtime, or by C code
+        // under the external name if imported.
+        let prototype = spec.c_prototype(&linkage, ctx)?;
+        ctx.push_function(c::Decl::Function(prototype));
+
+        spec.insert(&linkage, ctx)?;
+        Ok(Vec::new())
     }
 }
 
 impl CodeGenStmt for Function {
-    fn generate(&self, ctx: &mut Context) -> Result<CCode> {
-        let name = self.c_name();
+    fn generate(&self, ctx: &mut Context) -> Result<Vec<c::Stmt>> {
+        if let Ok(Value::Generic(generic)) = ctx.get(self.spec.ident()) {
+            let generic = generic.clone();
+            set_generic_body(
+                &generic,
+                &self.spec.name,
+                GenericBody::Subprogram(self.clone()),
+            )?;
+            return Ok(Vec::new());
+        }
+
+        if let Some(interface) = ctx.interface(self.spec.ident()) {
+            if interface.import {
+                return Err(self
+                    .spec
+                    .name
+                    .unrecoverable_error("an imported subprogram can not have a body"));
+            }
+            if interface.export && ctx.level() > 0 {
+                return Err(self
+                    .spec
+                    .name
+                    .unrecoverable_error("a nested subprogram can not be exported"));
+            }
+        }
+        let linkage = self.spec.declare_linkage(ctx);
+        let convention = linkage.convention;
+        let nested = linkage.static_link;
+        let return_type = self.spec.c_return_type(convention, ctx)?;
+        let mangled_name = self.spec.mangled_name(ctx);
+
+        // insert before generating the body to allow recursive calls
+        self.spec.insert(&linkage, ctx)?;
+
         let mut sub_ctx = ctx.subscope(
             self.return_type()
                 .map(|ty| Type::from_ident(ty, ctx))
                 .transpose()?,
         );
+        sub_ctx.enter_subprogram(
+            self.spec.ident(),
+            declares_subprograms(&self.items, &self.stmts)
+                || handlers_declare_subprograms(self.handlers.as_ref()),
+        );
 
-        let args = self
+        let mut frame_stores = Vec::new();
+        let mut params = self
             .args()
             .map(|arg| {
                 let ty = Type::from_ident(&arg.ty, &sub_ctx)?;
-                let ident = IdentBuilder::variable(&arg.name);
-                let ref_ = match arg.mode {
-                    ParamMode::In(_) => c_code!(),
-                    ParamMode::Out(_) | ParamMode::InOut(_) => c_code! { * },
+                let ident = IdentBuilder::variable(&Path::default(), &arg.name);
+                let param = c_param_type(&ty, &arg.mode, convention, &mut sub_ctx);
+                let name = match arg.mode {
+                    ParamMode::In(_) => c::Expr::from(ident.clone()),
+                    ParamMode::Out(_) | ParamMode::InOut(_) => c::Expr::from(ident.clone()).deref(),
                 };
                 let perm = match arg.mode {
                     ParamMode::In(_) => Permission::Read,
                     ParamMode::Out(_) => Permission::ReadWrite,
                     ParamMode::InOut(_) => Permission::ReadWrite,
                 };
-                let code = c_code! { #ty #ref_ const #ident };
+                let param = c::Param::new(param.const_(), ident);
+                let (slot, store) = sub_ctx.frame_slot(&name);
+                frame_stores.push(store);
                 sub_ctx.insert(
                     &arg.name,
                     Value::Variable(VariableValue {
-                        name: c_code! { #ref_ #ident },
+                        name,
                         ty,
                         perm,
+                        slot,
                     }),
                 )?;
-                Ok(code)
+                Ok(param)
             })
             .collect::<Result<Vec<_>>>()?;
+        if nested {
+            params.push(c::Param::new(
+                c::Type::void().pointer().pointer().const_(),
+                c::Ident::new("annabella_link"),
+            ));
+        }
 
-        let items = self
-            .items
-            .iter()
-            .map(|item| item.generate(&mut sub_ctx))
-            .collect::<Result<Vec<_>>>()?;
+        // the contract is checked in the body, before its declarations are elaborated
+        let contract = subprogram_contract(&mangled_name, self.aspects.as_ref(), &mut sub_ctx)?;
+        let pre = contract.generate_pre(&mut sub_ctx)?;
+        let post = contract.generate_post(&self.spec, &mut sub_ctx)?;
+        sub_ctx.set_postcondition(post.clone());
 
-        let stmts = self
-            .stmts
-            .iter()
-            .map(|stmt| stmt.generate(&mut sub_ctx))
-            .collect::<Result<Vec<_>>>()?;
+        declare_interfaces(&self.items, &mut sub_ctx)?;
+        declare_representations(&self.items, &mut sub_ctx)?;
+        let mut items = Vec::new();
+        for item in &self.items {
+            items.extend(sub_ctx.recover_item(item, |ctx| item.generate(ctx)));
+        }
+        let stmts = generate_handled_stmts(&self.stmts, self.handlers.as_ref(), &mut sub_ctx)?;
 
-        let return_type = if let Some(ty) = self.return_type() {
-            let ty = Type::from_ident(ty, ctx)?;
-            c_code! { #ty }
-        } else {
-            c_code! { void }
-        };
+        // the secondary stack is released when the subprogram returns
+        let entry = c::Ident::new("annabella_ss_entry");
+        let mut body = vec![c::VarDecl::new(
+            c::Type::named("annabella_ss_mark_t").const_(),
+            entry.clone(),
+        )
+        .init(c::Expr::ident("annabella_ss_mark").call([]))
+        .into()];
+        if let Some(size) = sub_ctx.frame_size() {
+            let frame = c::Ident::new("annabella_frame");
+            let link = if nested { "annabella_link" } else { "NULL" };
+            body.push(
+                c::VarDecl::new(c::Type::void().pointer(), frame.clone())
+                    .array(Some(c::Expr::int(size as i128)))
+                    .into(),
+            );
+            body.push(
+                c::Expr::from(frame)
+                    .index(c::Expr::int(0))
+                    .assign(c::Expr::ident(link))
+                    .into(),
+            );
+        }
+        body.extend(frame_stores.into_iter().flatten());
+        body.extend(pre);
+        body.extend(items);
+        body.extend(stmts);
+        if self.return_type().is_none() {
+            body.extend(post);
+        } else if !ends_with_return(&self.stmts)
+            || self
+                .handlers
+                .iter()
+                .flat_map(|handlers| &handlers.handlers)
+                .any(|handler| !ends_with_return(&handler.stmts))
+        {
+            // a function must not reach the end of its body
+            body.push(c::Expr::ident("throw_Program_Error").call([]).into());
+        }
+        body.push(
+            c::Expr::ident("annabella_ss_release")
+                .call([entry.into()])
+                .into(),
+        );
+        ctx.push_function(c::Decl::Function(c::Function {
+            return_type,
+            extern_c: linkage.convention == Convention::C,
+            name: linkage.name,
+            params,
+            body: Some(body),
+        }));
+
+        Ok(Vec::new())
+    }
+}
 
-        ctx.push_function(c_code! {
-            #return_type #name(#(#args),*) {
-                #(#items)*
-                #(#stmts)*
+/// Whether a subprogram body declares nested subprograms, which need its frame array.
+fn declares_subprograms<'a>(items: impl IntoIterator<Item = &'a Item>, stmts: &[Stmt]) -> bool {
+    items
+        .into_iter()
+        .any(|item| matches!(item, Item::Function(_)))
+        || stmts.iter().any(|stmt| match stmt {
+            Stmt::Block(stmt) => {
+                declares_subprograms(stmt.items(), &stmt.stmts)
+                    || handlers_declare_subprograms(stmt.handlers.as_ref())
             }
-        });
+            Stmt::If(stmt) => {
+                declares_subprograms([], &stmt.stmts)
+                    || stmt
+                        .elsifs
+                        .iter()
+                        .any(|elsif| declares_subprograms([], &elsif.stmts))
+                    || stmt
+                        .else_
+                        .as_ref()
+                        .is_some_and(|else_| declares_subprograms([], &else_.stmts))
+            }
+            Stmt::Loop(stmt) => declares_subprograms([], &stmt.stmts),
+            Stmt::ExtendedReturn(stmt) => stmt
+                .body
+                .as_ref()
+                .is_some_and(|body| declares_subprograms([], &body.stmts)),
+            Stmt::Case(stmt) => stmt
+                .alternatives
+                .iter()
+                .any(|alt| declares_subprograms([], &alt.stmts)),
+            _ => false,
+        })
+}
 
-        let args = self
-            .args()
-            .map(|arg| {
-                let ty = Type::from_ident(&arg.ty, ctx)?;
-                Ok(ArgumentType {
-                    ty,
-                    mode: (&arg.mode).into(),
-                })
-            })
-            .collect::<Result<Vec<_>>>()?;
+fn handlers_declare_subprograms(handlers: Option<&ExceptionHandlers>) -> bool {
+    handlers
+        .iter()
+        .flat_map(|handlers| &handlers.handlers)
+        .any(|handler| declares_subprograms([], &handler.stmts))
+}
 
-        let return_type = if let Some(ty) = self.return_type() {
-            Type::from_ident(ty, ctx)?
-        } else {
-            Type::void()
-        };
+/// Whether the statements end with a return, so that a function does not reach the end
+/// of its body.
+fn ends_with_return(stmts: &[Stmt]) -> bool {
+    matches!(
+        stmts.last(),
+        Some(Stmt::Return(_) | Stmt::ExtendedReturn(_))
+    )
+}
 
+impl CodeGenStmt for Variable {
+    fn generate(&self, ctx: &mut Context) -> Result<Vec<c::Stmt>> {
+        let ty = Type::from_name(&self.ty, ctx)?;
+        let interface = ctx.interface(&self.name).cloned().unwrap_or_default();
+        let ident = interface.external_name(&self.name).unwrap_or_else(|| {
+            if ctx.is_global() {
+                IdentBuilder::variable(ctx.path(), &self.name)
+            } else {
+                // a local variable is declared in the body of its subprogram
+                IdentBuilder::variable(&ctx.path().local(), &self.name)
+            }
+        });
+        let perm = match self.constant {
+            Some(_) => Permission::Read,
+            None => Permission::ReadWrite,
+        };
+        let mut decl = c::VarDecl::new(ty.c_type_with(interface.convention, ctx), ident.clone());
+        if interface.convention == Convention::C {
+            decl = decl.extern_c();
+        }
+        let name = c::Expr::from(ident);
+        if interface.import {
+            // an imported object is defined by the C code, only declare it
+            if !ctx.is_global() {
+                return Err(self
+                    .name
+                    .unrecoverable_error("only package level objects can be imported"));
+            }
+            if let Some(init) = self.init() {
+                return Err(init.unrecoverable_error("an imported object can not be initialized"));
+            }
+            ctx.push_type(c::Decl::Extern(decl));
+            ctx.insert(
+                &self.name,
+                Value::Variable(VariableValue {
+                    name,
+                    ty,
+                    perm,
+                    slot: None,
+                }),
+            )?;
+            return Ok(Vec::new());
+        }
+        if interface.export && !ctx.is_global() {
+            return Err(self
+                .name
+                .unrecoverable_error("only package level objects can be exported"));
+        }
+        let decl = match self.init() {
+            Some(init) if ty.is_string() => {
+                // an object of an unconstrained array type gets its bounds and a copy
+                // of the elements from the initial value
+                let init = init.generate_with_type_and_check(&ty, ctx)?;
+                let init = c::Expr::ident("annabella_string_copy").call([init]);
+                if ctx.is_global() {
+                    ctx.push_main([name.clone().assign(init).into()]);
+                    decl
+                } else {
+                    decl.init(init)
+                }
+            }
+            None if ty.is_string() => {
+                return Err(self
+                    .ty
+                    .unrecoverable_error("unconstrained subtype requires an initial value"))
+            }
+            // the initial value of a package level variable is assigned during elaboration
+            Some(init) if ctx.is_global() => {
+                let init = init.generate_with_type_and_check(&ty, ctx)?;
+                ctx.push_main([name.clone().assign(init).into()]);
+                decl
+            }
+            Some(init) => decl.init(init.generate_with_type_and_check(&ty, ctx)?),
+            None if self.constant.is_some() => {
+                return Err(self
+                    .name
+                    .unimplemented_error("deferred constants not yet implemented"))
+            }
+            None => match ty.default_value() {
+                Some(value) if ctx.is_global() => {
+                    ctx.push_main([name.clone().assign(c::Expr::int(value)).into()]);
+                    decl
+                }
+                Some(value) => decl.init(c::Expr::int(value)),
+                None => decl,
+            },
+        };
+        let (slot, store) = ctx.frame_slot(&name);
         ctx.insert(
             &self.name,
-            Value::Function(FunctionValue::new(
+            Value::Variable(VariableValue {
                 name,
-                Type::function(FunctionType { args, return_type }),
-            )),
+                ty,
+                perm,
+                slot,
+            }),
         )?;
-        Ok(c_code!())
+        Ok(iter::once(decl.into()).chain(store).collect())
     }
 }
 
-impl CodeGenStmt for Variable {
-    fn generate(&self, ctx: &mut Context) -> Result<CCode> {
-        let ty = Type::from_ident(&self.ty, ctx)?;
-        let ident = IdentBuilder::variable(&self.name);
-        let code = c_code! {
-            #ty #ident;
-        };
+impl CodeGenStmt for NumberDeclaration {
+    fn generate(&self, ctx: &mut Context) -> Result<Vec<c::Stmt>> {
+        let ty = Type::integer();
+        let value = self.expr.generate_with_type_and_check(&ty, ctx)?;
         ctx.insert(
             &self.name,
             Value::Variable(VariableValue {
-                name: c_code! { #ident },
+                name: value,
                 ty,
-                perm: Permission::ReadWrite,
+                perm: Permission::Read,
+                slot: None,
             }),
         )?;
-        Ok(code)
+        Ok(Vec::new())
+    }
+}
+
+impl CodeGenStmt for ExceptionDeclaration {
+    /// An exception needs no code, it is raised and reported by its expanded name as
+    /// spelled in the source.
+    fn generate(&self, ctx: &mut Context) -> Result<Vec<c::Stmt>> {
+        let name = ctx
+            .path()
+            .iter()
+            .chain([&self.name])
+            .map(|ident| {
+                ident
+                    .span
+                    .source()
+                    .unwrap_or_else(|| ident.name.to_string())
+            })
+            .collect::<Vec<_>>()
+            .join(".");
+        ctx.insert(
+            &self.name,
+            Value::Exception(ExceptionValue { name: name.into() }),
+        )?;
+        Ok(Vec::new())
+    }
+}
+
+impl CodeGenStmt for UseClause {
+    fn generate(&self, ctx: &mut Context) -> Result<Vec<c::Stmt>> {
+        for name in self.names.iter() {
+            let package = ctx.get_package(name)?;
+            ctx.use_package(package);
+        }
+        Ok(Vec::new())
     }
 }
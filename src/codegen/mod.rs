@@ -1,63 +1,111 @@
-use std::{fmt, rc::Rc};
-
-use quote::ToTokens;
+use std::{fmt, path::PathBuf, rc::Rc};
 
 use crate::{
-    parser::Item,
+    parser::CompilationUnit,
     tokenizer::{Ident, Span, Spanned},
-    Result,
+    Diagnostics, Error, Result,
 };
 
-#[macro_use]
-mod c_code;
-
+mod binder;
+pub mod c;
+mod conditional;
 mod context;
+mod contract;
 mod expr;
 mod ident;
 mod item;
+mod output;
+mod package;
+mod pragma;
+mod representation;
 mod standard;
 mod stmt;
+mod target;
 mod ty;
 mod type_item;
+mod unit;
 mod value;
 
-pub use self::{c_code::CCode, context::Context, ident::IdentBuilder, ty::*, value::*};
+pub use self::{
+    context::{Context, Frame, FrameSlot},
+    contract::AssertionPolicy,
+    ident::{IdentBuilder, Path},
+    output::{Output, UnitPart},
+    target::Target,
+    ty::*,
+    value::*,
+};
+
+#[derive(Debug, Default)]
+pub struct Options {
+    /// Directories searched for the library units in `with` clauses.
+    pub search_path: Vec<PathBuf>,
+
+    /// Target dependent values of the bundled `Standard`, `System` and `Interfaces` packages.
+    pub target: Target,
+
+    /// Language of the generated code.
+    pub language: Language,
+
+    /// Name of the main subprogram, by default the last subprogram body of the units.
+    pub main: Option<String>,
 
-pub fn run(items: Vec<Item>) -> Result<String> {
-    let mut ctx = Context::base();
-    let mut ctx = ctx.context();
+    /// Generate the units without a main subprogram, like a library unit on its own.
+    pub no_main: bool,
+
+    /// Whether assertions, preconditions and postconditions are checked, until changed
+    /// by `pragma Assertion_Policy`.
+    pub assertion_policy: AssertionPolicy,
+}
+
+/// Language of the generated code.
+///
+/// The C++ code is mostly the same as the C code, but Ada exceptions are C++ exceptions.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Language {
+    #[default]
+    C,
+    Cxx,
+}
+
+/// Generate C or C++ code for the compilation units.
+///
+/// Library units in `with` clauses are loaded from the bundled runtime or from the search path.
+/// The errors of the declarations and statements are reported to `diagnostics` and their
+/// code is left out.
+pub fn run(
+    units: Vec<CompilationUnit>,
+    options: Options,
+    diagnostics: &Diagnostics,
+) -> Result<Output> {
+    let mut base = Context::base(options.target, options.language, diagnostics.clone());
+    let mut ctx = base.context();
     let ctx = &mut ctx;
+    for path in options.search_path {
+        ctx.push_search_path(path);
+    }
+    ctx.set_assertion_policy(None, options.assertion_policy);
     ctx.push_include("<stdio.h>");
     standard::generate(ctx)?;
-    for item in &items {
-        let code = item.generate(ctx)?;
-        ctx.push_main(code);
+    for unit in &units {
+        unit.generate(ctx)?;
+    }
+    if !options.no_main {
+        let main = binder::main_subprogram(&units, options.main.as_deref(), ctx)?;
+        ctx.push_main(main);
     }
 
-    let ident = Ident {
-        name: "main".into(),
-        span: Span::call_site(),
-    };
-    let value = ctx
-        .get(&ident)?
-        .expr_value()
-        .filter_distinct(&ident, |value| {
-            matches!(
-                value.ty.as_function(),
-                Some(ty) if ty.args.is_empty() && ty.return_type.is_void(),
-            )
-        })?;
-    ctx.push_main(c_code! { #value(); });
-
-    Ok(ctx.to_string())
+    let mut output = base.into_output();
+    output.elaboration_order = binder::elaboration_order(&output)?;
+    Ok(output)
 }
 
 trait CodeGenStmt {
-    fn generate(&self, ctx: &mut Context) -> Result<CCode>;
+    fn generate(&self, ctx: &mut Context) -> Result<Vec<c::Stmt>>;
 }
 
 trait CodeGenType {
-    fn generate(&self, name: &Ident, ctx: &mut Context) -> Result<CCode>;
+    fn generate(&self, name: &Ident, ctx: &mut Context) -> Result<Vec<c::Stmt>>;
 }
 
 #[derive(Debug, Clone)]
@@ -98,7 +146,7 @@ impl ExprValue {
                     "value is not ambiguous: {} options",
                     values.len()
                 );
-                let mut last_err = None;
+                let mut best_err = None;
                 let mut new_values = Vec::new();
                 for value in values {
                     match f(value) {
@@ -110,11 +158,16 @@ impl ExprValue {
                             )
                         }
                         Err(err) => {
-                            last_err = Some(err);
+                            if best_err
+                                .as_ref()
+                                .is_none_or(|best: &Error| err.rank() > best.rank())
+                            {
+                                best_err = Some(err);
+                            }
                         }
                     }
                 }
-                Self::new(new_values).ok_or_else(|| last_err.unwrap())
+                Self::new(new_values).ok_or_else(|| best_err.unwrap())
             }
             Self::Dynamic(values) => {
                 Err(values.unrecoverable_error("dynamic expression type not allowed"))
@@ -122,25 +175,50 @@ impl ExprValue {
         }
     }
 
+    /// The distinct value, else the error `msg` with the candidates of an ambiguous value.
+    fn distinct(self, span: &impl Spanned, msg: &'static str) -> Result<SingleExprValue> {
+        match self {
+            Self::Distinct(value) => Ok(value),
+            Self::Ambiguous(values) => Err(with_candidates(span.unrecoverable_error(msg), &values)),
+            Self::Dynamic(_) => Err(span.unrecoverable_error(msg)),
+        }
+    }
+
     fn filter_type(mut self, span: &impl Spanned, ty: &Type) -> Result<SingleExprValue> {
+        let not_allowed = |values: &[SingleExprValue]| {
+            let found: Vec<_> = values
+                .iter()
+                .map(|value| format!("`{}`", value.ty.to_str()))
+                .collect();
+            let found = match &found[..] {
+                [found] => found.clone(),
+                found => format!("one of {}", found.join(", ")),
+            };
+            span.unrecoverable_error("expression type not allowed")
+                .with_note(format!("expected type `{}`, found {found}", ty.to_str()))
+        };
         Ok(loop {
             self = match self {
                 ExprValue::Distinct(value) => {
                     if ty.can_assign(&value.ty) {
                         break value;
                     } else {
-                        return Err(span.unrecoverable_error("expression type not allowed"));
+                        return Err(not_allowed(&[value]));
                     }
                 }
                 ExprValue::Ambiguous(values) => {
-                    let mut values = values.into_iter().filter(|value| ty.can_assign(&value.ty));
-                    let Some(value) = values.next() else {
-                        return Err(span.unrecoverable_error("expression type not allowed"));
-                    };
-                    if values.next().is_some() {
-                        return Err(span.unrecoverable_error("ambiguous expression"));
-                    } else {
-                        break value;
+                    let (mut allowed, other): (Vec<_>, Vec<_>) = values
+                        .into_iter()
+                        .partition(|value| ty.can_assign(&value.ty));
+                    match allowed.len() {
+                        0 => return Err(not_allowed(&other)),
+                        1 => break allowed.pop().unwrap(),
+                        _ => {
+                            return Err(with_candidates(
+                                span.unrecoverable_error("ambiguous expression"),
+                                &allowed,
+                            ))
+                        }
                     }
                 }
                 ExprValue::Dynamic(value) => value.generate(ty)?,
@@ -162,14 +240,16 @@ impl ExprValue {
                 }
             }
             ExprValue::Ambiguous(values) => {
-                let mut values = values.into_iter().filter(f);
-                let Some(value) = values.next() else {
-                    return Err(span.unrecoverable_error("expression type not allowed"));
-                };
-                if values.next().is_some() {
-                    return Err(span.unrecoverable_error("ambiguous expression"));
-                } else {
-                    value
+                let mut values: Vec<_> = values.into_iter().filter(f).collect();
+                match values.len() {
+                    0 => return Err(span.unrecoverable_error("expression type not allowed")),
+                    1 => values.pop().unwrap(),
+                    _ => {
+                        return Err(with_candidates(
+                            span.unrecoverable_error("ambiguous expression"),
+                            &values,
+                        ))
+                    }
                 }
             }
             ExprValue::Dynamic(_) => {
@@ -179,6 +259,35 @@ impl ExprValue {
     }
 }
 
+/// Label each candidate with the parameter and result types of its subprogram, else with
+/// its type, at the declaration of the subprogram or the type. The candidates declared
+/// outside of the sources, like the predefined operators, are named in a note.
+fn with_candidates(mut err: Error, values: &[SingleExprValue]) -> Error {
+    let mut candidates: Vec<(Option<Span>, String)> = Vec::new();
+    for value in values {
+        let (declaration, msg) = match &value.subprogram {
+            Some(subprogram) => (
+                subprogram.declaration.filter(|span| !span.is_call_site()),
+                format!("candidate {}", subprogram.profile()),
+            ),
+            None => (
+                value.ty.declaration(),
+                format!("candidate of type `{}`", value.ty.to_str()),
+            ),
+        };
+        if !candidates.iter().any(|(_, other)| *other == msg) {
+            candidates.push((declaration, msg));
+        }
+    }
+    for (declaration, msg) in candidates {
+        err = match declaration {
+            Some(span) => err.with_label(span, msg),
+            None => err.with_note(msg),
+        };
+    }
+    err
+}
+
 impl From<SingleExprValue> for ExprValue {
     fn from(value: SingleExprValue) -> Self {
         Self::Distinct(value)
@@ -224,14 +333,21 @@ impl DynamicExprValue {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Permission {
     Read,
     ReadWrite,
+
+    /// A component with a component clause, a bit-field without an address.
+    BitField,
 }
 
 impl Permission {
     fn can_write(self) -> bool {
+        matches!(self, Self::ReadWrite | Self::BitField)
+    }
+
+    fn has_address(self) -> bool {
         matches!(self, Self::ReadWrite)
     }
 }
@@ -242,6 +358,7 @@ enum CompileTimeValue {
     Character(char),
     Boolean(bool),
     Integer(i64),
+    Real(f64),
     String(String),
 }
 
@@ -249,38 +366,70 @@ enum CompileTimeValue {
 struct SingleExprValue {
     ty: Type,
     perm: Permission,
-    code: CCode,
-    #[expect(dead_code)]
+    code: c::Expr,
     value: Option<CompileTimeValue>,
+
+    /// The subprogram which is the value or is called to compute it, to label the
+    /// candidates of an ambiguous expression.
+    subprogram: Option<Subprogram>,
 }
 
-impl SingleExprValue {
-    fn with_check(self, ty: &Type) -> CCode {
-        if let Some(constraint_check) = ty.needs_constraint_check(&self.ty) {
-            let code = self.code;
-            c_code! {
-                #constraint_check(#code)
-            }
+#[derive(Debug, Clone)]
+struct Subprogram {
+    /// The declaration in the source, `None` for the predefined operators.
+    declaration: Option<Span>,
+    ty: Type,
+}
+
+impl Subprogram {
+    /// The kind and the parameter and result types, like
+    /// ``function (`integer`, `integer`) return `boolean` ``.
+    fn profile(&self) -> String {
+        let Some(ty) = self.ty.as_function() else {
+            return format!("`{}`", self.ty.to_str());
+        };
+        let args = ty
+            .args
+            .iter()
+            .map(|arg| {
+                let mode = match arg.mode {
+                    ArgumentMode::In => "",
+                    ArgumentMode::Out => "out ",
+                    ArgumentMode::InOut => "in out ",
+                };
+                format!("{mode}`{}`", arg.ty.to_str())
+            })
+            .collect::<Vec<_>>();
+        let args = match &args[..] {
+            [] => String::new(),
+            args => format!(" ({})", args.join(", ")),
+        };
+        if ty.return_type.is_void() {
+            format!("procedure{args}")
         } else {
-            self.code
+            format!("function{args} return `{}`", ty.return_type.to_str())
         }
     }
 }
 
-impl ToTokens for SingleExprValue {
-    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
-        self.code.to_tokens(tokens)
+impl SingleExprValue {
+    fn with_check(self, ty: &Type) -> c::Expr {
+        if let Some(constraint_check) = ty.needs_constraint_check(&self.ty) {
+            constraint_check.clone().call([self.code])
+        } else {
+            self.code
+        }
     }
 }
 
 trait CodeGenExpr: Spanned + Sized {
     fn generate(&self, ctx: &mut Context) -> Result<ExprValue>;
 
-    fn generate_with_type_and_check(&self, ty: &Type, ctx: &mut Context) -> Result<CCode> {
+    fn generate_with_type_and_check(&self, ty: &Type, ctx: &mut Context) -> Result<c::Expr> {
         Ok(self.generate(ctx)?.filter_type(self, ty)?.with_check(ty))
     }
 
-    fn generate_to_boolean(&self, ctx: &mut Context) -> Result<CCode> {
+    fn generate_to_boolean(&self, ctx: &mut Context) -> Result<c::Expr> {
         self.generate_with_type_and_check(&Type::boolean(ctx)?, ctx)
     }
 
@@ -289,9 +438,3 @@ trait CodeGenExpr: Spanned + Sized {
         Err(self.unrecoverable_error(format!("not a type: {}", std::any::type_name::<Self>())))
     }
 }
-
-impl ToTokens for Ident {
-    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
-        proc_macro2::Ident::new(&self.name, proc_macro2::Span::call_site()).to_tokens(tokens)
-    }
-}
@@ -0,0 +1,430 @@
+use std::fmt::{self, Write};
+
+use crate::{tokenizer::Span, Error};
+
+use super::{c, IdentBuilder, Language};
+
+/// The generated code of all library units.
+///
+/// It is either written as a single file, or as a header and a source file per unit.
+/// Both are split in sections, so the order of the declarations in the Ada code does
+/// not matter: all types come before all prototypes, which come before all bodies.
+#[derive(Default)]
+pub struct Output {
+    pub(super) includes: Vec<&'static str>,
+    pub(super) units: Vec<UnitCode>,
+
+    /// Parts of the units in the order of their elaboration, computed by the binder.
+    pub(super) elaboration_order: Vec<(usize, UnitPart)>,
+
+    /// The call of the main subprogram.
+    pub(super) main: Vec<c::Stmt>,
+    pub(super) language: Language,
+
+    /// Warnings about ignored code, like unknown pragmas.
+    pub(super) warnings: Vec<Error>,
+}
+
+/// The code generated for a library unit, its specification and body.
+pub(super) struct UnitCode {
+    /// Full name of the unit in lowercase, like `ada.text_io`.
+    pub name: String,
+
+    /// Base name of the generated files, like `a-textio`.
+    pub file_name: String,
+
+    /// Hand written C code of the runtime library.
+    pub runtime_headers: Vec<&'static str>,
+    pub runtime: Vec<&'static str>,
+
+    /// Declarations of the specification, in the header, and of the body.
+    pub spec: PartCode,
+    pub body: PartCode,
+    pub bodies: Vec<c::Decl>,
+
+    /// Units used by the specification, and additionally by the body.
+    pub spec_deps: Vec<usize>,
+    pub body_deps: Vec<usize>,
+
+    /// The `with` clauses of the specification and the body.
+    pub withs: Vec<With>,
+
+    /// Location of `pragma Elaborate_Body` and `pragma Preelaborate` for the unit.
+    pub elaborate_body: Option<Span>,
+    pub preelaborate: Option<Span>,
+}
+
+/// Part of a library unit receiving generated declarations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnitPart {
+    Spec,
+    Body,
+}
+
+/// The code generated for the specification or the body of a unit.
+#[derive(Default)]
+pub(super) struct PartCode {
+    pub types: Vec<c::Decl>,
+    pub prototypes: Vec<c::Function>,
+    pub globals: Vec<c::VarDecl>,
+
+    /// Initialization of the objects declared in the part, and the statements of a
+    /// package body.
+    pub elaboration: Vec<c::Stmt>,
+}
+
+/// A `with` clause of a library unit.
+pub(super) struct With {
+    pub unit: usize,
+    pub part: UnitPart,
+    pub span: Span,
+
+    /// `pragma Elaborate` or `pragma Elaborate_All` for the unit.
+    pub elaborate: Option<(Elaborate, Span)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Elaborate {
+    /// The body of the unit is elaborated before.
+    Unit,
+
+    /// The bodies of the unit and of all units it depends on are elaborated before.
+    All,
+}
+
+impl UnitCode {
+    pub fn new(name: String, file_name: String) -> Self {
+        Self {
+            name,
+            file_name,
+            runtime_headers: Vec::new(),
+            runtime: Vec::new(),
+            spec: PartCode::default(),
+            body: PartCode::default(),
+            bodies: Vec::new(),
+            spec_deps: Vec::new(),
+            body_deps: Vec::new(),
+            withs: Vec::new(),
+            elaborate_body: None,
+            preelaborate: None,
+        }
+    }
+
+    pub fn part(&self, part: UnitPart) -> &PartCode {
+        match part {
+            UnitPart::Spec => &self.spec,
+            UnitPart::Body => &self.body,
+        }
+    }
+
+    pub fn part_mut(&mut self, part: UnitPart) -> &mut PartCode {
+        match part {
+            UnitPart::Spec => &mut self.spec,
+            UnitPart::Body => &mut self.body,
+        }
+    }
+
+    /// Add a function prototype, unless the function is already declared.
+    pub fn push_prototype(&mut self, part: UnitPart, function: c::Function) {
+        let declared = [&self.spec, &self.body]
+            .iter()
+            .flat_map(|decls| &decls.prototypes)
+            .any(|f| f.name == function.name);
+        if !declared {
+            self.part_mut(part).prototypes.push(function);
+        }
+    }
+
+    fn header_name(&self) -> String {
+        format!("{}.h", self.file_name)
+    }
+
+    /// The function elaborating a part of the unit, if it has elaboration code.
+    fn elaboration_function(&self, part: UnitPart) -> Option<c::Function> {
+        let elaboration = &self.part(part).elaboration;
+        (!elaboration.is_empty()).then(|| c::Function {
+            return_type: c::Type::void(),
+            name: IdentBuilder::elaboration(&self.name, part),
+            params: Vec::new(),
+            body: Some(elaboration.clone()),
+            extern_c: false,
+        })
+    }
+
+    fn elaboration_functions(&self) -> impl Iterator<Item = c::Function> + '_ {
+        [UnitPart::Spec, UnitPart::Body]
+            .into_iter()
+            .filter_map(|part| self.elaboration_function(part))
+    }
+}
+
+impl Output {
+    /// Name of the source file with the C `main` function.
+    const MAIN_FILE_NAME: &'static str = "annabella_main";
+
+    /// Warnings about ignored code, like unknown pragmas.
+    pub fn warnings(&self) -> &[Error] {
+        &self.warnings
+    }
+
+    /// A header and a source file per library unit, and a source file with `main`.
+    pub fn files(&self) -> Vec<(String, String)> {
+        let ext = match self.language {
+            Language::C => "c",
+            Language::Cxx => "cpp",
+        };
+        let mut files = Vec::new();
+        for (i, unit) in self.units.iter().enumerate() {
+            files.push((unit.header_name(), self.header(i, unit)));
+            files.push((format!("{}.{ext}", unit.file_name), self.source(unit)));
+        }
+
+        let mut main = String::new();
+        for unit in &self.units {
+            writeln!(main, "#include \"{}\"", unit.header_name()).unwrap();
+        }
+        writeln!(main).unwrap();
+        c::Printer::new(&mut main, self.language)
+            .decl(&self.main_function())
+            .unwrap();
+        files.push((format!("{}.{ext}", Self::MAIN_FILE_NAME), main));
+        files
+    }
+
+    fn header(&self, index: usize, unit: &UnitCode) -> String {
+        let mut out = String::new();
+        let guard = format!("ANNABELLA_{}_H", unit.file_name.to_uppercase())
+            .replace(|c: char| !c.is_ascii_alphanumeric(), "_");
+        writeln!(out, "#ifndef {guard}\n#define {guard}\n").unwrap();
+
+        // every unit depends on `Standard`, the first unit
+        if index == 0 {
+            self.write_includes(&mut out).unwrap();
+        } else {
+            writeln!(out, "#include \"{}\"", self.units[0].header_name()).unwrap();
+        }
+        for &dep in &unit.spec_deps {
+            writeln!(out, "#include \"{}\"", self.units[dep].header_name()).unwrap();
+        }
+        writeln!(out).unwrap();
+        for header in &unit.runtime_headers {
+            writeln!(out, "{header}").unwrap();
+        }
+
+        let mut printer = c::Printer::new(&mut out, self.language);
+        write_declarations(&mut printer, [&unit.spec], |decl| {
+            c::Decl::Extern(decl.clone())
+        })
+        .unwrap();
+        let elaboration = unit.elaboration_functions().map(|f| f.prototype());
+        if write_all(&mut printer, &elaboration.collect::<Vec<_>>(), |f| {
+            c::Decl::Function(f.clone())
+        })
+        .unwrap()
+        {
+            printer.blank_line().unwrap();
+        }
+        printer.close_namespace().unwrap();
+        writeln!(out, "#endif").unwrap();
+        out
+    }
+
+    fn source(&self, unit: &UnitCode) -> String {
+        let mut out = String::new();
+        writeln!(out, "#include \"{}\"", unit.header_name()).unwrap();
+        for &dep in &unit.body_deps {
+            writeln!(out, "#include \"{}\"", self.units[dep].header_name()).unwrap();
+        }
+        writeln!(out).unwrap();
+        for runtime in &unit.runtime {
+            writeln!(out, "{runtime}").unwrap();
+        }
+
+        let mut printer = c::Printer::new(&mut out, self.language);
+        write_declarations(&mut printer, [&unit.body], |decl| {
+            c::Decl::Var(decl.clone())
+        })
+        .unwrap();
+        write_globals(&mut printer, [&unit.spec], |decl| {
+            c::Decl::Var(decl.clone())
+        })
+        .unwrap();
+        let elaboration = unit.elaboration_functions().map(c::Decl::Function);
+        for decl in unit.bodies.iter().cloned().chain(elaboration) {
+            printer.decl(&decl).unwrap();
+            printer.blank_line().unwrap();
+        }
+        printer.close_namespace().unwrap();
+        out
+    }
+
+    /// The units sorted so that each unit comes after the units it depends on.
+    fn dependency_order(&self) -> Vec<&UnitCode> {
+        fn visit<'a>(
+            output: &'a Output,
+            index: usize,
+            done: &mut [bool],
+            order: &mut Vec<&'a UnitCode>,
+        ) {
+            if done[index] {
+                return;
+            }
+            done[index] = true;
+            let unit = &output.units[index];
+            for &dep in unit.spec_deps.iter().chain(&unit.body_deps) {
+                visit(output, dep, done, order);
+            }
+            order.push(unit);
+        }
+
+        let mut done = vec![false; self.units.len()];
+        let mut order = Vec::new();
+        for index in 0..self.units.len() {
+            visit(self, index, &mut done, &mut order);
+        }
+        order
+    }
+
+    fn write_includes(&self, out: &mut dyn Write) -> fmt::Result {
+        for include in &self.includes {
+            writeln!(out, "#include {include}")?;
+        }
+        Ok(())
+    }
+
+    /// The C `main` function, storing its arguments, elaborating the units and calling
+    /// the main subprogram.
+    fn main_function(&self) -> c::Decl {
+        let argc = c::Ident::new("argc");
+        let argv = c::Ident::new("argv");
+        let mut body: Vec<c::Stmt> = vec![
+            c::Expr::ident("annabella_argc")
+                .assign(argc.clone().into())
+                .into(),
+            c::Expr::ident("annabella_argv")
+                .assign(argv.clone().into())
+                .into(),
+        ];
+        body.extend(
+            self.elaboration_order
+                .iter()
+                .filter_map(|&(index, part)| self.units[index].elaboration_function(part))
+                .map(|function| c::Expr::from(function.name).call([]).into()),
+        );
+        body.extend(self.main.iter().cloned());
+        // a main function returns the exit status
+        let has_status = matches!(self.main.last(), Some(c::Stmt::Return(_)));
+        if self.language == Language::Cxx {
+            // an unhandled exception ends the program like in C
+            let exception = c::Ident::new("exception");
+            body = vec![c::Stmt::TryCatch {
+                body,
+                ty: c::Type::named("annabella_exception"),
+                name: exception.clone(),
+                handler: vec![c::Stmt::return_(
+                    c::Expr::ident("annabella_unhandled_exception").call([
+                        c::Expr::from(exception.clone()).member(c::Ident::new("name")),
+                        c::Expr::from(exception).member(c::Ident::new("message")),
+                    ]),
+                )],
+            }];
+        }
+        if !has_status {
+            body.push(c::Stmt::return_(c::Expr::ident("annabella_exit_status")));
+        }
+        c::Decl::Function(c::Function {
+            return_type: c::Type::named("int"),
+            name: c::Ident::new("main"),
+            params: vec![
+                c::Param {
+                    ty: c::Type::named("int"),
+                    name: Some(argc),
+                },
+                c::Param {
+                    ty: c::Type::char().pointer().pointer(),
+                    name: Some(argv),
+                },
+            ],
+            body: Some(body),
+            extern_c: false,
+        })
+    }
+}
+
+/// The types, prototypes and global variables of some units.
+fn write_declarations<'a>(
+    printer: &mut c::Printer,
+    decls: impl IntoIterator<Item = &'a PartCode> + Clone,
+    global: impl Fn(&c::VarDecl) -> c::Decl,
+) -> fmt::Result {
+    let types = decls.clone().into_iter().flat_map(|decls| &decls.types);
+    if write_all(printer, types, |decl| decl.clone())? {
+        printer.blank_line()?;
+    }
+    let prototypes = decls
+        .clone()
+        .into_iter()
+        .flat_map(|decls| &decls.prototypes);
+    if write_all(printer, prototypes, |f| c::Decl::Function(f.clone()))? {
+        printer.blank_line()?;
+    }
+    write_globals(printer, decls, global)
+}
+
+fn write_globals<'a>(
+    printer: &mut c::Printer,
+    decls: impl IntoIterator<Item = &'a PartCode>,
+    global: impl Fn(&c::VarDecl) -> c::Decl,
+) -> fmt::Result {
+    let globals = decls.into_iter().flat_map(|decls| &decls.globals);
+    if write_all(printer, globals, global)? {
+        printer.blank_line()?;
+    }
+    Ok(())
+}
+
+/// Print the declarations, returns whether there were any.
+fn write_all<'a, T: 'a>(
+    printer: &mut c::Printer,
+    items: impl IntoIterator<Item = &'a T>,
+    decl: impl Fn(&T) -> c::Decl,
+) -> std::result::Result<bool, fmt::Error> {
+    let mut any = false;
+    for item in items {
+        printer.decl(&decl(item))?;
+        any = true;
+    }
+    Ok(any)
+}
+
+/// All units in a single file, each unit after the units it depends on.
+impl fmt::Display for Output {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let units = self.dependency_order();
+        self.write_includes(f)?;
+        writeln!(f)?;
+
+        for header in units.iter().flat_map(|unit| &unit.runtime_headers) {
+            writeln!(f, "{header}")?;
+        }
+
+        let decls = units.iter().flat_map(|unit| [&unit.spec, &unit.body]);
+        let mut printer = c::Printer::new(f, self.language);
+        write_declarations(&mut printer, decls, |decl| c::Decl::Var(decl.clone()))?;
+        printer.close_namespace()?;
+
+        for runtime in units.iter().flat_map(|unit| &unit.runtime) {
+            writeln!(f, "{runtime}")?;
+        }
+
+        let mut printer = c::Printer::new(f, self.language);
+        for unit in &units {
+            let elaboration = unit.elaboration_functions().map(c::Decl::Function);
+            for decl in unit.bodies.iter().cloned().chain(elaboration) {
+                printer.decl(&decl)?;
+                printer.blank_line()?;
+            }
+        }
+        printer.decl(&self.main_function())
+    }
+}
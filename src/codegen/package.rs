@@ -0,0 +1,331 @@
+use std::{cell::RefCell, collections::BTreeSet, iter, rc::Rc};
+
+use crate::{
+    parser::{
+        FullTypeItem, GenericDeclaration, GenericFormal, GenericInstantiation, GenericUnit,
+        InstantiationKind, Item, PackageBody, PackageItem, TypeDefinition, TypeItem, UnitName,
+    },
+    tokenizer::{Ident, Spanned},
+    Result,
+};
+
+use super::{
+    c,
+    pragma::{declare_interfaces, PragmaPlace},
+    representation::declare_representations,
+    stmt::generate_handled_stmts,
+    CodeGenStmt, Context, GenericBody, GenericValue, IdentBuilder, PackageValue, Type, TypeValue,
+    Value,
+};
+
+impl CodeGenStmt for PackageItem {
+    fn generate(&self, ctx: &mut Context) -> Result<Vec<c::Stmt>> {
+        let parent = parent_package(&self.name, ctx)?;
+        let mut sub_ctx = ctx.package_scope(package_path(&self.name, ctx));
+        use_ancestors(&self.name, &mut sub_ctx)?;
+        generate_declarations(self, &mut sub_ctx)?;
+        let values = sub_ctx.into_values();
+        insert_package(&self.name, parent, values, ctx)?;
+        Ok(Vec::new())
+    }
+}
+
+/// Full name of the package, used as prefix for C identifiers.
+fn package_path(name: &UnitName, ctx: &Context) -> Vec<Ident> {
+    ctx.path().iter().chain(name.iter()).cloned().collect()
+}
+
+fn parent_package(name: &UnitName, ctx: &Context) -> Result<Option<Rc<PackageValue>>> {
+    name.parent()
+        .map(|parent| ctx.get_package(&parent))
+        .transpose()
+}
+
+/// A child unit can see the declarations of all its ancestors.
+fn use_ancestors(name: &UnitName, ctx: &mut Context) -> Result<()> {
+    let mut ancestor = name.parent();
+    let mut ancestors = Vec::new();
+    while let Some(name) = ancestor {
+        ancestor = name.parent();
+        ancestors.push(ctx.get_package(&name)?);
+    }
+    for package in ancestors.into_iter().rev() {
+        ctx.use_package(package);
+    }
+    Ok(())
+}
+
+fn insert_package(
+    name: &UnitName,
+    parent: Option<Rc<PackageValue>>,
+    values: std::collections::BTreeMap<Box<str>, Value>,
+    ctx: &mut Context,
+) -> Result<()> {
+    let package = Rc::new(PackageValue {
+        name: name.last().clone(),
+        values,
+        children: Default::default(),
+    });
+    insert_unit(name, parent, Value::Package(package), ctx)
+}
+
+/// Insert a library unit into its parent, or into the current scope.
+fn insert_unit(
+    name: &UnitName,
+    parent: Option<Rc<PackageValue>>,
+    unit: Value,
+    ctx: &mut Context,
+) -> Result<()> {
+    let ident = name.last();
+    if let Some(parent) = parent {
+        if parent.values.contains_key(&ident.name)
+            || parent
+                .children
+                .borrow_mut()
+                .insert(ident.name.clone(), unit)
+                .is_some()
+        {
+            return Err(ident.unrecoverable_error("identifier already in use"));
+        }
+        Ok(())
+    } else {
+        ctx.insert(ident, unit)
+    }
+}
+
+/// Generate the visible and private declarations of a package specification.
+pub(super) fn generate_declarations(package: &PackageItem, ctx: &mut Context) -> Result<()> {
+    let private = package.private_items();
+    let mut completed = BTreeSet::new();
+    declare_interfaces(package.items.iter().chain(private), ctx)?;
+    declare_representations(package.items.iter().chain(private), ctx)?;
+    for item in &package.items {
+        let code = ctx.recover_item(item, |ctx| {
+            if let Some(full) = private_type_completion(item, private)? {
+                completed.insert(&full.name.name);
+                full.generate(ctx)
+            } else {
+                item.generate(ctx)
+            }
+        });
+        ctx.push_declarations(code);
+    }
+    for item in private {
+        if let Item::Type(TypeItem::Full(full)) = item {
+            if completed.contains(&full.name.name) {
+                continue;
+            }
+        }
+        let code = ctx.recover_item(item, |ctx| item.generate(ctx));
+        ctx.push_declarations(code);
+    }
+    Ok(())
+}
+
+/// Find the full declaration of a private type in the private part of the package.
+fn private_type_completion<'a>(
+    item: &Item,
+    private: &'a [Item],
+) -> Result<Option<&'a FullTypeItem>> {
+    let Item::Type(TypeItem::Full(partial)) = item else {
+        return Ok(None);
+    };
+    if !matches!(partial.definition, TypeDefinition::Private(_)) {
+        return Ok(None);
+    }
+    private
+        .iter()
+        .find_map(|item| match item {
+            Item::Type(TypeItem::Full(full))
+                if full.name == partial.name
+                    && !matches!(full.definition, TypeDefinition::Private(_)) =>
+            {
+                Some(full)
+            }
+            _ => None,
+        })
+        .map(Some)
+        .ok_or_else(|| {
+            partial
+                .name
+                .unrecoverable_error("missing full declaration of private type")
+        })
+}
+
+/// Lookup a package or generic unit by its (possibly expanded) name.
+fn get_unit(name: &UnitName, ctx: &Context) -> Result<Value> {
+    let unit = match name.parent() {
+        Some(parent) => ctx.get_package(&parent)?.find_unit(name.last()),
+        None => ctx.get(name.last())?.clone_unit(),
+    };
+    unit.ok_or_else(|| name.unrecoverable_error("not a package name"))
+}
+
+impl CodeGenStmt for PackageBody {
+    fn generate(&self, ctx: &mut Context) -> Result<Vec<c::Stmt>> {
+        let package = match get_unit(&self.name, ctx)? {
+            Value::Generic(generic) => {
+                set_generic_body(&generic, &self.name, GenericBody::Package(self.clone()))?;
+                return Ok(Vec::new());
+            }
+            Value::Package(package) => package,
+            _ => unreachable!(),
+        };
+
+        let mut sub_ctx = ctx.package_scope(package_path(&self.name, ctx));
+        use_ancestors(&self.name, &mut sub_ctx)?;
+        sub_ctx.use_package(package);
+        generate_body(self, &mut sub_ctx)?;
+        Ok(Vec::new())
+    }
+}
+
+/// Generate the declarations of a package body and elaborate its statements in `main`.
+fn generate_body(body: &PackageBody, ctx: &mut Context) -> Result<()> {
+    declare_interfaces(&body.items, ctx)?;
+    declare_representations(&body.items, ctx)?;
+    for item in &body.items {
+        let code = ctx.recover_item(item, |ctx| item.generate(ctx));
+        ctx.push_declarations(code);
+    }
+    let stmts = generate_handled_stmts(body.stmts(), body.handlers.as_ref(), ctx)?;
+    ctx.push_main(stmts);
+    Ok(())
+}
+
+impl CodeGenStmt for GenericDeclaration {
+    fn generate(&self, ctx: &mut Context) -> Result<Vec<c::Stmt>> {
+        let name = self.name();
+        let parent = parent_package(name, ctx)?;
+        for pragma in &self.pragmas {
+            pragma.generate_in(PragmaPlace::Component, ctx)?;
+        }
+
+        // a generic child unit sees the declarations of its ancestors when instantiated
+        let env = package_path(name, ctx);
+        let generic = Value::Generic(Rc::new(GenericValue {
+            declaration: self.clone(),
+            body: RefCell::new(None),
+            env: env[..env.len() - 1].to_vec(),
+        }));
+        insert_unit(name, parent, generic, ctx)?;
+        Ok(Vec::new())
+    }
+}
+
+/// Store the body of a generic unit, to be generated for each instance.
+pub(super) fn set_generic_body(
+    generic: &GenericValue,
+    name: &UnitName,
+    body: GenericBody,
+) -> Result<()> {
+    let mut slot = generic.body.borrow_mut();
+    if slot.is_some() {
+        return Err(name.unrecoverable_error("duplicate body of generic unit"));
+    }
+    *slot = Some(body);
+    Ok(())
+}
+
+impl CodeGenStmt for GenericInstantiation {
+    fn generate(&self, ctx: &mut Context) -> Result<Vec<c::Stmt>> {
+        let Value::Generic(generic) = get_unit(&self.generic_name, ctx)? else {
+            return Err(self
+                .generic_name
+                .unrecoverable_error("not a generic unit name"));
+        };
+        let declaration = &generic.declaration;
+        let body = generic.body.borrow().clone();
+
+        let formals = &declaration.formals;
+        let actuals = self.actuals().collect::<Vec<_>>();
+        if actuals.len() != formals.len() {
+            return Err(self.unrecoverable_error(format!(
+                "expected {} generic actual parameters, found {}",
+                formals.len(),
+                actuals.len(),
+            )));
+        }
+        let mut bindings = Vec::new();
+        for (i, actual) in actuals.iter().enumerate() {
+            let formal = match &actual.formal {
+                Some((name, _)) => formals
+                    .iter()
+                    .find(|formal| formal.name() == name)
+                    .ok_or_else(|| name.unrecoverable_error("unknown generic formal parameter"))?,
+                None => &formals[i],
+            };
+            let ty = Type::from_ident(&actual.ty, ctx)?;
+            match formal {
+                GenericFormal::Type(_) => bindings.push((formal.name(), ty)),
+            }
+        }
+
+        let parent = parent_package(&self.name, ctx)?;
+        let env = (1..=generic.env.len())
+            .map(|i| ctx.get_library_package(&generic.env[..i]))
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut sub_ctx = ctx.package_scope(package_path(&self.name, ctx));
+        for package in env {
+            sub_ctx.use_package(package);
+        }
+        for (name, ty) in &bindings {
+            sub_ctx.insert(name, Value::Type(TypeValue { ty: ty.clone() }))?;
+        }
+
+        let value = match (&self.kind, &declaration.unit) {
+            (InstantiationKind::Package(_), GenericUnit::Package(package)) => {
+                let Some(GenericBody::Package(body)) = body else {
+                    return Err(self
+                        .generic_name
+                        .unrecoverable_error("body of generic package not available"));
+                };
+                generate_declarations(package, &mut sub_ctx)?;
+                let path = sub_ctx.path().to_vec();
+                generate_body(&body, &mut sub_ctx.package_scope(path))?;
+                Value::Package(Rc::new(PackageValue {
+                    name: self.name.last().clone(),
+                    values: sub_ctx.into_values(),
+                    children: Default::default(),
+                }))
+            }
+            (InstantiationKind::Subprogram(_), GenericUnit::Subprogram(subprogram)) => {
+                let ident = self.name.last();
+                let mut spec = subprogram.spec.clone();
+                spec.name = ident.clone().into();
+                match body {
+                    Some(GenericBody::Subprogram(mut function)) => {
+                        function.spec = spec;
+                        function.generate(&mut sub_ctx)?;
+                    }
+                    Some(GenericBody::Package(_)) => unreachable!(),
+                    None => {
+                        // generic subprograms without an Ada body are C macros of the runtime
+                        let macro_ = IdentBuilder::generic(&generic.env, subprogram.spec.ident());
+                        let linkage = spec.c_linkage(&sub_ctx);
+                        let name = linkage.name.clone();
+                        let args = iter::once(name.clone().into()).chain(
+                            bindings
+                                .iter()
+                                .map(|(_, ty)| c::Expr::Ident(ty.c_type().name().clone())),
+                        );
+                        let prototype = spec.c_prototype(&linkage, &mut sub_ctx)?;
+                        sub_ctx.push_function(c::Decl::Function(prototype));
+                        sub_ctx.push_function(c::Decl::Macro(c::Expr::from(macro_).call(args)));
+                        spec.insert(&linkage, &mut sub_ctx)?;
+                    }
+                }
+                let mut values = sub_ctx.into_values();
+                values.remove(&ident.name).unwrap()
+            }
+            _ => {
+                return Err(self
+                    .generic_name
+                    .unrecoverable_error("generic unit does not match instantiation"))
+            }
+        };
+        insert_unit(&self.name, parent, value, ctx)?;
+        Ok(Vec::new())
+    }
+}
@@ -0,0 +1,414 @@
+use crate::{
+    parser::{BaseName, Expr, ExprLit, Item, Name, Pragma, PragmaArgument, UnitName},
+    tokenizer::{Ident, Spanned},
+    Error, Result,
+};
+
+use super::{
+    c,
+    contract::{generate_check, AssertionPolicy},
+    output::Elaborate,
+    unit::unit_key,
+    CodeGenStmt, Context, Convention, UnitPart,
+};
+
+/// Convention and external name of an entity, from the interfacing pragmas `Import`,
+/// `Export`, `Convention` and `Interface_Name` of its declarative part.
+#[derive(Debug, Clone, Default)]
+pub(super) struct Interface {
+    pub convention: Convention,
+    pub import: bool,
+    pub export: bool,
+    pub external_name: Option<String>,
+}
+
+impl Interface {
+    /// The C name of the imported or exported entity `ident`, its external name or the
+    /// Ada name in lower case.
+    pub fn external_name(&self, ident: &Ident) -> Option<c::Ident> {
+        (self.import || self.export)
+            .then(|| c::Ident::new(self.external_name.as_deref().unwrap_or(&ident.name)))
+    }
+}
+
+/// C name and convention of a subprogram.
+#[derive(Debug, Clone)]
+pub(super) struct Linkage {
+    pub name: c::Ident,
+    pub convention: Convention,
+
+    /// Whether the subprogram gets the frame of the enclosing subprogram, not for
+    /// imported subprograms.
+    pub static_link: bool,
+}
+
+/// Record the interfacing pragmas of a declarative part before its declarations are
+/// generated, the pragmas follow the declarations they apply to.
+pub(super) fn declare_interfaces<'a>(
+    items: impl IntoIterator<Item = &'a Item>,
+    ctx: &mut Context,
+) -> Result<()> {
+    for item in items {
+        let Item::Pragma(pragma) = item else {
+            continue;
+        };
+        if !matches!(
+            &*pragma.name.name,
+            "import" | "export" | "interface" | "convention" | "interface_name"
+        ) {
+            continue;
+        }
+        let entity = entity_argument(pragma, pragma.interface_entity()?)?;
+        let mut interface = ctx.interface(entity).cloned().unwrap_or_default();
+        match &*pragma.name.name {
+            "convention" => {
+                let [convention, _] = pragma.arguments(["convention", "entity"])?;
+                interface.convention = convention_argument(pragma, convention)?;
+            }
+            "interface_name" => {
+                let [_, external_name, link_name] =
+                    pragma.arguments(["entity", "external_name", "link_name"])?;
+                interface.external_name = external_name_argument(external_name, link_name)?;
+            }
+            name => {
+                let [convention, _, external_name, link_name] =
+                    pragma.arguments(["convention", "entity", "external_name", "link_name"])?;
+                interface.convention = convention_argument(pragma, convention)?;
+                if name == "export" {
+                    interface.export = true;
+                } else {
+                    interface.import = true;
+                }
+                if let Some(external_name) = external_name_argument(external_name, link_name)? {
+                    interface.external_name = Some(external_name);
+                }
+            }
+        }
+        if interface.import && interface.export {
+            return Err(
+                entity.unrecoverable_error("an entity can not be both imported and exported")
+            );
+        }
+        ctx.set_interface(entity, interface);
+    }
+    Ok(())
+}
+
+fn convention_argument(pragma: &Pragma, arg: Option<&PragmaArgument>) -> Result<Convention> {
+    let arg = arg.ok_or_else(|| pragma.unrecoverable_error("expected a convention"))?;
+    let ident = arg_ident(arg).ok_or_else(|| arg.unrecoverable_error("expected a convention"))?;
+    match &*ident.name {
+        "ada" => Ok(Convention::Ada),
+        "c" | "c_pass_by_copy" => Ok(Convention::C),
+        _ => Err(ident.unimplemented_error(format!("convention `{ident}` not yet implemented"))),
+    }
+}
+
+fn entity_argument<'a>(pragma: &Pragma, arg: Option<&'a PragmaArgument>) -> Result<&'a Ident> {
+    let arg = arg.ok_or_else(|| pragma.unrecoverable_error("expected the name of an entity"))?;
+    arg_ident(arg).ok_or_else(|| arg.unrecoverable_error("expected the name of an entity"))
+}
+
+/// The C name from the `External_Name` argument, or else the `Link_Name` argument.
+fn external_name_argument(
+    external_name: Option<&PragmaArgument>,
+    link_name: Option<&PragmaArgument>,
+) -> Result<Option<String>> {
+    external_name
+        .or(link_name)
+        .map(|arg| match &arg.value {
+            Expr::Lit(ExprLit::Str(lit)) => Ok(lit.str()),
+            _ => Err(arg.unrecoverable_error("expected a string literal")),
+        })
+        .transpose()
+}
+
+fn arg_ident(arg: &PragmaArgument) -> Option<&Ident> {
+    match &arg.value {
+        Expr::Name(Name::Base(BaseName::Ident(ident))) => Some(ident),
+        _ => None,
+    }
+}
+
+/// Place of a pragma, which decides the pragmas allowed there.
+#[derive(Debug, Clone, Copy)]
+pub(super) enum PragmaPlace<'a> {
+    /// The context clause of a compilation unit with the `with` clauses.
+    Context(&'a [&'a UnitName]),
+    Declaration,
+    Statement,
+
+    /// A record definition or the formal part of a generic unit.
+    Component,
+
+    /// After a library unit, like `pragma Inline (F);` after the declaration of `F`.
+    Unit,
+}
+
+type PragmaHandler = fn(&Pragma, PragmaPlace, &mut Context) -> Result<Vec<c::Stmt>>;
+
+/// The known pragmas and their handlers.
+const PRAGMAS: &[(&str, PragmaHandler)] = &[
+    ("assert", assert_pragma),
+    ("assertion_policy", assertion_policy_pragma),
+    ("check", assert_pragma),
+    ("convention", interfacing_pragma),
+    ("elaborate", elaborate_pragma),
+    ("elaborate_all", elaborate_pragma),
+    ("elaborate_body", library_unit_pragma),
+    ("export", interfacing_pragma),
+    ("import", interfacing_pragma),
+    ("interface", interfacing_pragma),
+    ("interface_name", interfacing_pragma),
+    ("preelaborate", library_unit_pragma),
+    ("pure", library_unit_pragma),
+    // pragmas without effect on the generated code
+    ("ada_83", ignored_pragma),
+    ("ada_95", ignored_pragma),
+    ("ada_05", ignored_pragma),
+    ("ada_2005", ignored_pragma),
+    ("ada_12", ignored_pragma),
+    ("ada_2012", ignored_pragma),
+    ("ada_2022", ignored_pragma),
+    ("annotate", ignored_pragma),
+    ("discard_names", ignored_pragma),
+    ("inline", ignored_pragma),
+    ("inline_always", ignored_pragma),
+    ("no_return", ignored_pragma),
+    ("optimize", ignored_pragma),
+    ("restrictions", ignored_pragma),
+    ("style_checks", ignored_pragma),
+    ("suppress", ignored_pragma),
+    ("unmodified", ignored_pragma),
+    ("unreferenced", ignored_pragma),
+    ("unsuppress", ignored_pragma),
+    ("warnings", ignored_pragma),
+];
+
+/// A pragma in a declarative part.
+impl CodeGenStmt for Pragma {
+    fn generate(&self, ctx: &mut Context) -> Result<Vec<c::Stmt>> {
+        self.generate_in(PragmaPlace::Declaration, ctx)
+    }
+}
+
+impl Pragma {
+    /// Dispatch the pragma to its handler, unknown pragmas are ignored with a warning.
+    pub(super) fn generate_in(
+        &self,
+        place: PragmaPlace,
+        ctx: &mut Context,
+    ) -> Result<Vec<c::Stmt>> {
+        match PRAGMAS.iter().find(|(name, _)| *name == &*self.name.name) {
+            Some((_, handler)) => handler(self, place, ctx),
+            None => {
+                ctx.warn(
+                    self.name.span(),
+                    format!("unknown pragma `{}` ignored", self.name),
+                );
+                Ok(Vec::new())
+            }
+        }
+    }
+
+    /// Library unit pragmas must be in the specification of the unit they apply to, with
+    /// the name of the unit as optional argument.
+    fn check_library_unit_pragma(&self, ctx: &Context) -> Result<()> {
+        let path = ctx
+            .path()
+            .iter()
+            .map(|ident| &*ident.name)
+            .collect::<Vec<_>>()
+            .join(".");
+        if !ctx.is_global() || ctx.current_unit() != Some((&path, UnitPart::Spec)) {
+            return Err(self.not_allowed("the specification of a library unit"));
+        }
+        let mut args = self.args();
+        if let Some(arg) = args.next() {
+            if arg.unit_name().map(|name| unit_key(&name)) != Some(path) {
+                return Err(arg.unrecoverable_error("expected the name of the library unit"));
+            }
+        }
+        if let Some(arg) = args.next() {
+            return Err(arg.unrecoverable_error("unexpected argument"));
+        }
+        Ok(())
+    }
+
+    /// The `Entity` argument of an interfacing pragma.
+    fn interface_entity(&self) -> Result<Option<&PragmaArgument>> {
+        Ok(match &*self.name.name {
+            "convention" => self.arguments(["convention", "entity"])?[1],
+            "interface_name" => self.arguments(["entity", "external_name", "link_name"])?[0],
+            _ => self.arguments(["convention", "entity", "external_name", "link_name"])?[1],
+        })
+    }
+
+    /// The arguments by position or by name, in the order of `names`.
+    fn arguments<const N: usize>(&self, names: [&str; N]) -> Result<[Option<&PragmaArgument>; N]> {
+        let mut args = [None; N];
+        let mut named = false;
+        for (index, arg) in self.args().enumerate() {
+            let index = match &arg.name {
+                Some((name, _)) => {
+                    named = true;
+                    names
+                        .iter()
+                        .position(|&n| n == &*name.name)
+                        .ok_or_else(|| name.unrecoverable_error("unknown argument name"))?
+                }
+                None if named => {
+                    return Err(arg.unrecoverable_error(
+                        "positional arguments must come before named arguments",
+                    ))
+                }
+                None if index < N => index,
+                None => return Err(arg.unrecoverable_error("unexpected argument")),
+            };
+            if args[index].replace(arg).is_some() {
+                return Err(arg.unrecoverable_error("argument given twice"));
+            }
+        }
+        Ok(args)
+    }
+
+    fn not_allowed(&self, place: &str) -> Error {
+        self.name
+            .unrecoverable_error(format!("pragma `{}` is only allowed in {place}", self.name))
+    }
+}
+
+fn ignored_pragma(_: &Pragma, _: PragmaPlace, _: &mut Context) -> Result<Vec<c::Stmt>> {
+    Ok(Vec::new())
+}
+
+/// `Elaborate` and `Elaborate_All` for units of the `with` clauses.
+fn elaborate_pragma(
+    pragma: &Pragma,
+    place: PragmaPlace,
+    ctx: &mut Context,
+) -> Result<Vec<c::Stmt>> {
+    let PragmaPlace::Context(withs) = place else {
+        return Err(pragma.not_allowed("a context clause"));
+    };
+    let elaborate = match &*pragma.name.name {
+        "elaborate" => Elaborate::Unit,
+        _ => Elaborate::All,
+    };
+    if pragma.args().next().is_none() {
+        return Err(pragma.unrecoverable_error("expected the names of library units"));
+    }
+    for arg in pragma.args() {
+        let name = arg
+            .unit_name()
+            .filter(|name| withs.contains(&name))
+            .ok_or_else(|| arg.unrecoverable_error("expected a unit of a `with` clause"))?;
+        ctx.set_elaborate(&unit_key(&name), elaborate, arg.span());
+    }
+    Ok(Vec::new())
+}
+
+/// `Elaborate_Body`, `Preelaborate` and `Pure`, which implies `Preelaborate`.
+fn library_unit_pragma(
+    pragma: &Pragma,
+    place: PragmaPlace,
+    ctx: &mut Context,
+) -> Result<Vec<c::Stmt>> {
+    if !matches!(place, PragmaPlace::Declaration) {
+        return Err(pragma.not_allowed("the specification of a library unit"));
+    }
+    pragma.check_library_unit_pragma(ctx)?;
+    match &*pragma.name.name {
+        "elaborate_body" => ctx.set_elaborate_body(pragma.span()),
+        _ => ctx.set_preelaborate(pragma.span()),
+    }
+    Ok(Vec::new())
+}
+
+/// `Assert (Condition, Message)` and `Check (Kind, Condition, Message)`, which raise
+/// `Assertion_Error` if the condition is false.
+fn assert_pragma(pragma: &Pragma, place: PragmaPlace, ctx: &mut Context) -> Result<Vec<c::Stmt>> {
+    if !matches!(place, PragmaPlace::Declaration | PragmaPlace::Statement) {
+        return Err(pragma.not_allowed("a declarative part or a sequence of statements"));
+    }
+    let (kind, cond, message) = match &*pragma.name.name {
+        "assert" => {
+            let [cond, message] = pragma.arguments(["check", "message"])?;
+            ("assert".into(), cond, message)
+        }
+        _ => {
+            let [kind, cond, message] = pragma.arguments(["name", "check", "message"])?;
+            let kind = kind
+                .and_then(arg_ident)
+                .ok_or_else(|| pragma.unrecoverable_error("expected the name of a check"))?;
+            (kind.name.clone(), cond, message)
+        }
+    };
+    let cond = cond.ok_or_else(|| pragma.unrecoverable_error("expected a condition"))?;
+    let label = match &*kind {
+        "assert" => "assertion".to_string(),
+        kind => format!("check {kind}"),
+    };
+    let check = generate_check(
+        &kind,
+        &label,
+        &cond.value,
+        message.map(|message| &message.value),
+        ctx,
+    )?;
+    // a pragma at library level is checked during elaboration
+    if ctx.is_global() {
+        ctx.push_main(check);
+        Ok(Vec::new())
+    } else {
+        Ok(check)
+    }
+}
+
+/// `Assertion_Policy (Check)` for all assertions, or `Assertion_Policy (Pre => Ignore)`
+/// for some assertion kinds. It applies to the code generated after it.
+fn assertion_policy_pragma(
+    pragma: &Pragma,
+    place: PragmaPlace,
+    ctx: &mut Context,
+) -> Result<Vec<c::Stmt>> {
+    if matches!(place, PragmaPlace::Component) {
+        return Err(pragma.not_allowed("a declarative part or a context clause"));
+    }
+    if pragma.args().next().is_none() {
+        return Err(pragma.unrecoverable_error("expected an assertion policy"));
+    }
+    for (index, arg) in pragma.args().enumerate() {
+        let policy = arg_ident(arg)
+            .and_then(|ident| AssertionPolicy::from_name(&ident.name))
+            .ok_or_else(|| arg.unrecoverable_error("expected `Check` or `Ignore`"))?;
+        match &arg.name {
+            Some((kind, _)) => ctx.set_assertion_policy(Some(&kind.name), policy),
+            None if index == 0 && pragma.args().count() == 1 => {
+                ctx.set_assertion_policy(None, policy)
+            }
+            None => return Err(arg.unrecoverable_error("expected `Kind => Policy`")),
+        }
+    }
+    Ok(Vec::new())
+}
+
+/// `Import`, `Export`, `Convention` and `Interface_Name`, recorded by `declare_interfaces`
+/// before the declarations, the entity must be declared before the pragma.
+fn interfacing_pragma(
+    pragma: &Pragma,
+    place: PragmaPlace,
+    ctx: &mut Context,
+) -> Result<Vec<c::Stmt>> {
+    let entity = entity_argument(pragma, pragma.interface_entity()?)?;
+    if !matches!(place, PragmaPlace::Declaration) || ctx.interface(entity).is_none() {
+        return Err(pragma.not_allowed("a declarative part"));
+    }
+    if !ctx.declares(entity) {
+        return Err(entity.unrecoverable_error(format!(
+            "`{entity}` must be declared before pragma `{}`",
+            pragma.name
+        )));
+    }
+    Ok(Vec::new())
+}
@@ -0,0 +1,417 @@
+use crate::{
+    parser::{
+        AggregateExpr, AttributeDefinitionClause, ComponentChoices, EnumRepresentationClause, Expr,
+        FullTypeItem, Item, RecordRepresentationClause, RepresentationClause, TypeDefinition,
+    },
+    tokenizer::{Ident, Spanned},
+    Result,
+};
+
+use super::{c, type_item::static_integer, CodeGenStmt, Context, RecordType, Type};
+
+/// Bits of a storage element, the unit of the positions in component clauses.
+const STORAGE_UNIT: i64 = 8;
+
+/// Representation clauses of a type, recorded by `declare_representations`, and the
+/// aspects of its declaration, recorded by `declare_aspects`.
+#[derive(Debug, Clone, Default)]
+pub(super) struct Representation {
+    size: Option<AttributeDefinitionClause>,
+    alignment: Option<Expr>,
+    enum_: Option<EnumRepresentationClause>,
+    record: Option<RecordRepresentationClause>,
+    default_value: Option<Expr>,
+}
+
+impl Representation {
+    /// The value of the `Default_Value` aspect, the initial value of objects declared
+    /// without one. It must be in the static `range` of the type.
+    pub fn default_value(
+        &self,
+        name: &Ident,
+        range: Option<(i64, i64)>,
+        ctx: &mut Context,
+    ) -> Result<Option<i64>> {
+        let Some(expr) = &self.default_value else {
+            return Ok(None);
+        };
+        let value = static_value(expr, ctx)?;
+        if range.is_some_and(|(first, last)| value < first || last < value) {
+            return Err(
+                expr.unrecoverable_error(format!("default value not in the range of `{name}`"))
+            );
+        }
+        Ok(Some(value))
+    }
+
+    /// The value of the `Size` clause, at least `min_size` and at most 64 bits for
+    /// discrete types.
+    pub fn size(
+        &self,
+        name: &Ident,
+        min_size: Option<i64>,
+        ctx: &mut Context,
+    ) -> Result<Option<i64>> {
+        let Some(clause) = &self.size else {
+            return Ok(None);
+        };
+        let size = static_value(&clause.expr, ctx)?;
+        if let Some(min_size) = min_size {
+            if size < min_size {
+                return Err(clause.expr.unrecoverable_error(format!(
+                    "size for `{name}` too small, minimum allowed is {min_size}"
+                )));
+            }
+            if size > 64 {
+                return Err(clause.expr.unrecoverable_error(format!(
+                    "size for `{name}` too large, maximum allowed is 64"
+                )));
+            }
+        } else if size < 0 {
+            return Err(clause.expr.unrecoverable_error("size must not be negative"));
+        }
+        Ok(Some(size))
+    }
+
+    /// The GCC attributes for the `Alignment` clause (or `at mod` of the record
+    /// representation clause).
+    pub fn attributes(&self, ctx: &mut Context) -> Result<c::TypeAttributes> {
+        let mut attributes = c::TypeAttributes::default();
+        if let Some(expr) = &self.alignment {
+            let alignment = static_value(expr, ctx)?;
+            if alignment <= 0 || alignment & (alignment - 1) != 0 {
+                return Err(expr.unrecoverable_error("alignment must be a power of two"));
+            }
+            attributes.aligned = Some(alignment as u64);
+        }
+        Ok(attributes)
+    }
+
+    /// The codes of the enumeration literals `values` from the enumeration
+    /// representation clause, `None` without a clause.
+    pub fn enum_codes(&self, values: &[Ident], ctx: &mut Context) -> Result<Option<Vec<i64>>> {
+        let Some(clause) = &self.enum_ else {
+            return Ok(None);
+        };
+        let AggregateExpr::Record(aggregate) = &clause.aggregate;
+        let mut codes = vec![None; values.len()];
+        for (i, association) in aggregate.associations.iter().enumerate() {
+            let names = match &association.choices {
+                None => {
+                    let Some(code) = codes.get_mut(i) else {
+                        return Err(association.unrecoverable_error("too many enumeration codes"));
+                    };
+                    *code = Some(enum_code(&association.expr, ctx)?);
+                    continue;
+                }
+                Some((ComponentChoices::Names(names), _)) => names,
+                Some((ComponentChoices::Others(others), _)) => {
+                    return Err(others.unrecoverable_error(
+                        "`others` is not allowed in an enumeration representation clause",
+                    ));
+                }
+            };
+            for name in names.iter() {
+                let Some(position) = values.iter().position(|value| value.name == name.name) else {
+                    return Err(name.unrecoverable_error(format!(
+                        "`{name}` is not a literal of `{}`",
+                        clause.name
+                    )));
+                };
+                if codes[position].is_some() {
+                    return Err(name.unrecoverable_error(format!("duplicate code for `{name}`")));
+                }
+                codes[position] = Some(enum_code(&association.expr, ctx)?);
+            }
+        }
+        let codes = codes
+            .into_iter()
+            .zip(values)
+            .map(|(code, value)| {
+                code.ok_or_else(|| {
+                    clause
+                        .aggregate
+                        .unrecoverable_error(format!("missing code for `{value}`"))
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        if codes.windows(2).any(|pair| pair[0] >= pair[1]) {
+            return Err(clause
+                .aggregate
+                .unrecoverable_error("enumeration codes must be increasing"));
+        }
+        Ok(Some(codes))
+    }
+
+    /// The fields of a record in the layout of the record representation clause and the
+    /// `Size` clause: bit-fields for discrete components, padding for the gaps, in a packed
+    /// struct. `None` keeps the default layout of the C compiler.
+    pub fn record_layout(
+        &self,
+        record: &RecordType,
+        fields: &[c::VarDecl],
+        size: Option<i64>,
+        ctx: &mut Context,
+    ) -> Result<Option<Vec<c::VarDecl>>> {
+        let Some(clause) = &self.record else {
+            return Ok(None);
+        };
+        let mut fields = fields.to_vec();
+
+        // (first bit, size, field index, clause) of the components with a component clause
+        let mut placed = Vec::new();
+        for component in &clause.components {
+            let Some(index) = record.fields.get_index_of(&component.name.name) else {
+                return Err(component.name.unrecoverable_error(format!(
+                    "`{}` is not a component of `{}`",
+                    component.name, record.name
+                )));
+            };
+            if placed.iter().any(|&(_, _, i, _)| i == index) {
+                return Err(component
+                    .name
+                    .unrecoverable_error("component clause already specified"));
+            }
+            let position = static_value(&component.position, ctx)?;
+            let first_bit = static_value(&component.range.start, ctx)?;
+            let last_bit = static_value(&component.range.end, ctx)?;
+            let bits = last_bit - first_bit + 1;
+            if position < 0 || first_bit < 0 || bits <= 0 {
+                return Err(component.unrecoverable_error("invalid component position"));
+            }
+            let (_, field) = record.fields.get_index(index).unwrap();
+            match field.ty.static_range() {
+                Some(range) => {
+                    let (first, last) = field.ty.static_bounds().unwrap_or(range);
+                    let min_size = field
+                        .ty
+                        .static_size()
+                        .unwrap_or_else(|| min_size(first, last));
+                    if bits < min_size || bits > 64 {
+                        return Err(component.range.unrecoverable_error(format!(
+                            "size for `{}` must be between {min_size} and 64",
+                            component.name
+                        )));
+                    }
+                    fields[index] =
+                        c::VarDecl::new(bit_field_type(first), fields[index].name.clone())
+                            .bits(bits as u32);
+                }
+                None => {
+                    if first_bit % STORAGE_UNIT != 0 || bits % STORAGE_UNIT != 0 {
+                        return Err(component.range.unrecoverable_error(format!(
+                            "component `{}` of a composite type must be at a storage element boundary",
+                            component.name
+                        )));
+                    }
+                }
+            }
+            placed.push((position * STORAGE_UNIT + first_bit, bits, index, component));
+        }
+        placed.sort_by_key(|&(offset, ..)| offset);
+
+        let pad = |layout: &mut Vec<c::VarDecl>, mut bits: i64| {
+            while bits > 0 {
+                let chunk = bits.min(32);
+                layout.push(c::VarDecl::padding(
+                    c::Type::named("unsigned"),
+                    chunk as u32,
+                ));
+                bits -= chunk;
+            }
+        };
+
+        let mut layout = Vec::new();
+        let mut end = 0;
+        for &(offset, bits, index, component) in &placed {
+            if offset < end {
+                return Err(component.unrecoverable_error("component overlaps another component"));
+            }
+            pad(&mut layout, offset - end);
+            layout.push(fields[index].clone());
+            end = offset + bits;
+        }
+        // the components without a component clause follow the placed ones
+        for (index, field) in fields.into_iter().enumerate() {
+            if !placed.iter().any(|&(_, _, i, _)| i == index) {
+                layout.push(field);
+            }
+        }
+        if let Some(size) = size {
+            if placed.len() == record.fields.len() {
+                if size < end {
+                    return Err(self
+                        .size
+                        .as_ref()
+                        .unwrap()
+                        .expr
+                        .unrecoverable_error(format!(
+                            "size for `{}` too small, minimum allowed is {end}",
+                            record.name
+                        )));
+                }
+                pad(&mut layout, size - end);
+            }
+        }
+        Ok(Some(layout))
+    }
+}
+
+/// Record the representation clauses of a declarative part, before its types are
+/// generated, because the clauses follow the type declarations.
+pub(super) fn declare_representations<'a>(
+    items: impl IntoIterator<Item = &'a Item>,
+    ctx: &mut Context,
+) -> Result<()> {
+    for item in items {
+        let Item::Representation(clause) = item else {
+            continue;
+        };
+        let name = clause.local_name();
+        let mut representation = ctx.representation(name).cloned().unwrap_or_default();
+        let specified = match clause {
+            RepresentationClause::Attribute(clause) => match &*clause.designator.name {
+                "size" => representation.size.replace(clause.clone()).is_some(),
+                "alignment" => representation
+                    .alignment
+                    .replace(clause.expr.clone())
+                    .is_some(),
+                _ => false,
+            },
+            RepresentationClause::Enum(clause) => {
+                representation.enum_.replace(clause.clone()).is_some()
+            }
+            RepresentationClause::Record(clause) => {
+                if let Some(alignment) = &clause.alignment {
+                    if representation
+                        .alignment
+                        .replace(alignment.expr.clone())
+                        .is_some()
+                    {
+                        return Err(alignment.unrecoverable_error(format!(
+                            "alignment of `{name}` already specified"
+                        )));
+                    }
+                }
+                representation.record.replace(clause.clone()).is_some()
+            }
+        };
+        if specified {
+            return Err(
+                clause.unrecoverable_error(format!("representation of `{name}` already specified"))
+            );
+        }
+        ctx.set_representation(name, representation);
+    }
+    Ok(())
+}
+
+/// Record the aspects of a type declaration, other aspects are ignored with a warning.
+pub(super) fn declare_aspects(item: &FullTypeItem, ctx: &mut Context) -> Result<()> {
+    let Some(aspects) = &item.aspects else {
+        return Ok(());
+    };
+    let mut representation = ctx.representation(&item.name).cloned().unwrap_or_default();
+    for aspect in aspects.iter() {
+        match &*aspect.name.name {
+            "default_value" => {
+                if !matches!(
+                    item.definition,
+                    TypeDefinition::Signed(_) | TypeDefinition::Modular(_)
+                ) {
+                    return Err(aspect
+                        .name
+                        .unimplemented_error("`Default_Value` of this type not yet implemented"));
+                }
+                let value = aspect
+                    .value()
+                    .ok_or_else(|| aspect.name.unrecoverable_error("expected a default value"))?;
+                representation.default_value = Some(value.clone());
+            }
+            _ => ctx.warn(
+                aspect.name.span(),
+                format!("unknown aspect `{}` ignored", aspect.name),
+            ),
+        }
+    }
+    ctx.set_representation(&item.name, representation);
+    Ok(())
+}
+
+impl CodeGenStmt for RepresentationClause {
+    /// The clauses were applied by the declaration of the type, check that they fit it.
+    fn generate(&self, ctx: &mut Context) -> Result<Vec<c::Stmt>> {
+        let name = self.local_name();
+        if !ctx.declares(name) {
+            return Err(name.unrecoverable_error(format!(
+                "`{name}` must be declared before its representation clause in the same declarative part"
+            )));
+        }
+        let ty = Type::from_ident(name, ctx)?;
+        match self {
+            Self::Attribute(clause) => match &*clause.designator.name {
+                "size" if ty.static_range().is_some() || ty.as_record().is_some() => {}
+                "size" => {
+                    return Err(
+                        name.unrecoverable_error("size clause needs a discrete or record type")
+                    );
+                }
+                "alignment" => {}
+                "component_size" => {
+                    return Err(
+                        name.unrecoverable_error("`Component_Size` clause needs an array type")
+                    );
+                }
+                _ => {
+                    return Err(clause.designator.unimplemented_error(format!(
+                        "attribute definition clause for `{}` not yet implemented",
+                        clause.designator
+                    )));
+                }
+            },
+            Self::Enum(_) if ty.is_enum() => {}
+            Self::Enum(_) => {
+                return Err(name.unrecoverable_error(
+                    "enumeration representation clause needs an enumeration type",
+                ));
+            }
+            Self::Record(_) if ty.as_record().is_some() => {}
+            Self::Record(_) => {
+                return Err(
+                    name.unrecoverable_error("record representation clause needs a record type")
+                );
+            }
+        }
+        Ok(Vec::new())
+    }
+}
+
+fn static_value(expr: &Expr, ctx: &mut Context) -> Result<i64> {
+    static_integer(expr, ctx)
+        .ok_or_else(|| expr.unrecoverable_error("expected a static integer expression"))
+}
+
+/// An enumeration code, the C type of enumerations is `int`.
+fn enum_code(expr: &Expr, ctx: &mut Context) -> Result<i64> {
+    let code = static_value(expr, ctx)?;
+    if i32::try_from(code).is_err() {
+        return Err(expr.unrecoverable_error("enumeration code out of range"));
+    }
+    Ok(code)
+}
+
+/// The minimum number of bits of the range `first .. last`, without a sign bit if the
+/// range is not negative.
+pub(super) fn min_size(first: i64, last: i64) -> i64 {
+    let bits = |value: i64| 64 - i64::from(value.leading_zeros());
+    if first >= 0 {
+        bits(last).max(1)
+    } else {
+        bits(!first).max(bits(last.max(0))) + 1
+    }
+}
+
+/// Bit-fields of non-negative ranges are unsigned, so that all bits hold the value.
+fn bit_field_type(first: i64) -> c::Type {
+    c::Type::named(if first >= 0 { "size_t" } else { "ssize_t" })
+}
@@ -1,104 +1,339 @@
 use crate::{
     codegen::{ArgumentMode, ArgumentType, TypeValue},
-    parser::{EnumTypeDefinition, Expr, Range, SignedTypeDefinition},
+    runtime,
     tokenizer::{Ident, Span},
     Result, Token,
 };
 
-use super::{CCode, CodeGenType, Context, FunctionType, FunctionValue, IdentBuilder, Type, Value};
+use super::{
+    c, type_item, unit, Context, FunctionType, FunctionValue, IdentBuilder, Path, Type, UnitPart,
+    Value,
+};
 
 pub fn generate(ctx: &mut Context) -> Result<()> {
+    ctx.in_unit(
+        "standard",
+        runtime::STANDARD.file_name,
+        UnitPart::Spec,
+        generate_standard,
+    )
+}
+
+fn generate_standard(ctx: &mut Context) -> Result<()> {
     ctx.push_include("<stdlib.h>");
-    ctx.push_function(c_code! {
-        void throw_Constraint_Error() {
-            fprintf(stderr, "Error: Constraint_Error\n");
-            exit(1);
-        }
-    });
-
-    generate_boolean(ctx)?;
-    generate_integer(ctx)?;
-
-    for (ty, code, fmt) in [
-        (Type::string(), c_code! {char*}, "%s"),
-        (Type::character(), c_code! {char}, "%c"),
-    ] {
-        let name = Ident {
-            name: ty.to_str().into(),
-            span: Span::call_site(),
-        };
-        ctx.push_type(c_code! {
-            typedef #code #ty;
-        });
-        ctx.insert(&name, Value::Type(TypeValue { ty: ty.clone() }))?;
-        generate_print(ty, fmt, ctx)?;
-    }
+
+    // the positions of the characters are their codes, so the C type is unsigned
+    let character = Type::character();
+    let character_ident = character.c_type().name().clone();
+    ctx.push_type(c::Decl::Typedef(
+        c::Type::named("unsigned char"),
+        character_ident.clone(),
+        Default::default(),
+    ));
+    insert_builtin_type(&character, ctx)?;
+    let name = Ident {
+        name: character.to_str().into(),
+        span: Span::call_site(),
+    };
+    type_item::generate_enum_image(
+        IdentBuilder::image(&Path::default(), &name),
+        &character_ident,
+        &character_literals(),
+        None,
+        ctx,
+    );
+    type_item::generate_enum_succ_pred(
+        IdentBuilder::succ(&Path::default(), &name),
+        IdentBuilder::pred(&Path::default(), &name),
+        &character_ident,
+        256,
+        None,
+        ctx,
+    );
+    generate_print(character.clone(), "%c", ctx)?;
+
+    // the positions of Wide_Character after Latin-1 have no names, their images and
+    // UTF-8 encoding are in the C runtime
+    let wide_character = Type::wide_character();
+    let wide_character_ident = wide_character.c_type().name().clone();
+    ctx.push_type(c::Decl::Typedef(
+        c::Type::named("unsigned short"),
+        wide_character_ident.clone(),
+        Default::default(),
+    ));
+    insert_builtin_type(&wide_character, ctx)?;
+    let name = Ident {
+        name: wide_character.to_str().into(),
+        span: Span::call_site(),
+    };
+    let self_ = c::Expr::ident("self");
+    push_expr_function(
+        &Type::string(),
+        IdentBuilder::image(&Path::default(), &name),
+        vec![c::Param::new(
+            wide_character.c_type().const_(),
+            c::Ident::new("self"),
+        )],
+        self_
+            .clone()
+            .binary(c::BinaryOp::Lt, c::Expr::int(256))
+            .cond(
+                character.image().unwrap().clone().call([self_.clone()]),
+                c::Expr::ident("annabella_wide_character_image").call([self_.clone()]),
+            ),
+        ctx,
+    );
+    type_item::generate_enum_succ_pred(
+        IdentBuilder::succ(&Path::default(), &name),
+        IdentBuilder::pred(&Path::default(), &name),
+        &wide_character_ident,
+        65536,
+        None,
+        ctx,
+    );
+    generate_custom_print(
+        wide_character.clone(),
+        vec![c::Expr::ident("annabella_wide_character_print")
+            .call([self_])
+            .into()],
+        ctx,
+    )?;
+
+    // the fat pointer type of String is declared in the C runtime
+    let string = Type::string();
+    insert_builtin_type(&string, ctx)?;
+    let self_ = c::Expr::ident("self");
+    generate_custom_print(
+        string,
+        vec![c::Expr::ident("printf")
+            .call([
+                c::Expr::str("%.*s\n"),
+                c::Expr::ident("annabella_string_length")
+                    .call([self_.clone()])
+                    .cast(c::Type::named("int")),
+                self_.member(c::Ident::new("data")),
+            ])
+            .into()],
+        ctx,
+    )?;
+
+    unit::load_standard(ctx)?;
+    generate_boolean_logical_ops(&Type::boolean(ctx)?, ctx)?;
+    generate_comparison_ops(&character, ctx)?;
+    generate_comparison_ops(&wide_character, ctx)?;
+    generate_string_ops(ctx)?;
 
     Ok(())
 }
 
-fn generate_boolean(ctx: &mut Context) -> Result<()> {
-    let ident = Ident {
-        name: "boolean".into(),
-        span: Span::call_site(),
-    };
-    EnumTypeDefinition {
-        values: [
+/// Names of the control characters, the positions 0 to 31.
+const CONTROL_CHARACTERS: [&str; 32] = [
+    "nul", "soh", "stx", "etx", "eot", "enq", "ack", "bel", "bs", "ht", "lf", "vt", "ff", "cr",
+    "so", "si", "dle", "dc1", "dc2", "dc3", "dc4", "nak", "syn", "etb", "can", "em", "sub", "esc",
+    "fs", "gs", "rs", "us",
+];
+
+/// Names of the control characters of Latin-1, the positions 128 to 159.
+const LATIN_1_CONTROL_CHARACTERS: [&str; 32] = [
+    "reserved_128",
+    "reserved_129",
+    "bph",
+    "nbh",
+    "reserved_132",
+    "nel",
+    "ssa",
+    "esa",
+    "hts",
+    "htj",
+    "vts",
+    "pld",
+    "plu",
+    "ri",
+    "ss2",
+    "ss3",
+    "dcs",
+    "pu1",
+    "pu2",
+    "sts",
+    "cch",
+    "mw",
+    "spa",
+    "epa",
+    "sos",
+    "reserved_153",
+    "sci",
+    "csi",
+    "st",
+    "osc",
+    "pm",
+    "apc",
+];
+
+/// The literals of the type `Character`: the names of the control characters and the
+/// character literals of the graphic characters, by position.
+pub(super) fn character_literals() -> Vec<Ident> {
+    (0..=255u8)
+        .map(|position| {
+            let name = match position {
+                0..=31 => CONTROL_CHARACTERS[position as usize].to_string(),
+                127 => "del".to_string(),
+                128..=159 => LATIN_1_CONTROL_CHARACTERS[position as usize - 128].to_string(),
+                _ => format!("'{}'", char::from(position)),
+            };
             Ident {
-                name: "false".into(),
+                name: name.into(),
                 span: Span::call_site(),
+            }
+        })
+        .collect()
+}
+
+fn insert_builtin_type(ty: &Type, ctx: &mut Context) -> Result<()> {
+    let name = Ident {
+        name: ty.to_str().into(),
+        span: Span::call_site(),
+    };
+    ctx.insert(&name, Value::Type(TypeValue { ty: ty.clone() }))
+}
+
+fn binary_function_type(lhs: &Type, rhs: &Type, return_type: &Type) -> Type {
+    Type::function(FunctionType {
+        args: vec![
+            ArgumentType {
+                ty: lhs.clone(),
+                mode: ArgumentMode::In,
             },
-            Ident {
-                name: "true".into(),
-                span: Span::call_site(),
+            ArgumentType {
+                ty: rhs.clone(),
+                mode: ArgumentMode::In,
             },
-        ]
-        .into_iter()
-        .collect(),
-    }
-    .generate(&ident, ctx)?;
+        ],
+        return_type: return_type.clone(),
+        static_link: None,
+    })
+}
 
-    generate_boolean_logical_ops(&Type::boolean(ctx)?, ctx)
+/// Parameters `lhs` and `rhs` of a binary operator.
+fn binary_params(lhs: &Type, rhs: &Type) -> Vec<c::Param> {
+    vec![
+        c::Param::new(lhs.c_type().const_(), c::Ident::new("lhs")),
+        c::Param::new(rhs.c_type().const_(), c::Ident::new("rhs")),
+    ]
 }
 
-fn generate_integer(ctx: &mut Context) -> Result<()> {
-    let ident = Ident {
-        name: "integer".into(),
-        span: Span::call_site(),
-    };
-    SignedTypeDefinition {
-        range_keyword: Default::default(),
-        range: Range {
-            start: Expr::number(i32::MIN),
-            dot_dot: Default::default(),
-            end: Expr::number(i32::MAX),
-        },
+/// Push a function returning the value of a single expression.
+fn push_expr_function(
+    return_type: &Type,
+    name: c::Ident,
+    params: Vec<c::Param>,
+    expr: c::Expr,
+    ctx: &mut Context,
+) {
+    ctx.push_function(c::Decl::Function(c::Function {
+        return_type: return_type.c_type(),
+        name,
+        params,
+        body: Some(vec![c::Stmt::return_(expr)]),
+        extern_c: false,
+    }));
+}
+
+/// Concatenation and comparison of strings, implemented in the C runtime.
+fn generate_string_ops(ctx: &mut Context) -> Result<()> {
+    let string = Type::string();
+    let character = Type::character();
+    let boolean = Type::boolean(ctx)?;
+
+    let op: Token![&] = Default::default();
+    for (lhs, rhs) in [
+        (&string, &string),
+        (&string, &character),
+        (&character, &string),
+        (&character, &character),
+    ] {
+        let ident = IdentBuilder::binary_op_function(ctx.path(), op, lhs, rhs);
+        let operand = |ty: &Type, name| {
+            let code = c::Expr::ident(name);
+            if ty.is_string() {
+                code
+            } else {
+                c::Expr::ident("annabella_string_from_element").call([code])
+            }
+        };
+        let code = c::Expr::ident("annabella_string_concat")
+            .call([operand(lhs, "lhs"), operand(rhs, "rhs")]);
+        push_expr_function(&string, ident.clone(), binary_params(lhs, rhs), code, ctx);
+        ctx.insert(
+            &op.operator_symbol(),
+            Value::Function(FunctionValue::new(
+                ident,
+                binary_function_type(lhs, rhs, &string),
+            )),
+        )?;
+    }
+
+    macro_rules! comparison_ops {
+        ($($ada:tt $c:tt)*) => {
+            $(
+                let op: Token![$ada] = Default::default();
+                let ident = IdentBuilder::op_function(ctx.path(), op, &string);
+                let code = c::Expr::ident("annabella_string_compare")
+                    .call([c::Expr::ident("lhs"), c::Expr::ident("rhs")])
+                    .binary(c::BinaryOp::$c, c::Expr::int(0));
+                push_expr_function(
+                    &boolean,
+                    ident.clone(),
+                    binary_params(&string, &string),
+                    code,
+                    ctx,
+                );
+                ctx.insert(
+                    &op.operator_symbol(),
+                    Value::Function(FunctionValue::new(
+                        ident,
+                        binary_function_type(&string, &string, &boolean),
+                    )),
+                )?;
+            )*
+        };
+    }
+
+    comparison_ops! {
+        = Eq
+        /= Ne
+        < Lt
+        <= Le
+        > Gt
+        >= Ge
     }
-    .generate(&ident, ctx)?;
 
     Ok(())
 }
 
+macro_rules! unary_op {
+    ($ty:expr, $ada:tt, $code:expr, $ctx:expr) => {{
+        let op: Token![$ada] = Default::default();
+        generate_unary_op($ty, op, op.operator_symbol(), $code, $ctx)
+    }};
+}
+
 pub(crate) fn generate_signed_ops(ty: &Type, ctx: &mut Context) -> Result<()> {
+    let check = |code: c::Expr| match ty.needs_constraint_check(&Type::integer()) {
+        Some(constraint_check) => constraint_check.clone().call([code]),
+        None => code,
+    };
     macro_rules! integer_ops {
         ($($ada:tt $c:tt)*) => {
             $(
                 let op: Token![$ada] = Default::default();
-                let ident = IdentBuilder::op_function(op, ty);
-                let mut code = c_code! { lhs $c rhs };
-                if let Some(constraint_check) = ty.needs_constraint_check(&Type::integer()) {
-                    code = c_code! { #constraint_check(#code) }
-                }
-                ctx.push_function(c_code! {
-                    #ty #ident(#ty const lhs, #ty const rhs) {
-                        return #code;
-                    }
-                });
+                let ident = IdentBuilder::op_function(ctx.path(), op, ty);
+                let code = c::Expr::ident("lhs").binary(c::BinaryOp::$c, c::Expr::ident("rhs"));
+                push_expr_function(ty, ident.clone(), binary_params(ty, ty), check(code), ctx);
 
                 ctx.insert(
                     &op.operator_symbol(),
                     Value::Function(FunctionValue::new(
-                         c_code! { #ident },
+                         ident,
                          Type::function(FunctionType {
                             args: vec![
                                 ArgumentType {
@@ -111,6 +346,7 @@ pub(crate) fn generate_signed_ops(ty: &Type, ctx: &mut Context) -> Result<()> {
                                 },
                             ],
                             return_type: ty.clone(),
+                            static_link: None,
                         })
                     )),
                 )?;
@@ -120,38 +356,114 @@ pub(crate) fn generate_signed_ops(ty: &Type, ctx: &mut Context) -> Result<()> {
     }
 
     integer_ops! {
-        + +
-        - -
-        * *
-        / /
-        and &
-        or |
-        xor ^
+        + Add
+        - Sub
+        * Mul
+        / Div
+        and BitAnd
+        or BitOr
+        xor BitXor
+    }
+
+    let self_ = c::Expr::ident("self");
+    let neg = c::Expr::unary(c::UnaryOp::Neg, self_.clone());
+    unary_op!(ty, -, check(neg.clone()), ctx)?;
+    unary_op!(ty, +, self_.clone(), ctx)?;
+    let code = self_
+        .clone()
+        .binary(c::BinaryOp::Lt, c::Expr::int(0))
+        .cond(neg, self_);
+    unary_op!(ty, abs, check(code), ctx)?;
+
+    generate_comparison_ops(ty, ctx)
+}
+
+/// Operators of a floating or fixed point type.
+pub(crate) fn generate_real_ops(ty: &Type, ctx: &mut Context) -> Result<()> {
+    let check = |code: c::Expr| match ty.needs_constraint_check(&Type::real()) {
+        Some(constraint_check) => constraint_check.clone().call([code]),
+        None => code,
+    };
+    macro_rules! real_ops {
+        ($($ada:tt $c:tt)*) => {
+            $(
+                let op: Token![$ada] = Default::default();
+                let ident = IdentBuilder::op_function(ctx.path(), op, ty);
+                let code = c::Expr::ident("lhs").binary(c::BinaryOp::$c, c::Expr::ident("rhs"));
+                push_expr_function(ty, ident.clone(), binary_params(ty, ty), check(code), ctx);
+
+                ctx.insert(
+                    &op.operator_symbol(),
+                    Value::Function(FunctionValue::new(ident, binary_function_type(ty, ty, ty))),
+                )?;
+            )*
+        };
+    }
+
+    real_ops! {
+        + Add
+        - Sub
+        * Mul
+        / Div
     }
 
+    let self_ = c::Expr::ident("self");
+    let neg = c::Expr::unary(c::UnaryOp::Neg, self_.clone());
+    unary_op!(ty, -, check(neg.clone()), ctx)?;
+    unary_op!(ty, +, self_.clone(), ctx)?;
+    let code = self_
+        .clone()
+        .binary(c::BinaryOp::Lt, c::Expr::float(0.0))
+        .cond(neg, self_);
+    unary_op!(ty, abs, check(code), ctx)?;
+
     generate_comparison_ops(ty, ctx)
 }
 
-pub(crate) fn generate_modular_ops(ty: &Type, modulus: &CCode, ctx: &mut Context) -> Result<()> {
+fn generate_unary_op(
+    ty: &Type,
+    op: impl crate::parser::token::Token,
+    symbol: Ident,
+    code: c::Expr,
+    ctx: &mut Context,
+) -> Result<()> {
+    let ident = IdentBuilder::unary_op_function(ctx.path(), op, ty);
+    let params = vec![c::Param::new(ty.c_type().const_(), c::Ident::new("self"))];
+    push_expr_function(ty, ident.clone(), params, code, ctx);
+
+    ctx.insert(
+        &symbol,
+        Value::Function(FunctionValue::new(
+            ident,
+            Type::function(FunctionType {
+                args: vec![ArgumentType {
+                    ty: ty.clone(),
+                    mode: ArgumentMode::In,
+                }],
+                return_type: ty.clone(),
+                static_link: None,
+            }),
+        )),
+    )
+}
+
+pub(crate) fn generate_modular_ops(ty: &Type, modulus: &c::Expr, ctx: &mut Context) -> Result<()> {
+    let modulo = |code: c::Expr| code.binary(c::BinaryOp::Rem, modulus.clone());
     macro_rules! integer_ops {
-        ($($ada:tt $c:tt {$($($map:tt)+)?})*) => {
+        ($($ada:tt $c:ident $(+ $add:expr)?;)*) => {
             $(
                 let op: Token![$ada] = Default::default();
-                let ident = IdentBuilder::op_function(op, ty);
-                let code = c_code! { (lhs $c rhs) };
+                let ident = IdentBuilder::op_function(ctx.path(), op, ty);
+                let code = c::Expr::ident("lhs").binary(c::BinaryOp::$c, c::Expr::ident("rhs"));
                 $(
-                    let code = c_code! { (#code $($map)*) };
+                    let code = code.binary(c::BinaryOp::Add, $add);
                 )?
-                ctx.push_function(c_code! {
-                    #ty #ident(#ty const lhs, #ty const rhs) {
-                        return #code % #modulus;
-                    }
-                });
+                push_expr_function(ty, ident.clone(), binary_params(ty, ty), modulo(code), ctx);
 
                 ctx.insert(
                     &op.operator_symbol(),
                     Value::Function(FunctionValue::new(
-                         c_code! { #ident },
+                         ident,
                          Type::function(FunctionType {
                             args: vec![
                                 ArgumentType {
@@ -164,6 +476,7 @@ pub(crate) fn generate_modular_ops(ty: &Type, modulus: &CCode, ctx: &mut Context
                                 },
                             ],
                             return_type: ty.clone(),
+                            static_link: None,
                         })
                     )),
                 )?;
@@ -173,15 +486,25 @@ pub(crate) fn generate_modular_ops(ty: &Type, modulus: &CCode, ctx: &mut Context
     }
 
     integer_ops! {
-        + + {}
-        - - { + #modulus}
-        * * {}
-        / / {}
-        and & {}
-        or | {}
-        xor ^ {}
+        + Add;
+        - Sub + modulus.clone();
+        * Mul;
+        / Div;
+        and BitAnd;
+        or BitOr;
+        xor BitXor;
     }
 
+    let self_ = c::Expr::ident("self");
+    let code = modulo(modulus.clone().binary(c::BinaryOp::Sub, self_.clone()));
+    unary_op!(ty, -, code, ctx)?;
+    unary_op!(ty, +, self_.clone(), ctx)?;
+    let code = modulus
+        .clone()
+        .binary(c::BinaryOp::Sub, c::Expr::int(1))
+        .binary(c::BinaryOp::BitXor, self_);
+    unary_op!(ty, not, code, ctx)?;
+
     generate_comparison_ops(ty, ctx)
 }
 
@@ -190,17 +513,14 @@ pub(crate) fn generate_boolean_logical_ops(ty: &Type, ctx: &mut Context) -> Resu
         ($($ada:tt $c:tt)*) => {
             $(
                 let op: Token![$ada] = Default::default();
-                let ident = IdentBuilder::op_function(op, ty);
-                ctx.push_function(c_code! {
-                    #ty #ident(#ty const lhs, #ty const rhs) {
-                        return lhs $c rhs;
-                    }
-                });
+                let ident = IdentBuilder::op_function(ctx.path(), op, ty);
+                let code = c::Expr::ident("lhs").binary(c::BinaryOp::$c, c::Expr::ident("rhs"));
+                push_expr_function(ty, ident.clone(), binary_params(ty, ty), code, ctx);
 
                 ctx.insert(
                     &op.operator_symbol(),
                     Value::Function(FunctionValue::new(
-                         c_code! { #ident },
+                         ident,
                          Type::function(FunctionType {
                             args: vec![
                                 ArgumentType {
@@ -213,6 +533,7 @@ pub(crate) fn generate_boolean_logical_ops(ty: &Type, ctx: &mut Context) -> Resu
                                 },
                             ],
                             return_type: ty.clone(),
+                            static_link: None,
                         })
                     )),
                 )?;
@@ -222,12 +543,17 @@ pub(crate) fn generate_boolean_logical_ops(ty: &Type, ctx: &mut Context) -> Resu
     }
 
     boolean_ops! {
-        and &&
-        or ||
-        xor ^
+        and And
+        or Or
+        xor BitXor
     }
 
-    Ok(())
+    unary_op!(
+        ty,
+        not,
+        c::Expr::unary(c::UnaryOp::Not, c::Expr::ident("self")),
+        ctx
+    )
 }
 
 pub(crate) fn generate_comparison_ops(ty: &Type, ctx: &mut Context) -> Result<()> {
@@ -236,17 +562,14 @@ pub(crate) fn generate_comparison_ops(ty: &Type, ctx: &mut Context) -> Result<()
         ($($ada:tt $c:tt)*) => {
             $(
                 let op: Token![$ada] = Default::default();
-                let ident = IdentBuilder::op_function(op, ty);
-                ctx.push_function(c_code! {
-                    #boolean #ident(#ty const lhs, #ty const rhs) {
-                        return lhs $c rhs;
-                    }
-                });
+                let ident = IdentBuilder::op_function(ctx.path(), op, ty);
+                let code = c::Expr::ident("lhs").binary(c::BinaryOp::$c, c::Expr::ident("rhs"));
+                push_expr_function(&boolean, ident.clone(), binary_params(ty, ty), code, ctx);
 
                 ctx.insert(
                     &op.operator_symbol(),
                     Value::Function(FunctionValue::new(
-                         c_code! { #ident },
+                         ident,
                          Type::function(FunctionType {
                             args: vec![
                                 ArgumentType {
@@ -259,6 +582,7 @@ pub(crate) fn generate_comparison_ops(ty: &Type, ctx: &mut Context) -> Result<()
                                 },
                             ],
                             return_type: boolean.clone(),
+                            static_link: None,
                         })
                     )),
                 )?;
@@ -268,25 +592,21 @@ pub(crate) fn generate_comparison_ops(ty: &Type, ctx: &mut Context) -> Result<()
     }
 
     integer_ops! {
-        = ==
-        /= !=
-        < <
-        <= <=
-        > >
-        >= >=
+        = Eq
+        /= Ne
+        < Lt
+        <= Le
+        > Gt
+        >= Ge
     }
 
     Ok(())
 }
 
 pub fn generate_print(ty: Type, fmt: &'static str, ctx: &mut Context) -> Result<()> {
-    generate_custom_print(
-        ty,
-        c_code! {
-            printf(#fmt "\n", self);
-        },
-        ctx,
-    )
+    let code =
+        c::Expr::ident("printf").call([c::Expr::str(format!("{fmt}\n")), c::Expr::ident("self")]);
+    generate_custom_print(ty, vec![code.into()], ctx)
 }
 
 pub fn print() -> Ident {
@@ -296,26 +616,29 @@ pub fn print() -> Ident {
     }
 }
 
-pub fn generate_custom_print(ty: Type, code: CCode, ctx: &mut Context) -> Result<()> {
+pub fn generate_custom_print(ty: Type, body: Vec<c::Stmt>, ctx: &mut Context) -> Result<()> {
     let print = print();
 
-    let ident = IdentBuilder::print(&ty);
-    ctx.push_function(c_code! {
-        void #ident(#ty const self) {
-            #code
-        }
-    });
+    let ident = IdentBuilder::print(ctx.path(), &ty);
+    ctx.push_function(c::Decl::Function(c::Function {
+        return_type: c::Type::void(),
+        name: ident.clone(),
+        params: vec![c::Param::new(ty.c_type().const_(), c::Ident::new("self"))],
+        body: Some(body),
+        extern_c: false,
+    }));
 
     ctx.insert(
         &print,
         Value::Function(FunctionValue::new(
-            c_code! { #ident },
+            ident,
             Type::function(FunctionType {
                 args: vec![ArgumentType {
                     ty,
                     mode: ArgumentMode::In,
                 }],
                 return_type: Type::void(),
+                static_link: None,
             }),
         )),
     )?;
@@ -1,63 +1,178 @@
 use crate::{
     codegen::IdentBuilder,
     parser::{
-        AssignStmt, BlockStmt, CaseStmt, DiscreteChoice, ExitStmt, ExprStmt, GotoStmt, IfStmt,
-        LabelStmt, LoopScheme, LoopStmt, ReturnStmt, Stmt,
+        AssignStmt, BlockStmt, CaseStmt, DelayStmt, DiscreteChoice, DiscreteSubtypeDefinition,
+        ExceptionChoice, ExceptionHandlers, ExitStmt, ExprStmt, ExtendedReturnStmt, GotoStmt,
+        IfStmt, IteratorSpecification, LabelStmt, LoopParameter, LoopParameterSpecification,
+        LoopScheme, LoopStmt, Name, RaiseStmt, Range, ReturnStmt, Stmt, Variable,
     },
     tokenizer::Spanned,
     Result,
 };
 
 use super::{
-    CCode, CodeGenExpr, CodeGenStmt, Context, ExprValue, LabelValue, Permission, SingleExprValue,
-    Type, Value, VariableValue,
+    c,
+    expr::generate_string_prefix,
+    pragma::{declare_interfaces, PragmaPlace},
+    representation::declare_representations,
+    CodeGenExpr, CodeGenStmt, Context, ExprValue, LabelValue, Language, Path, Permission,
+    SingleExprValue, Type, Value, VariableValue,
 };
 
 impl CodeGenStmt for Stmt {
-    fn generate(&self, ctx: &mut Context) -> Result<CCode> {
+    fn generate(&self, ctx: &mut Context) -> Result<Vec<c::Stmt>> {
         match self {
             Self::Label(stmt) => stmt.generate(ctx),
-            Self::Expr(stmt) => stmt.generate(ctx),
-            Self::Assign(stmt) => stmt.generate(ctx),
+            Self::Expr(stmt) => Ok(secondary_stack_scope(stmt.generate(ctx)?)),
+            Self::Assign(stmt) => Ok(secondary_stack_scope(stmt.generate(ctx)?)),
             Self::Return(stmt) => stmt.generate(ctx),
+            Self::ExtendedReturn(stmt) => stmt.generate(ctx),
             Self::If(stmt) => stmt.generate(ctx),
             Self::Block(stmt) => stmt.generate(ctx),
             Self::Goto(stmt) => stmt.generate(ctx),
             Self::Loop(stmt) => stmt.generate(ctx),
             Self::Exit(stmt) => stmt.generate(ctx),
             Self::Case(stmt) => stmt.generate(ctx),
+            Self::Null(_) => Ok(Vec::new()),
+            Self::Raise(stmt) => stmt.generate(ctx),
+            Self::Delay(stmt) => stmt.generate(ctx),
+            Self::Abort(stmt) => Err(stmt
+                .abort
+                .unimplemented_error("abort statement not supported, tasks not yet implemented")),
+            Self::Requeue(stmt) => Err(stmt.requeue.unimplemented_error(
+                "requeue statement not supported, entries not yet implemented",
+            )),
+            Self::Pragma(stmt) => stmt.generate_in(PragmaPlace::Statement, ctx),
         }
     }
 }
 
+/// Release the temporaries a simple statement allocated on the secondary stack.
+fn secondary_stack_scope(stmts: Vec<c::Stmt>) -> Vec<c::Stmt> {
+    let mark = c::Ident::new("annabella_ss_stmt");
+    let mut block =
+        vec![
+            c::VarDecl::new(c::Type::named("annabella_ss_mark_t").const_(), mark.clone())
+                .init(c::Expr::ident("annabella_ss_mark").call([]))
+                .into(),
+        ];
+    block.extend(stmts);
+    block.push(
+        c::Expr::ident("annabella_ss_release")
+            .call([mark.into()])
+            .into(),
+    );
+    vec![c::Stmt::Block(block)]
+}
+
+/// Generate a sequence of statements.
+pub(super) fn generate_stmts(stmts: &[Stmt], ctx: &mut Context) -> Result<Vec<c::Stmt>> {
+    let mut code = Vec::new();
+    for stmt in stmts {
+        code.extend(ctx.recover(stmt.kind(), |ctx| stmt.generate(ctx)));
+    }
+    Ok(code)
+}
+
+/// The statements of a body or a block and their exception handlers. In C++ they are a
+/// `try` block, its `catch` releases the secondary stack of the statements, chooses the
+/// handler by the name of the exception and raises it again when no handler matches.
+pub(super) fn generate_handled_stmts(
+    stmts: &[Stmt],
+    handlers: Option<&ExceptionHandlers>,
+    ctx: &mut Context,
+) -> Result<Vec<c::Stmt>> {
+    let body = generate_stmts(stmts, ctx)?;
+    let Some(handlers) = handlers else {
+        return Ok(body);
+    };
+    if ctx.language() != Language::Cxx {
+        return Err(handlers
+            .exception
+            .unimplemented_error("exception handlers not yet implemented in C"));
+    }
+    let occurrence = c::Ident::new("annabella_occurrence");
+    let mut chain = vec![c::Expr::ident("annabella_reraise").call([]).into()];
+    for (index, handler) in handlers.handlers.iter().enumerate().rev() {
+        if let Some((param, _)) = &handler.param {
+            return Err(param.unimplemented_error("choice parameter not yet implemented"));
+        }
+        let mut cond: Option<c::Expr> = None;
+        for choice in handler.choices.iter() {
+            let name = match choice {
+                ExceptionChoice::Others(others) => {
+                    if handler.choices.len() > 1 || index + 1 < handlers.handlers.len() {
+                        return Err(others.unrecoverable_error(
+                            "`others` must be the only choice of the last handler",
+                        ));
+                    }
+                    continue;
+                }
+                ExceptionChoice::Name(name) => name,
+            };
+            let Some(exception) = name.resolve_exception(ctx)? else {
+                return Err(name.unrecoverable_error("not an exception name"));
+            };
+            let test = c::Expr::ident("annabella_exception_is")
+                .call([occurrence.clone().into(), c::Expr::str(&*exception)]);
+            cond = Some(match cond {
+                Some(cond) => cond.binary(c::BinaryOp::Or, test),
+                None => test,
+            });
+        }
+        let mut sub_ctx = ctx.subscope(ctx.return_type());
+        sub_ctx.enter_handler();
+        let then = generate_stmts(&handler.stmts, &mut sub_ctx)?;
+        chain = match cond {
+            Some(cond) => vec![c::Stmt::If {
+                cond,
+                then,
+                else_: Some(chain),
+            }],
+            None => then,
+        };
+    }
+    let mark = c::Ident::new("annabella_ss_handler");
+    let mut handler = vec![c::Expr::ident("annabella_ss_release")
+        .call([mark.clone().into()])
+        .into()];
+    handler.extend(chain);
+    Ok(vec![c::Stmt::Block(vec![
+        c::VarDecl::new(c::Type::named("annabella_ss_mark_t").const_(), mark)
+            .init(c::Expr::ident("annabella_ss_mark").call([]))
+            .into(),
+        c::Stmt::TryCatch {
+            body,
+            ty: c::Type::named("annabella_exception"),
+            name: occurrence,
+            handler,
+        },
+    ])])
+}
+
 impl CodeGenStmt for LabelStmt {
-    fn generate(&self, ctx: &mut Context) -> Result<CCode> {
+    fn generate(&self, ctx: &mut Context) -> Result<Vec<c::Stmt>> {
         let value = ctx.get_or_insert(&self.label, || {
-            let ident = IdentBuilder::label(&self.label);
             Value::Label(LabelValue {
-                name: c_code! { #ident },
+                name: IdentBuilder::label(&self.label),
             })
         });
         let Value::Label(LabelValue { name }) = value else {
             return Err(self.label.unrecoverable_error("expected label name"));
         };
-        Ok(c_code! {
-            #name:
-        })
+        Ok(vec![c::Stmt::Label(name.clone())])
     }
 }
 
 impl CodeGenStmt for ExprStmt {
-    fn generate(&self, ctx: &mut Context) -> Result<CCode> {
+    fn generate(&self, ctx: &mut Context) -> Result<Vec<c::Stmt>> {
         let expr = self.expr.generate_with_type_and_check(&Type::void(), ctx)?;
-        Ok(c_code! {
-            #expr;
-        })
+        Ok(vec![expr.into()])
     }
 }
 
 impl CodeGenStmt for AssignStmt {
-    fn generate(&self, ctx: &mut Context) -> Result<CCode> {
+    fn generate(&self, ctx: &mut Context) -> Result<Vec<c::Stmt>> {
         let expr = self.name.generate(ctx)?.flat_map(|name| {
             if !name.perm.can_write() {
                 return Err(self
@@ -66,13 +181,17 @@ impl CodeGenStmt for AssignStmt {
             }
 
             let expr = self.expr.generate_with_type_and_check(&name.ty, ctx)?;
+            let code = if name.ty.is_string() {
+                c::Expr::ident("annabella_string_assign").call([name.code, expr])
+            } else {
+                name.code.assign(expr)
+            };
             Ok(SingleExprValue {
                 ty: Type::void(),
                 perm: Permission::Read,
-                code: c_code! {
-                    #name = #expr;
-                },
+                code,
                 value: None,
+                subprogram: None,
             }
             .into())
         })?;
@@ -80,301 +199,557 @@ impl CodeGenStmt for AssignStmt {
             return Err(self.expr.unrecoverable_error("ambiguous assignment"));
         };
 
-        Ok(expr.code)
+        Ok(vec![expr.code.into()])
     }
 }
 
 impl CodeGenStmt for ReturnStmt {
-    fn generate(&self, ctx: &mut Context) -> Result<CCode> {
+    fn generate(&self, ctx: &mut Context) -> Result<Vec<c::Stmt>> {
+        match (&self.expr, ctx.return_type()) {
+            (Some(expr), Some(return_type)) => {
+                if ctx.return_object().is_some() {
+                    return Err(expr.unrecoverable_error(
+                        "return with a value is not allowed in an extended return statement",
+                    ));
+                }
+                let expr = expr.generate_with_type_and_check(&return_type, ctx)?;
+                Ok(vec![generate_return(expr, &return_type, ctx)])
+            }
+            (None, Some(return_type)) => match ctx.return_object() {
+                Some(object) => Ok(vec![generate_return(object, &return_type, ctx)]),
+                None => Err(self.return_.unrecoverable_error("missing return value")),
+            },
+            // the statements of a procedure body
+            (None, None) if ctx.level() > 0 => {
+                let mut block = ctx
+                    .postcondition()
+                    .map_or_else(Vec::new, |post| post.to_vec());
+                block.push(
+                    c::Expr::ident("annabella_ss_release")
+                        .call([c::Expr::ident("annabella_ss_entry")])
+                        .into(),
+                );
+                block.push(c::Stmt::Return(None));
+                Ok(vec![c::Stmt::Block(block)])
+            }
+            (Some(expr), None) if ctx.level() > 0 => {
+                Err(expr.unrecoverable_error("a procedure can not return a value"))
+            }
+            _ => Err(self
+                .return_
+                .unrecoverable_error("return not allowed in this context")),
+        }
+    }
+}
+
+impl CodeGenStmt for ExtendedReturnStmt {
+    /// The return object is declared in a block, the statements of the body change it
+    /// in place before it is returned.
+    fn generate(&self, ctx: &mut Context) -> Result<Vec<c::Stmt>> {
         let Some(return_type) = ctx.return_type() else {
             return Err(self
                 .return_
                 .unrecoverable_error("return not allowed in this context"));
         };
-        let expr = self.expr.generate_with_type_and_check(&return_type, ctx)?;
-        Ok(c_code! {
-            return #expr;
-        })
+        let ty = Type::from_name(&self.ty, ctx)?;
+        if !return_type.can_assign(&ty) {
+            return Err(self
+                .ty
+                .unrecoverable_error("the return object must be of the result type"));
+        }
+
+        let mut sub_ctx = ctx.subscope(Some(return_type.clone()));
+        let object = Variable {
+            name: self.name.clone(),
+            colon: self.colon,
+            constant: self.constant,
+            ty: self.ty.clone(),
+            init: self.init.clone(),
+            semi: self.semi,
+        };
+        let mut block = object.generate(&mut sub_ctx)?;
+        let ExprValue::Distinct(object) = sub_ctx.get_expr_value(&self.name)? else {
+            unreachable!("the return object is a variable");
+        };
+        let code = object.with_check(&return_type);
+        sub_ctx.set_return_object(code.clone());
+
+        if let Some(body) = &self.body {
+            block.extend(generate_stmts(&body.stmts, &mut sub_ctx)?);
+        }
+        block.push(generate_return(code, &return_type, &sub_ctx));
+        Ok(vec![c::Stmt::Block(block)])
+    }
+}
+
+/// Return the value `expr` of a function with the result type `return_type`, after the
+/// postcondition.
+fn generate_return(expr: c::Expr, return_type: &Type, ctx: &Context) -> c::Stmt {
+    let entry = c::Expr::ident("annabella_ss_entry");
+    let result = c::Ident::new("annabella_result");
+    if let Some(post) = ctx.postcondition() {
+        // the postcondition refers to the result as `F'Result`
+        let mut block = vec![
+            c::VarDecl::new(return_type.c_type().const_(), result.clone())
+                .init(expr)
+                .into(),
+        ];
+        block.extend(post.iter().cloned());
+        if return_type.is_string() {
+            block.push(c::Stmt::return_(
+                c::Expr::ident("annabella_string_return").call([entry, result.into()]),
+            ));
+        } else {
+            block.push(c::Expr::ident("annabella_ss_release").call([entry]).into());
+            block.push(c::Stmt::return_(result.into()));
+        }
+        c::Stmt::Block(block)
+    } else if return_type.is_string() {
+        // the result is copied to the secondary stack of the caller
+        c::Stmt::return_(c::Expr::ident("annabella_string_return").call([entry, expr]))
+    } else {
+        c::Stmt::Block(vec![
+            c::VarDecl::new(return_type.c_type().const_(), result.clone())
+                .init(expr)
+                .into(),
+            c::Expr::ident("annabella_ss_release").call([entry]).into(),
+            c::Stmt::return_(result.into()),
+        ])
     }
 }
 
 impl CodeGenStmt for IfStmt {
-    fn generate(&self, ctx: &mut Context) -> Result<CCode> {
+    fn generate(&self, ctx: &mut Context) -> Result<Vec<c::Stmt>> {
         let boolean = Type::boolean(ctx)?;
         let cond = self.cond.generate_with_type_and_check(&boolean, ctx)?;
-        let stmts = self
-            .stmts
-            .iter()
-            .map(|stmt| stmt.generate(ctx))
-            .collect::<Result<Vec<_>>>()?;
+        let stmts = generate_stmts(&self.stmts, ctx)?;
         let else_ifs = self
             .elsifs
             .iter()
             .map(|e| {
                 let cond = e.cond.generate_with_type_and_check(&boolean, ctx)?;
-                let stmts = e
-                    .stmts
-                    .iter()
-                    .map(|stmt| stmt.generate(ctx))
-                    .collect::<Result<Vec<_>>>()?;
-                Ok(c_code! {
-                    else if (#cond) {
-                        #(#stmts)*
-                    }
-                })
+                Ok((cond, generate_stmts(&e.stmts, ctx)?))
             })
             .collect::<Result<Vec<_>>>()?;
         let else_ = self
             .else_
             .as_ref()
-            .map(|e| {
-                let stmts = e
-                    .stmts
-                    .iter()
-                    .map(|stmt| stmt.generate(ctx))
-                    .collect::<Result<Vec<_>>>()?;
-                Ok(c_code! {
-                    else {
-                        #(#stmts)*
-                    }
-                })
-            })
+            .map(|e| generate_stmts(&e.stmts, ctx))
             .transpose()?;
-        Ok(c_code! {
-            if (#cond) {
-                #(#stmts)*
-            }
-            #(#else_ifs)*
-            #else_
-        })
+
+        // the `elsif` branches nest in the `else` branch of the previous condition
+        let else_ = else_ifs
+            .into_iter()
+            .rev()
+            .fold(else_, |else_, (cond, then)| {
+                Some(vec![c::Stmt::If { cond, then, else_ }])
+            });
+        Ok(vec![c::Stmt::If {
+            cond,
+            then: stmts,
+            else_,
+        }])
     }
 }
 
 impl CodeGenStmt for BlockStmt {
-    fn generate(&self, ctx: &mut Context) -> Result<CCode> {
+    fn generate(&self, ctx: &mut Context) -> Result<Vec<c::Stmt>> {
         let mut sub_ctx = ctx.subscope(ctx.return_type());
 
-        let items = self
-            .items()
-            .map(|item| item.generate(&mut sub_ctx))
-            .collect::<Result<Vec<_>>>()?;
-
-        let stmts = self
-            .stmts
-            .iter()
-            .map(|stmt| stmt.generate(&mut sub_ctx))
-            .collect::<Result<Vec<_>>>()?;
+        declare_interfaces(self.items(), &mut sub_ctx)?;
+        declare_representations(self.items(), &mut sub_ctx)?;
+        let mut code = Vec::new();
+        for item in self.items() {
+            code.extend(sub_ctx.recover_item(item, |ctx| item.generate(ctx)));
+        }
+        code.extend(generate_handled_stmts(
+            &self.stmts,
+            self.handlers.as_ref(),
+            &mut sub_ctx,
+        )?);
 
-        Ok(c_code! {
-            {
-                #(#items)*
-                #(#stmts)*
-            }
-        })
+        Ok(vec![c::Stmt::Block(code)])
     }
 }
 
 impl CodeGenStmt for GotoStmt {
-    fn generate(&self, ctx: &mut Context) -> Result<CCode> {
+    fn generate(&self, ctx: &mut Context) -> Result<Vec<c::Stmt>> {
         let value = ctx.get_or_insert(&self.label, || {
-            let ident = IdentBuilder::label(&self.label);
             Value::Label(LabelValue {
-                name: c_code! { #ident },
+                name: IdentBuilder::label(&self.label),
             })
         });
         let Value::Label(LabelValue { name }) = value else {
             return Err(self.label.unrecoverable_error("expected label name"));
         };
-        Ok(c_code! {
-            goto #name;
-        })
+        Ok(vec![c::Stmt::Goto(name.clone())])
     }
 }
 
 impl CodeGenStmt for LoopStmt {
-    fn generate(&self, ctx: &mut Context) -> Result<CCode> {
+    fn generate(&self, ctx: &mut Context) -> Result<Vec<c::Stmt>> {
         let mut sub_ctx = ctx.subscope(ctx.return_type());
 
-        let stmts = |ctx: &mut Context| {
-            let stmts = self
-                .stmts
-                .iter()
-                .map(|stmt| stmt.generate(ctx))
-                .collect::<Result<Vec<_>>>()?;
+        let code = match &self.scheme {
+            LoopScheme::Endless(_) => c::Stmt::While {
+                cond: c::Expr::int(1),
+                body: generate_stmts(&self.stmts, &mut sub_ctx)?,
+            },
+            LoopScheme::While(scheme) => c::Stmt::While {
+                cond: scheme.cond.generate_to_boolean(&mut sub_ctx)?,
+                body: generate_stmts(&self.stmts, &mut sub_ctx)?,
+            },
+            LoopScheme::For(scheme) => generate_for_loop(&scheme.param, &mut sub_ctx, |ctx| {
+                generate_stmts(&self.stmts, ctx)
+            })?,
+        };
 
-            let stmts = c_code! {
-                {
-                    #(#stmts)*
-                }
-            };
+        Ok(vec![code])
+    }
+}
 
-            Ok(stmts)
+/// The loop over the values of the loop parameter `param`, with the `body` generated
+/// in the scope of the parameter. `break` in the body ends the loop.
+pub(super) fn generate_for_loop(
+    param: &LoopParameter,
+    ctx: &mut Context,
+    body: impl FnOnce(&mut Context) -> Result<Vec<c::Stmt>>,
+) -> Result<c::Stmt> {
+    match param {
+        LoopParameter::Spec(param) => generate_range_loop(param, ctx, body),
+        LoopParameter::Iterator(param) => generate_iterator_loop(param, ctx, body),
+    }
+}
+
+/// The loop over a discrete subtype. The parameter of an enumeration type steps with
+/// `'Succ` (or `'Pred`), skipping the codes missing in a representation clause.
+fn generate_range_loop(
+    param: &LoopParameterSpecification,
+    ctx: &mut Context,
+    body: impl FnOnce(&mut Context) -> Result<Vec<c::Stmt>>,
+) -> Result<c::Stmt> {
+    let (ty, first, last) = param.range.generate(ctx)?;
+
+    let ident = IdentBuilder::variable(&Path::default(), &param.ident);
+    let name = c::Expr::from(ident.clone());
+    let (slot, store) = ctx.frame_slot(&name);
+
+    ctx.insert(
+        &param.ident,
+        Value::Variable(VariableValue {
+            name: name.clone(),
+            ty: ty.clone(),
+            perm: Permission::Read,
+            slot,
+        }),
+    )?;
+
+    let body = body(ctx)?;
+    let reverse = param.reverse();
+    let step = if ty.is_enum() {
+        let function = if reverse { ty.pred() } else { ty.succ() };
+        let function = function.expect("enumeration type without `'Succ`");
+        name.clone().assign(function.clone().call([name.clone()]))
+    } else {
+        let op = if reverse {
+            c::BinaryOp::Sub
+        } else {
+            c::BinaryOp::Add
         };
+        name.clone().assign_op(op, c::Expr::int(1))
+    };
 
-        let code = match &self.scheme {
-            LoopScheme::Endless(_) => {
-                let stmts = stmts(&mut sub_ctx)?;
-                c_code! { while (1) #stmts }
+    let init = if reverse { last.clone() } else { first.clone() };
+    let mut block = vec![c::VarDecl::new(ty.c_type(), ident).init(init).into()];
+    block.extend(store);
+    block.push(counting_loop(&name, first, last, reverse, body, step));
+    Ok(c::Stmt::Block(block))
+}
+
+/// The loop over the elements of a string. The loop parameter is a view of the element
+/// at `iterated_index`, a variable if the string is a variable.
+fn generate_iterator_loop(
+    param: &IteratorSpecification,
+    ctx: &mut Context,
+    body: impl FnOnce(&mut Context) -> Result<Vec<c::Stmt>>,
+) -> Result<c::Stmt> {
+    let prefix = generate_string_prefix(&param.name, ctx)?;
+    let string = c::Expr::ident("iterated");
+    let index = c::Expr::ident("iterated_index");
+    let first = string.clone().member(c::Ident::new("first"));
+    let last = string.clone().member(c::Ident::new("last"));
+    let index_ty = Type::standard_integer(ctx)?;
+
+    let element_ty = Type::character();
+    let ident = IdentBuilder::variable(&Path::default(), &param.ident);
+    let name = c::Expr::from(ident.clone()).deref();
+    let (slot, store) = ctx.frame_slot(&name);
+
+    ctx.insert(
+        &param.ident,
+        Value::Variable(VariableValue {
+            name,
+            ty: element_ty.clone(),
+            perm: prefix.perm,
+            slot,
+        }),
+    )?;
+
+    let mut loop_body = vec![c::VarDecl::new(element_ty.c_type().pointer(), ident)
+        .init(
+            c::Expr::ident("annabella_string_element")
+                .call([string.clone(), index.clone()])
+                .cast(element_ty.c_type().pointer()),
+        )
+        .into()];
+    loop_body.extend(store);
+    loop_body.extend(body(ctx)?);
+
+    let reverse = param.reverse();
+    let op = if reverse {
+        c::BinaryOp::Sub
+    } else {
+        c::BinaryOp::Add
+    };
+    let step = index.clone().assign_op(op, c::Expr::int(1));
+    let init = if reverse { last.clone() } else { first.clone() };
+    Ok(c::Stmt::Block(vec![
+        c::VarDecl::new(Type::string().c_type(), c::Ident::new("iterated"))
+            .init(prefix.code)
+            .into(),
+        c::VarDecl::new(index_ty.c_type(), c::Ident::new("iterated_index"))
+            .init(init)
+            .into(),
+        counting_loop(&index, first, last, reverse, loop_body, step),
+    ]))
+}
+
+/// The loop of the variable `var` from `first` to `last` (or from `last` to `first` in
+/// `reverse`) with the `step` to the next value after the `body`, which doesn't step
+/// past the last value.
+fn counting_loop(
+    var: &c::Expr,
+    first: c::Expr,
+    last: c::Expr,
+    reverse: bool,
+    mut body: Vec<c::Stmt>,
+    step: c::Expr,
+) -> c::Stmt {
+    let (end, op) = if reverse {
+        (first.clone(), c::BinaryOp::Le)
+    } else {
+        (last.clone(), c::BinaryOp::Ge)
+    };
+    body.push(c::Stmt::if_(
+        var.clone().binary(op, end),
+        vec![c::Stmt::Break],
+    ));
+    body.push(step.into());
+    c::Stmt::if_(
+        first.binary(c::BinaryOp::Le, last),
+        vec![c::Stmt::While {
+            cond: c::Expr::int(1),
+            body,
+        }],
+    )
+}
+
+impl DiscreteSubtypeDefinition {
+    /// The type and the first and last value of the range.
+    fn generate(&self, ctx: &mut Context) -> Result<(Type, c::Expr, c::Expr)> {
+        match self {
+            Self::Range(range) => generate_range(range, ctx),
+            Self::Subtype(indication) => {
+                let (ty, first, last) = match &indication.mark {
+                    Name::Attribute(attribute) if &*attribute.designator.name == "range" => {
+                        attribute.generate_range(ctx)?
+                    }
+                    mark => {
+                        let ty = mark.generate_type(ctx)?;
+                        let Some((first, last)) = ty.bounds() else {
+                            return Err(mark.unimplemented_error(
+                                "range of a type without static bounds not yet implemented",
+                            ));
+                        };
+                        (ty, first, last)
+                    }
+                };
+                match &indication.constraint {
+                    Some(constraint) => {
+                        let (first, last) = constraint.bounds(&ty, ctx)?;
+                        Ok((ty, first, last))
+                    }
+                    None => Ok((ty, first, last)),
+                }
             }
-            LoopScheme::While(scheme) => {
-                let cond = scheme.cond.generate_to_boolean(&mut sub_ctx)?;
-                let stmts = stmts(&mut sub_ctx)?;
-                c_code! { while (#cond) #stmts }
+        }
+    }
+}
+
+/// The type and the first and last value of the range `A .. B`.
+fn generate_range(range: &Range, ctx: &mut Context) -> Result<(Type, c::Expr, c::Expr)> {
+    let start = range.start.generate(ctx)?.flat_map(|start| {
+        // a range of universal integers is of type Integer
+        let ty = if start.ty.is_universal_integer() {
+            match range.end.generate(ctx)? {
+                ExprValue::Distinct(end) if !end.ty.is_universal_integer() => end.ty,
+                _ => Type::standard_integer(ctx)?,
             }
-            LoopScheme::For(scheme) => {
-                let code = scheme
-                    .range
-                    .start
-                    .generate(&mut sub_ctx)?
-                    .flat_map(|start| {
-                        let ty = &start.ty;
-
-                        let end = scheme
-                            .range
-                            .end
-                            .generate_with_type_and_check(ty, &mut sub_ctx)?;
-                        let ident = IdentBuilder::variable(&scheme.ident);
-
-                        sub_ctx.insert(
-                            &scheme.ident,
-                            Value::Variable(VariableValue {
-                                name: c_code! { #ident },
-                                ty: ty.clone(),
-                                perm: Permission::Read,
-                            }),
-                        )?;
-
-                        let stmts = stmts(&mut sub_ctx)?;
-
-                        if scheme.reverse() {
-                            Ok(SingleExprValue {
-                                ty: Type::void(),
-                                perm: Permission::Read,
-                                code: c_code! {
-                                    {
-                                        #ty #ident = #end;
-                                        if (#start <= #end) {
-                                            while (1) {
-                                                #stmts
-                                                if (#ident <= #start) {
-                                                    break;
-                                                }
-                                                #ident -= 1;
-                                            }
-                                        }
-                                    }
-                                },
-                                value: None,
-                            }
-                            .into())
-                        } else {
-                            Ok(SingleExprValue {
-                                ty: Type::void(),
-                                perm: Permission::Read,
-                                code: c_code! {
-                                    {
-                                        #ty #ident = #start;
-                                        if (#start <= #end) {
-                                            while(1) {
-                                                #stmts
-                                                if (#ident >= #end) {
-                                                    break;
-                                                }
-                                                #ident += 1;
-                                            }
-                                        }
-                                    }
-                                },
-                                value: None,
-                            }
-                            .into())
-                        }
-                    })?;
-                let ExprValue::Distinct(code) = code else {
-                    return Err(scheme
-                        .range
-                        .unrecoverable_error("ambiguous range expression"));
-                };
-                code.code
+        } else {
+            start.ty.clone()
+        };
+
+        // only the start values of a type matching the end remain
+        range.end.generate_with_type_and_check(&ty, ctx)?;
+        Ok(SingleExprValue { ty, ..start }.into())
+    })?;
+    let start = start.distinct(range, "ambiguous range expression")?;
+    let ty = start.ty;
+    let end = range.end.generate_with_type_and_check(&ty, ctx)?;
+    Ok((ty, start.code, end))
+}
+
+impl CodeGenStmt for RaiseStmt {
+    /// An exception not handled ends the program with a report of the exception and the
+    /// message, in C exceptions are never handled.
+    fn generate(&self, ctx: &mut Context) -> Result<Vec<c::Stmt>> {
+        let Some(name) = &self.name else {
+            if !ctx.in_handler() {
+                return Err(self
+                    .raise
+                    .unrecoverable_error("re-raise is only allowed in an exception handler"));
+            }
+            return Ok(vec![c::Expr::ident("annabella_reraise").call([]).into()]);
+        };
+        let Some(exception) = name.resolve_exception(ctx)? else {
+            return Err(name.unrecoverable_error("not an exception name"));
+        };
+        let exception = c::Expr::str(&*exception);
+        let code = match self.message() {
+            Some(message) => {
+                let message = message.generate_with_type_and_check(&Type::string(), ctx)?;
+                c::Expr::ident("annabella_raise_message").call([
+                    exception,
+                    c::Expr::ident("annabella_string_to_c").call([message]),
+                ])
             }
+            None => c::Expr::ident("annabella_raise").call([exception]),
         };
+        Ok(vec![code.into()])
+    }
+}
 
-        Ok(code)
+impl CodeGenStmt for DelayStmt {
+    /// Sleep with `nanosleep` for the seconds of type `Duration`, a delay that isn't
+    /// positive doesn't sleep.
+    fn generate(&self, ctx: &mut Context) -> Result<Vec<c::Stmt>> {
+        if let Some(until) = &self.until {
+            return Err(
+                until.unimplemented_error("delay until not supported, `Ada.Calendar` is missing")
+            );
+        }
+        let duration = Type::standard_duration(ctx)?;
+        let seconds = self.expr.generate_with_type_and_check(&duration, ctx)?;
+        ctx.push_include("<time.h>");
+        let seconds_ident = c::Ident::new("seconds");
+        let delay = c::Ident::new("delay");
+        let whole = c::Expr::from(seconds_ident.clone()).cast(c::Type::named("time_t"));
+        let nanoseconds = c::Expr::from(seconds_ident.clone())
+            .binary(c::BinaryOp::Sub, whole.clone())
+            .binary(c::BinaryOp::Mul, c::Expr::float(1e9))
+            .cast(c::Type::named("long"));
+        Ok(vec![c::Stmt::Block(vec![
+            c::VarDecl::new(duration.c_type().const_(), seconds_ident.clone())
+                .init(seconds)
+                .into(),
+            c::Stmt::if_(
+                c::Expr::from(seconds_ident).binary(c::BinaryOp::Gt, c::Expr::float(0.0)),
+                vec![
+                    c::VarDecl::new(c::Type::named("struct timespec"), delay.clone())
+                        .init(c::Expr::InitList(vec![whole, nanoseconds]))
+                        .into(),
+                    c::Expr::ident("nanosleep")
+                        .call([c::Expr::from(delay).addr_of(), c::Expr::ident("NULL")])
+                        .into(),
+                ],
+            ),
+        ])])
     }
 }
 
 impl CodeGenStmt for ExitStmt {
-    fn generate(&self, ctx: &mut Context) -> Result<CCode> {
+    fn generate(&self, ctx: &mut Context) -> Result<Vec<c::Stmt>> {
         if let Some(name) = &self.name {
-            return Err(name.unrecoverable_error("exit to named loop not yet implemented"));
+            return Err(name.unimplemented_error("exit to named loop not yet implemented"));
         }
 
-        Ok(if let Some(cond) = self.cond() {
+        Ok(vec![if let Some(cond) = self.cond() {
             let cond = cond.generate_to_boolean(ctx)?;
-            c_code! {
-                if (#cond) {
-                    break;
-                }
-            }
+            c::Stmt::if_(cond, vec![c::Stmt::Break])
         } else {
-            c_code! {
-                break;
-            }
-        })
+            c::Stmt::Break
+        }])
     }
 }
 
 impl CodeGenStmt for CaseStmt {
-    fn generate(&self, ctx: &mut Context) -> Result<CCode> {
-        let ExprValue::Distinct(expr) = self.expr.generate(ctx)? else {
-            return Err(self.expr.unrecoverable_error("ambiguous case expression"));
-        };
+    fn generate(&self, ctx: &mut Context) -> Result<Vec<c::Stmt>> {
+        let expr = self
+            .expr
+            .generate(ctx)?
+            .distinct(&self.expr, "ambiguous case expression")?;
         let ty = expr.ty;
         let expr = expr.code;
+        let mut pragmas = Vec::new();
+        for pragma in &self.pragmas {
+            pragmas.extend(pragma.generate_in(PragmaPlace::Statement, ctx)?);
+        }
         let alternatives = self
             .alternatives
             .iter()
             .map(|alt| {
-                let choices = alt
+                let cond = alt
                     .choices
                     .iter()
                     .map(|choice| choice.generate(&ty, ctx))
-                    .collect::<Result<Vec<_>>>()?;
-
-                let stmts = alt
-                    .stmts
-                    .iter()
-                    .map(|stmt| stmt.generate(ctx))
-                    .collect::<Result<Vec<_>>>()?;
-
-                Ok(c_code! {
-                    if (#(#choices)||*) {
-                        #(#stmts)*
-                    } else
-                })
+                    .reduce(|lhs, rhs| Ok(lhs?.binary(c::BinaryOp::Or, rhs?)))
+                    .expect("case alternative without choices")?;
+                Ok((cond, generate_stmts(&alt.stmts, ctx)?))
             })
             .collect::<Result<Vec<_>>>()?;
-        Ok(c_code! {
-            {
-                #ty case_expr = #expr;
-                #(#alternatives)* {}
-            }
-        })
+        let chain = alternatives
+            .into_iter()
+            .rev()
+            .fold(None, |else_, (cond, then)| {
+                Some(vec![c::Stmt::If { cond, then, else_ }])
+            });
+
+        let mut block = vec![c::VarDecl::new(ty.c_type(), c::Ident::new("case_expr"))
+            .init(expr)
+            .into()];
+        block.extend(pragmas);
+        block.extend(chain.into_iter().flatten());
+        Ok(vec![c::Stmt::Block(block)])
     }
 }
 
 impl DiscreteChoice {
-    fn generate(&self, ty: &Type, ctx: &mut Context) -> Result<CCode> {
+    /// The condition of the choice for the value `case_expr`.
+    pub(super) fn generate(&self, ty: &Type, ctx: &mut Context) -> Result<c::Expr> {
+        let case_expr = c::Expr::ident("case_expr");
         Ok(match self {
-            Self::Others(_) => c_code! { 1 },
+            Self::Others(_) => c::Expr::int(1),
             Self::Expr(expr) => {
                 let expr = expr.generate_with_type_and_check(ty, ctx)?;
-                c_code! { case_expr == #expr }
+                case_expr.binary(c::BinaryOp::Eq, expr)
             }
             Self::Range(range) => {
                 let start = range.start.generate_with_type_and_check(ty, ctx)?;
                 let end = range.end.generate_with_type_and_check(ty, ctx)?;
-                c_code! { (case_expr >= #start && case_expr <= #end) }
+                case_expr
+                    .clone()
+                    .binary(c::BinaryOp::Ge, start)
+                    .binary(c::BinaryOp::And, case_expr.binary(c::BinaryOp::Le, end))
             }
         })
     }
@@ -0,0 +1,136 @@
+/// Target dependent values used by the bundled runtime specifications.
+///
+/// The names follow the target dependent information file of GNAT (`-gnatet`),
+/// a file of `Name Value` lines, e.g. `Int_Size 32`.
+#[derive(Debug, Clone)]
+pub struct Target {
+    pub bits_per_unit: u32,
+    pub bits_per_word: u32,
+    pub char_size: u32,
+    pub short_size: u32,
+    pub int_size: u32,
+    pub long_size: u32,
+    pub long_long_size: u32,
+    pub pointer_size: u32,
+}
+
+impl Default for Target {
+    /// 64 bit Linux
+    fn default() -> Self {
+        Self {
+            bits_per_unit: 8,
+            bits_per_word: 64,
+            char_size: 8,
+            short_size: 16,
+            int_size: 32,
+            long_size: 64,
+            long_long_size: 64,
+            pointer_size: 64,
+        }
+    }
+}
+
+impl Target {
+    /// Parse a target information file, missing values keep their default.
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let mut target = Self::default();
+        for (i, line) in source.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let (name, value) = line
+                .split_once(char::is_whitespace)
+                .ok_or_else(|| format!("line {}: expected `Name Value`", i + 1))?;
+            let value = value
+                .trim()
+                .parse()
+                .map_err(|err| format!("line {}: invalid value for {name}: {err}", i + 1))?;
+            // other values of the GNAT format are not used
+            if let Some(field) = target.field_mut(name) {
+                *field = value;
+            }
+        }
+        for (name, size) in target.sizes() {
+            if !(1..=64).contains(&size) {
+                return Err(format!(
+                    "{name}_Size must be between 1 and 64, found {size}"
+                ));
+            }
+            // the modulus 2 ** 64 does not fit into the integers of the runtime specs
+            if Self::MODULAR.contains(&name) && size == 64 {
+                return Err(format!(
+                    "{name}_Size must be less than 64 for the modular types of the runtime"
+                ));
+            }
+        }
+        Ok(target)
+    }
+
+    /// The sizes of the C types with a modular type in the runtime, using `$Name_Modulus`.
+    const MODULAR: [&'static str; 3] = ["Char", "Short", "Int"];
+
+    fn field_mut(&mut self, name: &str) -> Option<&mut u32> {
+        Some(match name {
+            "Bits_Per_Unit" => &mut self.bits_per_unit,
+            "Bits_Per_Word" => &mut self.bits_per_word,
+            "Char_Size" => &mut self.char_size,
+            "Short_Size" => &mut self.short_size,
+            "Int_Size" => &mut self.int_size,
+            "Long_Size" => &mut self.long_size,
+            "Long_Long_Size" => &mut self.long_long_size,
+            "Pointer_Size" => &mut self.pointer_size,
+            _ => return None,
+        })
+    }
+
+    fn sizes(&self) -> [(&'static str, u32); 6] {
+        [
+            ("Char", self.char_size),
+            ("Short", self.short_size),
+            ("Int", self.int_size),
+            ("Long", self.long_size),
+            ("Long_Long", self.long_long_size),
+            ("Pointer", self.pointer_size),
+        ]
+    }
+
+    fn lookup(&self, name: &str) -> Option<String> {
+        match name {
+            "Bits_Per_Unit" => return Some(self.bits_per_unit.to_string()),
+            "Bits_Per_Word" => return Some(self.bits_per_word.to_string()),
+            _ => {}
+        }
+        let (prefix, attribute) = name.rsplit_once('_')?;
+        let (_, size) = self.sizes().into_iter().find(|(p, _)| *p == prefix)?;
+        Some(match attribute {
+            "Size" => size.to_string(),
+            // written as an expression, the literal 2 ** 63 does not fit into 64 bits
+            "First" => format!("-{} - 1", (1u64 << (size - 1)) - 1),
+            "Last" => ((1u64 << (size - 1)) - 1).to_string(),
+            "Modulus" if size < 64 => (1u64 << size).to_string(),
+            _ => return None,
+        })
+    }
+
+    /// Replace the `$Name` placeholders of a runtime source with the target values,
+    /// e.g. `$Int_Last` or `$Long_Modulus`.
+    pub(super) fn expand(&self, source: &str) -> String {
+        let mut result = String::with_capacity(source.len());
+        let mut rest = source;
+        while let Some(start) = rest.find('$') {
+            result.push_str(&rest[..start]);
+            let name_len = rest[start + 1..]
+                .find(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+                .unwrap_or(rest.len() - start - 1);
+            let name = &rest[start + 1..start + 1 + name_len];
+            let value = self
+                .lookup(name)
+                .unwrap_or_else(|| panic!("unknown target value `${name}` in runtime source"));
+            result.push_str(&value);
+            rest = &rest[start + 1 + name_len..];
+        }
+        result.push_str(rest);
+        result
+    }
+}
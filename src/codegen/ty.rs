@@ -1,16 +1,17 @@
 use std::{fmt, mem, ptr, rc::Rc};
 
 use indexmap::IndexMap;
-use quote::ToTokens;
 
 use crate::{
-    codegen::IdentBuilder,
-    parser::{ParamMode, SelectorName},
+    codegen::{IdentBuilder, Path},
+    parser::{ParamMode, SelectorName, UnitName},
     tokenizer::{Ident, Span, Spanned},
     Result,
 };
 
-use super::{CCode, Context, ExprValue, SingleExprValue, Value};
+use super::{
+    c, representation::min_size, standard, Context, ExprValue, Permission, SingleExprValue, Value,
+};
 
 #[derive(Clone)]
 pub struct Type(Rc<Inner>);
@@ -19,12 +20,13 @@ enum_dispatch!({
     #[derive(Debug)]
     enum Inner {
         Void(VoidType),
-        Character(CharacterType),
         Integer(IntegerType),
+        Real(RealType),
         String(StringType),
         Function(FunctionType),
         Enum(EnumType),
         Signed(SignedType),
+        Float(FloatType),
         Record(RecordType),
         Subtype(SubtypeType),
     }
@@ -40,7 +42,7 @@ macro_rules! singleton {
     ($ident:ident, $ty:ident $(, $name:literal)?) => {{
         thread_local! {
             static TYPE: Type = Type::new(Inner::$ident($ty $({
-                ident: IdentBuilder::type_(&Ident {
+                ident: IdentBuilder::type_(&Path::default(), &Ident {
                     name: $name.into(),
                     span: Span::call_site(),
                 }),
@@ -69,14 +71,103 @@ impl Type {
         BOOLEAN.with(|ident| Self::from_ident(ident, ctx))
     }
 
+    /// The predefined type `Integer` of package `Standard`.
+    pub fn standard_integer(ctx: &Context<'_>) -> Result<Self> {
+        thread_local! {
+            static INTEGER: Ident = Ident {
+                name: "integer".into(),
+                span: Span::call_site(),
+            };
+        }
+        INTEGER.with(|ident| Self::from_ident(ident, ctx))
+    }
+
+    /// The predefined type `Duration` of package `Standard`.
+    pub fn standard_duration(ctx: &Context<'_>) -> Result<Self> {
+        thread_local! {
+            static DURATION: Ident = Ident {
+                name: "duration".into(),
+                span: Span::call_site(),
+            };
+        }
+        DURATION.with(|ident| Self::from_ident(ident, ctx))
+    }
+
+    /// The type `Address` of package `System`, which must be visible.
+    pub fn system_address(span: &impl Spanned, ctx: &Context<'_>) -> Result<Self> {
+        let system = Ident {
+            name: "system".into(),
+            span: span.span(),
+        };
+        let Ok(Value::Package(package)) = ctx.get(&system) else {
+            return Err(span.unrecoverable_error("`System.Address` needs `with System`"));
+        };
+        let address = Ident {
+            name: "address".into(),
+            span: span.span(),
+        };
+        Self::from_value(package.get(&address)?)
+            .ok_or_else(|| span.unrecoverable_error("`System.Address` is not a type"))
+    }
+
+    /// The predefined type `Character`, an enumeration type with the 256 characters of
+    /// Latin-1 as literals.
     pub fn character() -> Self {
-        singleton!(Character, CharacterType, "character")
+        thread_local! {
+            static CHARACTER: Type = {
+                let name = Ident {
+                    name: "character".into(),
+                    span: Span::call_site(),
+                };
+                Type::enum_(EnumType {
+                    ident: IdentBuilder::type_(&Path::default(), &name),
+                    image: IdentBuilder::image(&Path::default(), &name).into(),
+                    succ: IdentBuilder::succ(&Path::default(), &name).into(),
+                    pred: IdentBuilder::pred(&Path::default(), &name).into(),
+                    values: standard::character_literals(),
+                    range: (0, 255),
+                    size: None,
+                    name,
+                })
+            };
+        }
+        CHARACTER.with(Clone::clone)
+    }
+
+    /// The predefined type `Wide_Character`, an enumeration type with the 65536
+    /// characters of the Basic Multilingual Plane. Only the first 256, the characters of
+    /// Latin-1, have literals.
+    pub fn wide_character() -> Self {
+        thread_local! {
+            static WIDE_CHARACTER: Type = {
+                let name = Ident {
+                    name: "wide_character".into(),
+                    span: Span::call_site(),
+                };
+                Type::enum_(EnumType {
+                    ident: IdentBuilder::type_(&Path::default(), &name),
+                    image: IdentBuilder::image(&Path::default(), &name).into(),
+                    succ: IdentBuilder::succ(&Path::default(), &name).into(),
+                    pred: IdentBuilder::pred(&Path::default(), &name).into(),
+                    values: standard::character_literals(),
+                    range: (0, 65535),
+                    size: None,
+                    name,
+                })
+            };
+        }
+        WIDE_CHARACTER.with(Clone::clone)
     }
 
     pub fn integer() -> Self {
         singleton!(Integer, IntegerType, "integer")
     }
 
+    /// The type of real literals and named real numbers, `universal_real`.
+    pub fn real() -> Self {
+        singleton!(Real, RealType)
+    }
+
     pub fn string() -> Self {
         singleton!(String, StringType, "string")
     }
@@ -93,6 +184,10 @@ impl Type {
         Self::new(Inner::Signed(ty))
     }
 
+    pub fn float(ty: FloatType) -> Self {
+        Self::new(Inner::Float(ty))
+    }
+
     pub fn record(ty: RecordType) -> Self {
         Self::new(Inner::Record(ty))
     }
@@ -106,6 +201,15 @@ impl Type {
             .ok_or_else(|| ident.unrecoverable_error("not a type name"))
     }
 
+    /// Lookup a type by its (possibly expanded) name, like `Interfaces.C.int`.
+    pub fn from_name(name: &UnitName, ctx: &Context) -> Result<Self> {
+        let ty = match name.parent() {
+            Some(package) => Self::from_value(ctx.get_package(&package)?.get(name.last())?),
+            None => Self::from_value(ctx.get(name.last())?),
+        };
+        ty.ok_or_else(|| name.unrecoverable_error("not a type name"))
+    }
+
     pub fn from_value(value: &Value) -> Option<Self> {
         let Value::Type(value) = value else {
             return None;
@@ -129,7 +233,7 @@ impl Type {
         self.last_parent().inner()
     }
 
-    fn parents(&self) -> Parents {
+    fn parents(&self) -> Parents<'_> {
         Parents(Some(self.inner()))
     }
 
@@ -151,20 +255,144 @@ impl Type {
         }
     }
 
+    pub fn is_enum(&self) -> bool {
+        matches!(self.last_parent_inner(), Inner::Enum(_))
+    }
+
+    /// The static range of the values of a discrete type in the generated code, the
+    /// codes of an enumeration type. Constraints of subtypes are ignored.
+    pub fn static_range(&self) -> Option<(i64, i64)> {
+        match self.last_parent_inner() {
+            Inner::Enum(ty) => Some(ty.range),
+            Inner::Signed(ty) => ty.range,
+            _ => None,
+        }
+    }
+
+    /// The first and last value of a discrete subtype in the generated code: the range
+    /// of its constraint, or else the static range of its type.
+    pub fn bounds(&self) -> Option<(c::Expr, c::Expr)> {
+        for inner in self.parents() {
+            if let Inner::Subtype(ty) = inner {
+                if ty.constraint_check.is_some() {
+                    return ty.bounds.clone();
+                }
+            }
+        }
+        let (first, last) = self.static_range()?;
+        Some((c::Expr::int(first), c::Expr::int(last)))
+    }
+
+    /// The first and last value of a discrete subtype if they are static: the range of
+    /// its constraint, or else the static range of its type.
+    pub fn static_bounds(&self) -> Option<(i64, i64)> {
+        for inner in self.parents() {
+            if let Inner::Subtype(ty) = inner {
+                if ty.constraint_check.is_some() {
+                    return ty.static_bounds;
+                }
+            }
+        }
+        self.static_range()
+    }
+
+    /// The value of `'Size` if it is static: the size clause, or else the minimum
+    /// number of bits of the range of a discrete type. A subtype with a static
+    /// constraint has the minimum size of its range, like `Integer range 0 .. 7`.
+    pub fn static_size(&self) -> Option<i64> {
+        for inner in self.parents() {
+            if let Inner::Subtype(SubtypeType {
+                static_bounds: Some((first, last)),
+                ..
+            }) = inner
+            {
+                return Some(min_size(*first, *last));
+            }
+        }
+        let size = match self.last_parent_inner() {
+            Inner::Enum(ty) => ty.size,
+            Inner::Signed(ty) => ty.size,
+            Inner::Record(ty) => return ty.size,
+            _ => None,
+        };
+        size.or_else(|| {
+            let (first, last) = self.static_range()?;
+            Some(min_size(first, last))
+        })
+    }
+
     pub fn to_str(&self) -> &str {
         Inner!(self.inner(), |value| value.to_str())
     }
 
+    /// The span of the name in the type declaration, if declared in the source.
+    pub fn declaration(&self) -> Option<Span> {
+        Inner!(self.inner(), |value| value.declaration())
+            .map(|name| name.span)
+            .filter(|span| !span.is_call_site())
+    }
+
     /// Is it allowed to assign a `source` value to `self`?
     pub fn can_assign(&self, source: &Self) -> bool {
         Inner!(self.inner(), |value| value.can_assign(source))
     }
 
     /// Is a constraint check required when assigning a `source` value to `self`?
-    pub fn needs_constraint_check(&self, source: &Self) -> Option<&CCode> {
+    pub fn needs_constraint_check(&self, source: &Self) -> Option<&c::Expr> {
         Inner!(self.inner(), |value| value.needs_constraint_check(source))
     }
 
+    pub fn is_string(&self) -> bool {
+        matches!(self.last_parent_inner(), Inner::String(_))
+    }
+
+    /// Is it the type of integer literals and named numbers?
+    pub fn is_universal_integer(&self) -> bool {
+        matches!(self.inner(), Inner::Integer(_))
+    }
+
+    /// Is it the type of real literals and named real numbers?
+    pub fn is_universal_real(&self) -> bool {
+        matches!(self.inner(), Inner::Real(_))
+    }
+
+    /// Is it a floating or fixed point type, or `universal_real`?
+    pub fn is_real(&self) -> bool {
+        matches!(self.last_parent_inner(), Inner::Real(_) | Inner::Float(_))
+    }
+
+    pub fn is_numeric(&self) -> bool {
+        matches!(
+            self.last_parent_inner(),
+            Inner::Integer(_) | Inner::Signed(_) | Inner::Real(_) | Inner::Float(_)
+        )
+    }
+
+    /// Is an explicit type conversion from `source` to `self` allowed?
+    pub fn can_convert(&self, source: &Self) -> bool {
+        self.can_assign(source) || (self.is_numeric() && source.is_numeric())
+    }
+
+    /// Function implementing the `'Image` attribute of this type.
+    pub fn image(&self) -> Option<&c::Expr> {
+        Inner!(self.last_parent_inner(), |value| value.image())
+    }
+
+    /// Function implementing the `'Succ` attribute of this type.
+    pub fn succ(&self) -> Option<&c::Expr> {
+        Inner!(self.last_parent_inner(), |value| value.succ())
+    }
+
+    /// Function implementing the `'Pred` attribute of this type.
+    pub fn pred(&self) -> Option<&c::Expr> {
+        Inner!(self.last_parent_inner(), |value| value.pred())
+    }
+
+    /// Initial value of objects declared without one, from the `Default_Value` aspect.
+    pub fn default_value(&self) -> Option<i64> {
+        Inner!(self.last_parent_inner(), |value| value.default_value())
+    }
+
     pub(super) fn select(
         &self,
         prefix: &SingleExprValue,
@@ -174,12 +402,34 @@ impl Type {
     }
 }
 
-impl ToTokens for Type {
-    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
-        Inner!(self.inner(), |value| value.to_tokens(tokens))
+impl Type {
+    /// The type in the generated code.
+    pub fn c_type(&self) -> c::Type {
+        Inner!(self.inner(), |value| value.c_type())
+    }
+
+    /// The C type of objects, parameters and record components with the `convention`.
+    ///
+    /// Integer types are `ssize_t` in Ada code, but have the size of their range in C.
+    pub fn c_type_with(&self, convention: Convention, ctx: &mut Context) -> c::Type {
+        match (convention, self.last_parent_inner()) {
+            (Convention::C, Inner::Signed(ty)) => {
+                ctx.push_include("<stdint.h>");
+                ty.c_sized.clone()
+            }
+            _ => self.c_type(),
+        }
     }
 }
 
+/// Calling convention and data layout of an entity, see `pragma Convention`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Convention {
+    #[default]
+    Ada,
+    C,
+}
+
 struct Parents<'a>(Option<&'a Inner>);
 
 impl<'a> Iterator for Parents<'a> {
@@ -202,9 +452,24 @@ impl Inner {
 
 trait TypeImpl: fmt::Debug {
     fn to_str(&self) -> &str;
-    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream);
+    fn declaration(&self) -> Option<&Ident> {
+        None
+    }
+    fn c_type(&self) -> c::Type;
     fn can_assign(&self, source: &Type) -> bool;
-    fn needs_constraint_check(&self, source: &Type) -> Option<&CCode>;
+    fn needs_constraint_check(&self, source: &Type) -> Option<&c::Expr>;
+    fn image(&self) -> Option<&c::Expr> {
+        None
+    }
+    fn succ(&self) -> Option<&c::Expr> {
+        None
+    }
+    fn pred(&self) -> Option<&c::Expr> {
+        None
+    }
+    fn default_value(&self) -> Option<i64> {
+        None
+    }
     fn select(&self, prefix: &SingleExprValue, name: &SelectorName) -> Result<ExprValue> {
         let _ = prefix;
         Err(name.unrecoverable_error("select not supported on this type"))
@@ -219,68 +484,66 @@ impl TypeImpl for VoidType {
         "void"
     }
 
-    fn to_tokens(&self, _tokens: &mut proc_macro2::TokenStream) {
-        unimplemented!("void type to tokens")
+    fn c_type(&self) -> c::Type {
+        c::Type::void()
     }
 
     fn can_assign(&self, source: &Type) -> bool {
         matches!(source.inner(), Inner::Void(_))
     }
 
-    fn needs_constraint_check(&self, _source: &Type) -> Option<&CCode> {
+    fn needs_constraint_check(&self, _source: &Type) -> Option<&c::Expr> {
         None
     }
 }
 
 #[derive(Debug)]
-pub struct CharacterType {
-    ident: proc_macro2::Ident,
+pub struct IntegerType {
+    ident: c::Ident,
 }
 
-impl TypeImpl for CharacterType {
+impl TypeImpl for IntegerType {
     fn to_str(&self) -> &str {
-        "character"
+        "integer"
     }
 
-    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
-        self.ident.to_tokens(tokens);
+    fn c_type(&self) -> c::Type {
+        self.ident.clone().into()
     }
 
     fn can_assign(&self, source: &Type) -> bool {
-        matches!(source.inner(), Inner::Character(_))
+        matches!(source.inner(), Inner::Integer(_))
     }
 
-    fn needs_constraint_check(&self, _source: &Type) -> Option<&CCode> {
+    fn needs_constraint_check(&self, _source: &Type) -> Option<&c::Expr> {
         None
     }
 }
 
 #[derive(Debug)]
-pub struct IntegerType {
-    ident: proc_macro2::Ident,
-}
+pub struct RealType;
 
-impl TypeImpl for IntegerType {
+impl TypeImpl for RealType {
     fn to_str(&self) -> &str {
-        "integer"
+        "universal_real"
     }
 
-    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
-        self.ident.to_tokens(tokens);
+    fn c_type(&self) -> c::Type {
+        c::Type::named("double")
     }
 
     fn can_assign(&self, source: &Type) -> bool {
-        matches!(source.inner(), Inner::Integer(_))
+        matches!(source.inner(), Inner::Real(_))
     }
 
-    fn needs_constraint_check(&self, _source: &Type) -> Option<&CCode> {
+    fn needs_constraint_check(&self, _source: &Type) -> Option<&c::Expr> {
         None
     }
 }
 
 #[derive(Debug)]
 pub struct StringType {
-    ident: proc_macro2::Ident,
+    ident: c::Ident,
 }
 
 impl TypeImpl for StringType {
@@ -288,15 +551,15 @@ impl TypeImpl for StringType {
         "string"
     }
 
-    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
-        self.ident.to_tokens(tokens);
+    fn c_type(&self) -> c::Type {
+        self.ident.clone().into()
     }
 
     fn can_assign(&self, source: &Type) -> bool {
         matches!(source.inner(), Inner::String(_))
     }
 
-    fn needs_constraint_check(&self, _source: &Type) -> Option<&CCode> {
+    fn needs_constraint_check(&self, _source: &Type) -> Option<&c::Expr> {
         None
     }
 }
@@ -305,6 +568,10 @@ impl TypeImpl for StringType {
 pub struct FunctionType {
     pub args: Vec<ArgumentType>,
     pub return_type: Type,
+
+    /// Level of the enclosing subprogram of a nested subprogram, its frame array is passed
+    /// as the static link.
+    pub static_link: Option<usize>,
 }
 
 impl TypeImpl for FunctionType {
@@ -312,8 +579,8 @@ impl TypeImpl for FunctionType {
         "Function"
     }
 
-    fn to_tokens(&self, _tokens: &mut proc_macro2::TokenStream) {
-        todo!("function type to tokens")
+    fn c_type(&self) -> c::Type {
+        todo!("function type to C type")
     }
 
     fn can_assign(&self, source: &Type) -> bool {
@@ -324,7 +591,7 @@ impl TypeImpl for FunctionType {
         ptr::eq(self, source)
     }
 
-    fn needs_constraint_check(&self, _target: &Type) -> Option<&CCode> {
+    fn needs_constraint_check(&self, _target: &Type) -> Option<&c::Expr> {
         None
     }
 }
@@ -361,8 +628,17 @@ impl From<&ParamMode> for ArgumentMode {
 #[derive(Debug)]
 pub struct EnumType {
     pub name: Ident,
-    pub ident: proc_macro2::Ident,
+    pub ident: c::Ident,
     pub values: Vec<Ident>,
+    pub image: c::Expr,
+    pub succ: c::Expr,
+    pub pred: c::Expr,
+
+    /// The codes of the first and last literal.
+    pub range: (i64, i64),
+
+    /// The size clause.
+    pub size: Option<i64>,
 }
 
 impl TypeImpl for EnumType {
@@ -370,8 +646,12 @@ impl TypeImpl for EnumType {
         &self.name.name
     }
 
-    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
-        self.ident.to_tokens(tokens);
+    fn declaration(&self) -> Option<&Ident> {
+        Some(&self.name)
+    }
+
+    fn c_type(&self) -> c::Type {
+        self.ident.clone().into()
     }
 
     fn can_assign(&self, source: &Type) -> bool {
@@ -381,16 +661,43 @@ impl TypeImpl for EnumType {
         }
     }
 
-    fn needs_constraint_check(&self, _target: &Type) -> Option<&CCode> {
+    fn needs_constraint_check(&self, _target: &Type) -> Option<&c::Expr> {
         None
     }
+
+    fn image(&self) -> Option<&c::Expr> {
+        Some(&self.image)
+    }
+
+    fn succ(&self) -> Option<&c::Expr> {
+        Some(&self.succ)
+    }
+
+    fn pred(&self) -> Option<&c::Expr> {
+        Some(&self.pred)
+    }
 }
 
 #[derive(Debug)]
 pub struct SignedType {
     pub name: Ident,
-    pub ident: proc_macro2::Ident,
-    pub constraint_check: Option<CCode>,
+    pub ident: c::Ident,
+    pub constraint_check: Option<c::Expr>,
+    pub image: c::Expr,
+    pub succ: c::Expr,
+    pub pred: c::Expr,
+
+    /// C integer type with the size of the range, for objects with convention C.
+    pub c_sized: c::Type,
+
+    /// The range, if it is static.
+    pub range: Option<(i64, i64)>,
+
+    /// The size clause.
+    pub size: Option<i64>,
+
+    /// The `Default_Value` aspect.
+    pub default_value: Option<i64>,
 }
 
 impl TypeImpl for SignedType {
@@ -398,8 +705,12 @@ impl TypeImpl for SignedType {
         &self.name.name
     }
 
-    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
-        self.ident.to_tokens(tokens);
+    fn declaration(&self) -> Option<&Ident> {
+        Some(&self.name)
+    }
+
+    fn c_type(&self) -> c::Type {
+        self.ident.clone().into()
     }
 
     fn can_assign(&self, source: &Type) -> bool {
@@ -410,7 +721,7 @@ impl TypeImpl for SignedType {
         }
     }
 
-    fn needs_constraint_check(&self, source: &Type) -> Option<&CCode> {
+    fn needs_constraint_check(&self, source: &Type) -> Option<&c::Expr> {
         if let Inner::Signed(source) = source.last_parent_inner() {
             if ptr::eq(self, source) {
                 return None;
@@ -418,13 +729,83 @@ impl TypeImpl for SignedType {
         }
         self.constraint_check.as_ref()
     }
+
+    fn image(&self) -> Option<&c::Expr> {
+        Some(&self.image)
+    }
+
+    fn succ(&self) -> Option<&c::Expr> {
+        Some(&self.succ)
+    }
+
+    fn pred(&self) -> Option<&c::Expr> {
+        Some(&self.pred)
+    }
+
+    fn default_value(&self) -> Option<i64> {
+        self.default_value
+    }
+}
+
+/// A floating or fixed point type.
+///
+/// Values of fixed point types are C floating point numbers too, they are not rounded to
+/// multiples of the small.
+#[derive(Debug)]
+pub struct FloatType {
+    pub name: Ident,
+    pub ident: c::Ident,
+    pub constraint_check: Option<c::Expr>,
+    pub image: c::Expr,
+}
+
+impl TypeImpl for FloatType {
+    fn to_str(&self) -> &str {
+        &self.name.name
+    }
+
+    fn declaration(&self) -> Option<&Ident> {
+        Some(&self.name)
+    }
+
+    fn c_type(&self) -> c::Type {
+        self.ident.clone().into()
+    }
+
+    fn can_assign(&self, source: &Type) -> bool {
+        match source.last_parent_inner() {
+            Inner::Real(_) => true,
+            Inner::Float(source) => ptr::eq(self, source),
+            _ => false,
+        }
+    }
+
+    fn needs_constraint_check(&self, source: &Type) -> Option<&c::Expr> {
+        if let Inner::Float(source) = source.last_parent_inner() {
+            if ptr::eq(self, source) {
+                return None;
+            }
+        }
+        self.constraint_check.as_ref()
+    }
+
+    fn image(&self) -> Option<&c::Expr> {
+        Some(&self.image)
+    }
 }
 
 #[derive(Debug)]
 pub struct RecordType {
     pub name: Ident,
-    pub ident: proc_macro2::Ident,
+    pub ident: c::Ident,
     pub fields: IndexMap<Box<str>, RecordField>,
+
+    /// The indices of the fields in the order of the C struct, a record representation
+    /// clause orders them by position.
+    pub c_order: Vec<usize>,
+
+    /// The size clause.
+    pub size: Option<i64>,
 }
 
 impl TypeImpl for RecordType {
@@ -432,8 +813,12 @@ impl TypeImpl for RecordType {
         &self.name.name
     }
 
-    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
-        self.ident.to_tokens(tokens);
+    fn declaration(&self) -> Option<&Ident> {
+        Some(&self.name)
+    }
+
+    fn c_type(&self) -> c::Type {
+        self.ident.clone().into()
     }
 
     fn can_assign(&self, source: &Type) -> bool {
@@ -443,7 +828,7 @@ impl TypeImpl for RecordType {
         }
     }
 
-    fn needs_constraint_check(&self, _source: &Type) -> Option<&CCode> {
+    fn needs_constraint_check(&self, _source: &Type) -> Option<&c::Expr> {
         None
     }
 
@@ -454,12 +839,16 @@ impl TypeImpl for RecordType {
             return Err(name.unrecoverable_error("unknown field name"));
         };
 
-        let ident = &field.ident;
+        let perm = match prefix.perm {
+            Permission::ReadWrite if field.bit_field.is_some() => Permission::BitField,
+            perm => perm,
+        };
         Ok(SingleExprValue {
             ty: field.ty.clone(),
-            perm: prefix.perm,
-            code: c_code! { (#prefix).#ident },
+            perm,
+            code: prefix.code.clone().member(field.ident.clone()),
             value: None,
+            subprogram: None,
         }
         .into())
     }
@@ -467,14 +856,23 @@ impl TypeImpl for RecordType {
 
 #[derive(Debug)]
 pub struct RecordField {
-    pub ident: proc_macro2::Ident,
+    pub ident: c::Ident,
     pub ty: Type,
+
+    /// The C type of the bit-field, if placed by a component clause in a bit-field.
+    pub bit_field: Option<c::Type>,
 }
 
 #[derive(Debug)]
 pub struct SubtypeType {
     pub parent: Type,
-    pub constraint_check: Option<CCode>,
+    pub constraint_check: Option<c::Expr>,
+
+    /// The first and last value of a range constraint.
+    pub bounds: Option<(c::Expr, c::Expr)>,
+
+    /// The first and last value of a static range constraint of an integer subtype.
+    pub static_bounds: Option<(i64, i64)>,
 }
 
 impl SubtypeType {
@@ -488,15 +886,19 @@ impl TypeImpl for SubtypeType {
         self.last_parent().to_str()
     }
 
-    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
-        self.last_parent().to_tokens(tokens);
+    fn declaration(&self) -> Option<&Ident> {
+        Inner!(self.last_parent().inner(), |value| value.declaration())
+    }
+
+    fn c_type(&self) -> c::Type {
+        self.last_parent().c_type()
     }
 
     fn can_assign(&self, source: &Type) -> bool {
         self.last_parent().can_assign(source)
     }
 
-    fn needs_constraint_check(&self, source: &Type) -> Option<&CCode> {
+    fn needs_constraint_check(&self, source: &Type) -> Option<&c::Expr> {
         for source in source.parents() {
             if let Inner::Subtype(source) = source {
                 if ptr::eq(self, source) {
@@ -2,22 +2,27 @@ use indexmap::{map::Entry, IndexMap};
 
 use crate::{
     parser::{
-        BaseName, Constraint, EnumTypeDefinition, Expr, FullTypeItem, ModularTypeDefinition, Name,
-        Range, RangeConstraint, RecordComponentList, RecordTypeDefinition, SignedTypeDefinition,
-        SubtypeItem, TypeDefinition, TypeItem, Variable,
+        BaseName, Constraint, EnumLiteral, EnumTypeDefinition, Expr, FixedTypeDefinition,
+        FloatTypeDefinition, FullTypeItem, ModularTypeDefinition, Name, Range, RangeConstraint,
+        RecordComponentList, RecordTypeDefinition, SignedTypeDefinition, SubtypeItem,
+        TypeDefinition, TypeItem, Variable,
     },
     tokenizer::{Ident, Span, Spanned},
     Result,
 };
 
 use super::{
-    expr::generate_function_call, standard, CCode, CodeGenExpr, CodeGenStmt, CodeGenType, Context,
-    EnumType, ExprValue, FunctionType, FunctionValue, IdentBuilder, Permission, RecordField,
-    RecordType, SignedType, SingleExprValue, SubtypeType, Type, TypeValue, Value,
+    c,
+    expr::generate_function_call,
+    pragma::PragmaPlace,
+    representation::{declare_aspects, min_size},
+    standard, CodeGenExpr, CodeGenStmt, CodeGenType, CompileTimeValue, Context, Convention,
+    EnumType, ExprValue, FloatType, FunctionType, FunctionValue, IdentBuilder, Permission,
+    RecordField, RecordType, SignedType, SingleExprValue, SubtypeType, Type, TypeValue, Value,
 };
 
 impl CodeGenStmt for TypeItem {
-    fn generate(&self, ctx: &mut Context) -> Result<CCode> {
+    fn generate(&self, ctx: &mut Context) -> Result<Vec<c::Stmt>> {
         match self {
             TypeItem::Full(item) => item.generate(ctx),
         }
@@ -25,52 +30,103 @@ impl CodeGenStmt for TypeItem {
 }
 
 impl CodeGenStmt for FullTypeItem {
-    fn generate(&self, ctx: &mut Context) -> Result<CCode> {
+    fn generate(&self, ctx: &mut Context) -> Result<Vec<c::Stmt>> {
+        declare_aspects(self, ctx)?;
         self.definition.generate(&self.name, ctx)
     }
 }
 
 impl CodeGenType for TypeDefinition {
-    fn generate(&self, name: &Ident, ctx: &mut Context) -> Result<CCode> {
+    fn generate(&self, name: &Ident, ctx: &mut Context) -> Result<Vec<c::Stmt>> {
         match self {
             Self::Enum(definition) => definition.generate(name, ctx),
             Self::Signed(definition) => definition.generate(name, ctx),
             Self::Modular(definition) => definition.generate(name, ctx),
+            Self::Float(definition) => definition.generate(name, ctx),
+            Self::Fixed(definition) => definition.generate(name, ctx),
             Self::Record(definition) => definition.generate(name, ctx),
+            Self::Private(_) => Err(name.unrecoverable_error(
+                "private type declaration is only allowed in a package specification",
+            )),
         }
     }
 }
 
 impl CodeGenType for EnumTypeDefinition {
-    fn generate(&self, name: &Ident, ctx: &mut Context) -> Result<CCode> {
-        let ident = IdentBuilder::type_(name);
-
-        ctx.push_type(c_code! {
-            typedef int #ident;
-        });
+    fn generate(&self, name: &Ident, ctx: &mut Context) -> Result<Vec<c::Stmt>> {
+        let ident = IdentBuilder::type_(ctx.path(), name);
+        let values = self
+            .values
+            .iter()
+            .map(EnumLiteral::ident)
+            .collect::<Vec<_>>();
+
+        let representation = ctx.representation(name).cloned().unwrap_or_default();
+        let codes = representation.enum_codes(&values, ctx)?;
+        let range = match &codes {
+            Some(codes) => (codes[0], codes[codes.len() - 1]),
+            None => (0, values.len() as i64 - 1),
+        };
+        let size = representation.size(name, Some(min_size(range.0, range.1)), ctx)?;
+        let attributes = representation.attributes(ctx)?;
+
+        ctx.push_type(c::Decl::Typedef(
+            c::Type::named("int"),
+            ident.clone(),
+            attributes,
+        ));
+
+        let image = generate_enum_image(
+            IdentBuilder::image(ctx.path(), name),
+            &ident,
+            &values,
+            codes.as_deref(),
+            ctx,
+        );
+
+        let (succ, pred) = generate_enum_succ_pred(
+            IdentBuilder::succ(ctx.path(), name),
+            IdentBuilder::pred(ctx.path(), name),
+            &ident,
+            values.len(),
+            codes.as_deref(),
+            ctx,
+        );
 
         let ty = Type::enum_(EnumType {
             name: name.clone(),
             ident: ident.clone(),
-            values: self.values.iter().cloned().collect(),
+            values: values.clone(),
+            image,
+            succ,
+            pred,
+            range,
+            size,
         });
 
-        for (i, value) in self.values.iter().enumerate() {
-            let value_ident = IdentBuilder::enum_value(name, value);
-            ctx.push_function(c_code! {
-                #ident #value_ident() {
-                    return #i;
-                }
-            });
+        for (i, value) in values.iter().enumerate() {
+            let value_ident = IdentBuilder::enum_value(ctx.path(), name, value);
+            let code = codes.as_ref().map_or(i as i64, |codes| codes[i]);
+            ctx.push_function(c::Decl::Function(c::Function {
+                return_type: ident.clone().into(),
+                name: value_ident.clone(),
+                params: Vec::new(),
+                body: Some(vec![c::Stmt::return_(c::Expr::int(code))]),
+                extern_c: false,
+            }));
             ctx.insert(
                 value,
-                Value::Function(FunctionValue::new(
-                    c_code! { #value_ident},
-                    Type::function(FunctionType {
-                        args: vec![],
-                        return_type: ty.clone(),
-                    }),
-                )),
+                Value::Function(
+                    FunctionValue::new(
+                        value_ident,
+                        Type::function(FunctionType {
+                            args: vec![],
+                            return_type: ty.clone(),
+                            static_link: None,
+                        }),
+                    )
+                    .with_declaration(value.span),
+                ),
             )?;
         }
 
@@ -78,33 +134,36 @@ impl CodeGenType for EnumTypeDefinition {
 
         standard::generate_comparison_ops(&ty, ctx)?;
 
-        let len = self.values.iter().len();
-        let values_str = self.values.iter().map(|v| &*v.name);
-        standard::generate_custom_print(
-            ty,
-            c_code! {
-                static const char *const values[] = {
-                    #(#values_str,)*
-                };
-                if (self < 0 || self >= #len) {
-                    throw_Constraint_Error();
-                }
-                printf("%s\n", values[self]);
-            },
-            ctx,
-        )?;
+        let values_str = values.iter().map(|v| v.name.to_string());
+        let (mut body, value_name) = enum_values_check(values_str, codes.as_deref());
+        body.push(
+            c::Expr::ident("printf")
+                .call([c::Expr::str("%s\n"), value_name])
+                .into(),
+        );
+        standard::generate_custom_print(ty, body, ctx)?;
 
-        Ok(c_code!())
+        Ok(Vec::new())
     }
 }
 
 impl CodeGenType for SignedTypeDefinition {
-    fn generate(&self, name: &Ident, ctx: &mut Context) -> Result<CCode> {
-        let ident = IdentBuilder::type_(name);
-
-        ctx.push_type(c_code! {
-            typedef ssize_t #ident;
-        });
+    fn generate(&self, name: &Ident, ctx: &mut Context) -> Result<Vec<c::Stmt>> {
+        let ident = IdentBuilder::type_(ctx.path(), name);
+
+        let range =
+            static_integer(&self.range.start, ctx).zip(static_integer(&self.range.end, ctx));
+        let representation = ctx.representation(name).cloned().unwrap_or_default();
+        let size =
+            representation.size(name, range.map(|(first, last)| min_size(first, last)), ctx)?;
+        let attributes = representation.attributes(ctx)?;
+        let default_value = representation.default_value(name, range, ctx)?;
+
+        ctx.push_type(c::Decl::Typedef(
+            c::Type::named("ssize_t"),
+            ident.clone(),
+            attributes,
+        ));
 
         let constraint_check = {
             let constraint = RangeConstraint {
@@ -112,20 +171,32 @@ impl CodeGenType for SignedTypeDefinition {
                 range: self.range.clone(),
             }
             .generate(&Type::integer(), ctx)?;
-            let constraint_ident = IdentBuilder::constraint_check(name);
-            ctx.push_function(c_code! {
-                #ident #constraint_ident (#ident self) {
-                    #constraint
-                    return self;
-                }
-            });
-            Some(c_code! { #constraint_ident})
+            let constraint_ident = IdentBuilder::constraint_check(ctx.path(), name);
+            push_constraint_check(ident.clone().into(), &constraint_ident, constraint, ctx);
+            constraint_ident
         };
 
+        let self_ = c::Expr::ident("self");
+        let check = |code: c::Expr| c::Expr::from(constraint_check.clone()).call([code]);
+        let (succ, pred) = generate_signed_succ_pred(
+            name,
+            &ident,
+            check(self_.clone().binary(c::BinaryOp::Add, c::Expr::int(1))),
+            check(self_.binary(c::BinaryOp::Sub, c::Expr::int(1))),
+            ctx,
+        );
+
         let ty = Type::signed(SignedType {
             name: name.clone(),
             ident: ident.clone(),
-            constraint_check,
+            constraint_check: Some(constraint_check.into()),
+            image: generate_signed_image(name, &ident, ctx),
+            succ,
+            pred,
+            c_sized: c_sized_type(range, size, false),
+            range,
+            size,
+            default_value,
         });
 
         ctx.insert(name, Value::Type(TypeValue { ty: ty.clone() }))?;
@@ -133,17 +204,172 @@ impl CodeGenType for SignedTypeDefinition {
         standard::generate_signed_ops(&ty, ctx)?;
         standard::generate_print(ty, "%ld", ctx)?;
 
-        Ok(c_code!())
+        Ok(Vec::new())
     }
 }
 
-impl CodeGenType for ModularTypeDefinition {
-    fn generate(&self, name: &Ident, ctx: &mut Context) -> Result<CCode> {
-        let ident = IdentBuilder::type_(name);
+/// The value of a static integer expression.
+pub(super) fn static_integer(expr: &Expr, ctx: &mut Context) -> Option<i64> {
+    let value = expr.generate(ctx).ok()?.filter_type(expr, &Type::integer());
+    match value.ok()?.value {
+        Some(CompileTimeValue::Integer(value)) => Some(value),
+        _ => None,
+    }
+}
 
-        ctx.push_type(c_code! {
-            typedef ssize_t #ident;
-        });
+/// The value of a static real expression.
+fn static_real(expr: &Expr, ctx: &mut Context) -> Option<f64> {
+    let value = expr.generate(ctx).ok()?.filter_type(expr, &Type::real());
+    match value.ok()?.value {
+        Some(CompileTimeValue::Real(value)) => Some(value),
+        _ => None,
+    }
+}
+
+/// The smallest C integer type of `<stdint.h>` for the range `first .. last` and at least
+/// `size` bits, `ssize_t` if the range is not static.
+fn c_sized_type(range: Option<(i64, i64)>, size: Option<i64>, unsigned: bool) -> c::Type {
+    let Some((first, last)) = range else {
+        return c::Type::named("ssize_t");
+    };
+    let bits = [8, 16, 32]
+        .into_iter()
+        .filter(|&bits| bits >= size.unwrap_or(0))
+        .find(|&bits| {
+            if unsigned {
+                first >= 0 && last < 1 << bits
+            } else {
+                first >= -(1 << (bits - 1)) && last < 1 << (bits - 1)
+            }
+        })
+        .unwrap_or(64);
+    c::Type::named(&format!("{}int{bits}_t", if unsigned { "u" } else { "" }))
+}
+
+impl CodeGenType for FloatTypeDefinition {
+    fn generate(&self, name: &Ident, ctx: &mut Context) -> Result<Vec<c::Stmt>> {
+        let Some(digits) = static_integer(&self.digits, ctx) else {
+            return Err(self.digits.unrecoverable_error("expected static integer"));
+        };
+        // the precision of `float`, `double` and x86 `long double`
+        let c_type = match digits {
+            ..=0 => return Err(self.digits.unrecoverable_error("digits must be positive")),
+            1..=6 => "float",
+            7..=15 => "double",
+            16..=18 => "long double",
+            _ => return Err(self.digits.unimplemented_error("digits greater than 18")),
+        };
+        // like GNAT, the image has `digits` significant digits in scientific notation
+        // the sign, the point and up to 4 digits of exponent
+        let format = format!("%.{}LE", digits - 1);
+        let len = digits + 8;
+        generate_real_type(name, c_type, self.range.as_ref(), &format, len, ctx)
+    }
+}
+
+impl CodeGenType for FixedTypeDefinition {
+    fn generate(&self, name: &Ident, ctx: &mut Context) -> Result<Vec<c::Stmt>> {
+        let Some(delta) = static_real(&self.delta, ctx) else {
+            return Err(self.delta.unrecoverable_error("expected static real"));
+        };
+        if delta <= 0.0 {
+            return Err(self.delta.unrecoverable_error("delta must be positive"));
+        }
+        let range = &self.range.range;
+        let (Some(first), Some(last)) =
+            (static_real(&range.start, ctx), static_real(&range.end, ctx))
+        else {
+            return Err(range.unrecoverable_error("expected static range"));
+        };
+        // the image has as many decimal digits as needed for the delta, `'Aft`
+        let aft = (-delta.log10()).ceil().max(1.0) as i64;
+        let fore = first.abs().max(last.abs()).log10().floor().max(0.0) as i64 + 1;
+        let format = format!("%.{aft}Lf");
+        generate_real_type(
+            name,
+            "double",
+            Some(&self.range),
+            &format,
+            fore + aft + 2,
+            ctx,
+        )
+    }
+}
+
+/// Declare the floating or fixed point type `name` with the C type `c_type`.
+///
+/// The `format` of `printf` is used for the image and printing, the image of
+/// non-negative values starts with a space and has at most `len` characters.
+fn generate_real_type(
+    name: &Ident,
+    c_type: &str,
+    range: Option<&RangeConstraint>,
+    format: &str,
+    len: i64,
+    ctx: &mut Context,
+) -> Result<Vec<c::Stmt>> {
+    let ident = IdentBuilder::type_(ctx.path(), name);
+
+    let representation = ctx.representation(name).cloned().unwrap_or_default();
+    let attributes = representation.attributes(ctx)?;
+    ctx.push_type(c::Decl::Typedef(
+        c::Type::named(c_type),
+        ident.clone(),
+        attributes,
+    ));
+
+    let constraint_check = match range {
+        Some(range) => {
+            let constraint = range.generate(&Type::real(), ctx)?;
+            let constraint_ident = IdentBuilder::constraint_check(ctx.path(), name);
+            push_constraint_check(ident.clone().into(), &constraint_ident, constraint, ctx);
+            Some(constraint_ident.into())
+        }
+        None => None,
+    };
+
+    let value = c::Expr::ident("self").cast(c::Type::named("long double"));
+    let image = generate_printf_image(
+        name,
+        &ident,
+        &format.replacen('%', "% ", 1),
+        value.clone(),
+        len,
+        ctx,
+    );
+
+    let ty = Type::float(FloatType {
+        name: name.clone(),
+        ident: ident.clone(),
+        constraint_check,
+        image,
+    });
+
+    ctx.insert(name, Value::Type(TypeValue { ty: ty.clone() }))?;
+
+    standard::generate_real_ops(&ty, ctx)?;
+    let print = c::Expr::ident("printf").call([c::Expr::str(format!("{format}\n")), value]);
+    standard::generate_custom_print(ty, vec![print.into()], ctx)?;
+
+    Ok(Vec::new())
+}
+
+impl CodeGenType for ModularTypeDefinition {
+    fn generate(&self, name: &Ident, ctx: &mut Context) -> Result<Vec<c::Stmt>> {
+        let ident = IdentBuilder::type_(ctx.path(), name);
+
+        let range = static_integer(&self.modulus, ctx).map(|modulus| (0, modulus - 1));
+        let representation = ctx.representation(name).cloned().unwrap_or_default();
+        let size =
+            representation.size(name, range.map(|(first, last)| min_size(first, last)), ctx)?;
+        let attributes = representation.attributes(ctx)?;
+        let default_value = representation.default_value(name, range, ctx)?;
+
+        ctx.push_type(c::Decl::Typedef(
+            c::Type::named("ssize_t"),
+            ident.clone(),
+            attributes,
+        ));
 
         let constraint_check = {
             let constraint = RangeConstraint {
@@ -155,73 +381,392 @@ impl CodeGenType for ModularTypeDefinition {
                 },
             }
             .generate(&Type::integer(), ctx)?;
-            let constraint_ident = IdentBuilder::constraint_check(name);
-            ctx.push_function(c_code! {
-                #ident #constraint_ident (#ident self) {
-                    #constraint
-                    return self;
-                }
-            });
-            Some(c_code! { #constraint_ident})
+            let constraint_ident = IdentBuilder::constraint_check(ctx.path(), name);
+            push_constraint_check(ident.clone().into(), &constraint_ident, constraint, ctx);
+            Some(constraint_ident.into())
         };
 
+        let modulus = self
+            .modulus
+            .generate_with_type_and_check(&Type::integer(), ctx)?;
+
+        // the successor of the last value is 0, the predecessor of 0 the last value
+        let self_ = c::Expr::ident("self");
+        let (succ, pred) = generate_signed_succ_pred(
+            name,
+            &ident,
+            self_
+                .clone()
+                .binary(c::BinaryOp::Add, c::Expr::int(1))
+                .binary(c::BinaryOp::Rem, modulus.clone()),
+            self_
+                .binary(c::BinaryOp::Add, modulus.clone())
+                .binary(c::BinaryOp::Sub, c::Expr::int(1))
+                .binary(c::BinaryOp::Rem, modulus.clone()),
+            ctx,
+        );
+
         let ty = Type::signed(SignedType {
             name: name.clone(),
             ident: ident.clone(),
             constraint_check,
+            image: generate_signed_image(name, &ident, ctx),
+            succ,
+            pred,
+            c_sized: c_sized_type(range, size, true),
+            range,
+            size,
+            default_value,
         });
 
         ctx.insert(name, Value::Type(TypeValue { ty: ty.clone() }))?;
 
-        let modulus = self
-            .modulus
-            .generate_with_type_and_check(&Type::integer(), ctx)?;
-
         standard::generate_modular_ops(&ty, &modulus, ctx)?;
         standard::generate_print(ty, "%ld", ctx)?;
 
-        Ok(c_code!())
+        Ok(Vec::new())
     }
 }
 
+fn generate_signed_image(name: &Ident, ident: &c::Ident, ctx: &mut Context) -> c::Expr {
+    generate_printf_image(name, ident, "% ld", c::Expr::ident("self"), 24, ctx)
+}
+
+/// The function implementing `'Image` with the `printf` format `format` of `value`, an
+/// expression of `self`, which has at most `len` characters.
+fn generate_printf_image(
+    name: &Ident,
+    ident: &c::Ident,
+    format: &str,
+    value: c::Expr,
+    len: i64,
+    ctx: &mut Context,
+) -> c::Expr {
+    let image_ident = IdentBuilder::image(ctx.path(), name);
+    let string = Type::string().c_type();
+    let image = c::Expr::ident("image");
+    let body = vec![
+        c::VarDecl::new(string.clone(), c::Ident::new("image"))
+            .init(
+                c::Expr::ident("annabella_string_allocate")
+                    .call([c::Expr::int(1), c::Expr::int(len)]),
+            )
+            .into(),
+        image
+            .clone()
+            .member(c::Ident::new("last"))
+            .assign(c::Expr::ident("snprintf").call([
+                image.clone().member(c::Ident::new("data")),
+                c::Expr::int(len + 1),
+                c::Expr::str(format),
+                value,
+            ]))
+            .into(),
+        c::Stmt::return_(image),
+    ];
+    ctx.push_function(c::Decl::Function(c::Function {
+        return_type: string,
+        name: image_ident.clone(),
+        params: vec![self_param(ident.clone().into())],
+        body: Some(body),
+        extern_c: false,
+    }));
+    image_ident.into()
+}
+
+/// The function implementing `'Image` for the enumeration type `ident` with the literals
+/// `values`.
+///
+/// The image of an identifier is in upper case, the image of a character literal is the
+/// literal with its quotes, like `'a'`.
+pub(super) fn generate_enum_image(
+    image_ident: c::Ident,
+    ident: &c::Ident,
+    values: &[Ident],
+    codes: Option<&[i64]>,
+    ctx: &mut Context,
+) -> c::Expr {
+    let images = values.iter().map(|value| {
+        if value.name.starts_with('\'') {
+            value.name.to_string()
+        } else {
+            value.name.to_uppercase()
+        }
+    });
+    let (mut body, value_name) = enum_values_check(images, codes);
+    body.push(c::Stmt::return_(
+        c::Expr::ident("annabella_string_from_c").call([value_name]),
+    ));
+    ctx.push_function(c::Decl::Function(c::Function {
+        return_type: Type::string().c_type(),
+        name: image_ident.clone(),
+        params: vec![self_param(ident.clone().into())],
+        body: Some(body),
+        extern_c: false,
+    }));
+    image_ident.into()
+}
+
+/// The functions implementing `'Succ` and `'Pred` of the signed or modular type `ident`,
+/// returning `succ` and `pred` computed from `self`.
+fn generate_signed_succ_pred(
+    name: &Ident,
+    ident: &c::Ident,
+    succ: c::Expr,
+    pred: c::Expr,
+    ctx: &mut Context,
+) -> (c::Expr, c::Expr) {
+    (
+        push_attribute_function(
+            IdentBuilder::succ(ctx.path(), name),
+            ident,
+            vec![c::Stmt::return_(succ)],
+            ctx,
+        ),
+        push_attribute_function(
+            IdentBuilder::pred(ctx.path(), name),
+            ident,
+            vec![c::Stmt::return_(pred)],
+            ctx,
+        ),
+    )
+}
+
+/// The functions implementing `'Succ` and `'Pred` of the enumeration type `ident` with
+/// `len` literals, which are the next and previous literal of `self`.
+///
+/// The literal after `self` is `self + 1`, or with the `codes` of a representation
+/// clause, the next code in the table `codes`.
+pub(super) fn generate_enum_succ_pred(
+    succ_ident: c::Ident,
+    pred_ident: c::Ident,
+    ident: &c::Ident,
+    len: usize,
+    codes: Option<&[i64]>,
+    ctx: &mut Context,
+) -> (c::Expr, c::Expr) {
+    let throw = || vec![c::Expr::ident("throw_Constraint_Error").call([]).into()];
+    let self_ = c::Expr::ident("self");
+    let last = c::Expr::int(len as i128 - 1);
+    let (succ, pred) = match codes {
+        None => (
+            vec![
+                c::Stmt::if_(self_.clone().binary(c::BinaryOp::Ge, last), throw()),
+                c::Stmt::return_(self_.clone().binary(c::BinaryOp::Add, c::Expr::int(1))),
+            ],
+            vec![
+                c::Stmt::if_(
+                    self_.clone().binary(c::BinaryOp::Le, c::Expr::int(0)),
+                    throw(),
+                ),
+                c::Stmt::return_(self_.binary(c::BinaryOp::Sub, c::Expr::int(1))),
+            ],
+        ),
+        Some(codes) => {
+            let (mut succ, pos) = enum_code_position(codes);
+            let mut pred = succ.clone();
+            let codes_table = c::Expr::ident("codes");
+            succ.extend([
+                c::Stmt::if_(pos.clone().binary(c::BinaryOp::Ge, last), throw()),
+                c::Stmt::return_(
+                    codes_table
+                        .clone()
+                        .index(pos.clone().binary(c::BinaryOp::Add, c::Expr::int(1))),
+                ),
+            ]);
+            pred.extend([
+                c::Stmt::if_(
+                    pos.clone().binary(c::BinaryOp::Eq, c::Expr::int(0)).binary(
+                        c::BinaryOp::Or,
+                        pos.clone()
+                            .binary(c::BinaryOp::Eq, c::Expr::int(len as i128)),
+                    ),
+                    throw(),
+                ),
+                c::Stmt::return_(codes_table.index(pos.binary(c::BinaryOp::Sub, c::Expr::int(1)))),
+            ]);
+            (succ, pred)
+        }
+    };
+    (
+        push_attribute_function(succ_ident, ident, succ, ctx),
+        push_attribute_function(pred_ident, ident, pred, ctx),
+    )
+}
+
+/// Push the function `name` of the type `ident` with the parameter `self`.
+fn push_attribute_function(
+    name: c::Ident,
+    ident: &c::Ident,
+    body: Vec<c::Stmt>,
+    ctx: &mut Context,
+) -> c::Expr {
+    ctx.push_function(c::Decl::Function(c::Function {
+        return_type: ident.clone().into(),
+        name: name.clone(),
+        params: vec![self_param(ident.clone().into())],
+        body: Some(body),
+        extern_c: false,
+    }));
+    name.into()
+}
+
+/// The parameter `self` of the functions generated for a type.
+fn self_param(ty: c::Type) -> c::Param {
+    c::Param::new(ty.const_(), c::Ident::new("self"))
+}
+
+/// The table `values` of the names of the enumeration values, and the check of `self`.
+///
+/// Returns the statements and the name of the value `self`, `values[self]`. With the
+/// `codes` of a representation clause, `values[pos]` where `pos` is the position of the
+/// code in the table `codes`.
+fn enum_values_check(
+    names: impl Iterator<Item = String>,
+    codes: Option<&[i64]>,
+) -> (Vec<c::Stmt>, c::Expr) {
+    let names = names.map(c::Expr::str).collect::<Vec<_>>();
+    let len = c::Expr::int(names.len() as i128);
+    let values = c::Expr::ident("values");
+    let mut stmts = vec![c::VarDecl::new(
+        c::Type::char().const_().pointer().const_(),
+        c::Ident::new("values"),
+    )
+    .static_()
+    .array(None)
+    .init(c::Expr::InitList(names))
+    .into()];
+    let throw = vec![c::Expr::ident("throw_Constraint_Error").call([]).into()];
+    let self_ = c::Expr::ident("self");
+    let Some(codes) = codes else {
+        stmts.push(c::Stmt::if_(
+            self_
+                .clone()
+                .binary(c::BinaryOp::Lt, c::Expr::int(0))
+                .binary(c::BinaryOp::Or, self_.clone().binary(c::BinaryOp::Ge, len)),
+            throw,
+        ));
+        return (stmts, values.index(self_));
+    };
+    let (position, pos) = enum_code_position(codes);
+    stmts.extend(position);
+    stmts.push(c::Stmt::if_(
+        pos.clone().binary(c::BinaryOp::Eq, len),
+        throw,
+    ));
+    (stmts, values.index(pos))
+}
+
+/// The search of the position `pos` of `self` in the table `codes` of an enumeration
+/// type with a representation clause, the number of codes if not found.
+fn enum_code_position(codes: &[i64]) -> (Vec<c::Stmt>, c::Expr) {
+    let len = c::Expr::int(codes.len() as i128);
+    let self_ = c::Expr::ident("self");
+    let pos = c::Expr::ident("pos");
+    let codes_table = c::Expr::ident("codes");
+    let stmts = vec![
+        c::VarDecl::new(c::Type::named("int").const_(), c::Ident::new("codes"))
+            .static_()
+            .array(None)
+            .init(c::Expr::InitList(
+                codes.iter().map(|&code| c::Expr::int(code)).collect(),
+            ))
+            .into(),
+        c::VarDecl::new(c::Type::named("ssize_t"), c::Ident::new("pos"))
+            .init(c::Expr::int(0))
+            .into(),
+        c::Stmt::While {
+            cond: pos.clone().binary(c::BinaryOp::Lt, len.clone()).binary(
+                c::BinaryOp::And,
+                codes_table
+                    .index(pos.clone())
+                    .binary(c::BinaryOp::Ne, self_),
+            ),
+            body: vec![pos
+                .clone()
+                .assign_op(c::BinaryOp::Add, c::Expr::int(1))
+                .into()],
+        },
+    ];
+    (stmts, pos)
+}
+
+/// Push the function `ident` checking the `constraint` of a value `self` of type `ty`.
+fn push_constraint_check(ty: c::Type, ident: &c::Ident, constraint: c::Stmt, ctx: &mut Context) {
+    ctx.push_function(c::Decl::Function(c::Function {
+        return_type: ty.clone(),
+        name: ident.clone(),
+        params: vec![c::Param::new(ty, c::Ident::new("self"))],
+        body: Some(vec![constraint, c::Stmt::return_(c::Expr::ident("self"))]),
+        extern_c: false,
+    }));
+}
+
 impl CodeGenType for RecordTypeDefinition {
-    fn generate(&self, name: &Ident, ctx: &mut Context) -> Result<CCode> {
-        let ident = IdentBuilder::type_(name);
+    fn generate(&self, name: &Ident, ctx: &mut Context) -> Result<Vec<c::Stmt>> {
+        let ident = IdentBuilder::type_(ctx.path(), name);
 
+        let representation = ctx.representation(name).cloned().unwrap_or_default();
         let mut ty = RecordType {
             name: name.clone(),
             ident: ident.clone(),
             fields: IndexMap::new(),
+            c_order: Vec::new(),
+            size: representation.size(name, None, ctx)?,
         };
 
-        let struct_code = self.components.generate(&mut ty, ctx)?;
-
-        ctx.push_type(c_code! {
-            typedef #struct_code #ident;
-        });
+        // with convention C the fields have the Ada names and C types of the size of
+        // their range, like a struct declared in C
+        let convention = ctx
+            .interface(name)
+            .map_or(Convention::Ada, |interface| interface.convention);
+        let mut fields = self.components.generate(&mut ty, convention, ctx)?;
+        ty.c_order = (0..ty.fields.len()).collect();
+
+        let mut attributes = representation.attributes(ctx)?;
+        if let Some(layout) = representation.record_layout(&ty, &fields, ty.size, ctx)? {
+            for field in ty.fields.values_mut() {
+                field.bit_field = layout
+                    .iter()
+                    .find(|decl| decl.bits.is_some() && decl.name == field.ident)
+                    .map(|decl| decl.ty.clone());
+            }
+            ty.c_order = layout
+                .iter()
+                .filter_map(|decl| {
+                    ty.fields
+                        .values()
+                        .position(|field| field.ident == decl.name)
+                })
+                .collect();
+            fields = layout;
+            attributes.packed = true;
+        }
+        ctx.push_type(c::Decl::TypedefStruct(fields, ident, attributes));
 
         let print_fields = ty
             .fields
             .iter()
             .map(|(name, field)| {
-                let ident = &field.ident;
                 let value = generate_function_call(
                     &Name::Base(BaseName::Ident(standard::print())),
                     [&ExprValue::from(SingleExprValue {
                         ty: field.ty.clone(),
                         perm: Permission::Read,
-                        code: c_code! { self.#ident },
+                        code: c::Expr::ident("self").member(field.ident.clone()),
                         value: None,
+                        subprogram: None,
                     })]
                     .into_iter(),
                     ctx,
                 )?
                 .filter_type(&Span::call_site(), &Type::void())?;
-                let code = value.code;
-                Ok(c_code! {
-                    printf("  %s => ", #name);
-                    #code;
-                })
+                Ok([
+                    c::Expr::ident("printf")
+                        .call([c::Expr::str("  %s => "), c::Expr::str(&**name)])
+                        .into(),
+                    value.code.into(),
+                ])
             })
             .collect::<Result<Vec<_>>>()?;
 
@@ -229,50 +774,70 @@ impl CodeGenType for RecordTypeDefinition {
 
         ctx.insert(name, Value::Type(TypeValue { ty: ty.clone() }))?;
 
-        let name_str = &name.name;
-
-        standard::generate_custom_print(
-            ty,
-            c_code! {
-                printf("%s {\n", #name_str);
-                #(#print_fields)*
-                printf("}\n");
-            },
-            ctx,
-        )?;
+        let printf = c::Expr::ident("printf");
+        let mut body = vec![printf
+            .clone()
+            .call([c::Expr::str("%s {\n"), c::Expr::str(&*name.name)])
+            .into()];
+        body.extend(print_fields.into_iter().flatten());
+        body.push(printf.call([c::Expr::str("}\n")]).into());
+        standard::generate_custom_print(ty, body, ctx)?;
 
-        Ok(c_code! {})
+        Ok(Vec::new())
     }
 }
 
 impl RecordComponentList {
-    fn generate(&self, record: &mut RecordType, ctx: &mut Context) -> Result<CCode> {
+    fn generate(
+        &self,
+        record: &mut RecordType,
+        convention: Convention,
+        ctx: &mut Context,
+    ) -> Result<Vec<c::VarDecl>> {
         let components = self
             .components
             .iter()
-            .map(|v| v.generate_record(record, ctx))
+            .map(|v| v.generate_record(record, convention, ctx))
             .collect::<Result<Vec<_>>>()?;
+        for pragma in &self.pragmas {
+            pragma.generate_in(PragmaPlace::Component, ctx)?;
+        }
         if let Some(variant) = &self.variant {
-            return Err(variant.unrecoverable_error("not yet implemented"));
+            return Err(variant.unimplemented_error("not yet implemented"));
         }
-        Ok(c_code! {
-            struct {
-                #(#components)*
-            }
-        })
+        Ok(components)
     }
 }
 
 impl Variable {
-    fn generate_record(&self, record: &mut RecordType, ctx: &mut Context) -> Result<CCode> {
-        let ty = Type::from_ident(&self.ty, ctx)?;
-        let ident = IdentBuilder::field(&self.name);
-        let code = c_code! {
-            #ty #ident;
+    fn generate_record(
+        &self,
+        record: &mut RecordType,
+        convention: Convention,
+        ctx: &mut Context,
+    ) -> Result<c::VarDecl> {
+        if let Some(constant) = &self.constant {
+            return Err(constant.unrecoverable_error("record component cannot be constant"));
+        }
+        if let Some(init) = self.init() {
+            return Err(init.unimplemented_error("default component values not yet implemented"));
+        }
+        let ty = Type::from_name(&self.ty, ctx)?;
+        let (ident, c_type) = match convention {
+            Convention::Ada => (IdentBuilder::field(&self.name), ty.c_type()),
+            Convention::C => (
+                c::Ident::new(&self.name.name),
+                ty.c_type_with(convention, ctx),
+            ),
         };
+        let code = c::VarDecl::new(c_type, ident.clone());
         match record.fields.entry(self.name.name.clone()) {
             Entry::Vacant(entry) => {
-                entry.insert(RecordField { ident, ty });
+                entry.insert(RecordField {
+                    ident,
+                    ty,
+                    bit_field: None,
+                });
             }
             Entry::Occupied(_) => {
                 return Err(self.name.unrecoverable_error("field name already in use"));
@@ -283,7 +848,7 @@ impl Variable {
 }
 
 impl CodeGenStmt for SubtypeItem {
-    fn generate(&self, ctx: &mut Context) -> Result<CCode> {
+    fn generate(&self, ctx: &mut Context) -> Result<Vec<c::Stmt>> {
         let name = &self.name;
         let parent = Type::from_ident(&self.mark, ctx)?;
 
@@ -292,16 +857,20 @@ impl CodeGenStmt for SubtypeItem {
             .as_ref()
             .map(|constraint| constraint.generate(&parent, ctx))
             .transpose()?;
+        let bounds = self
+            .constraint
+            .as_ref()
+            .map(|constraint| constraint.bounds(&parent, ctx))
+            .transpose()?;
+        let static_bounds = self
+            .constraint
+            .as_ref()
+            .and_then(|constraint| constraint.static_bounds(ctx));
 
         let constraint_check = if let Some(constraint) = constraint {
-            let ident = IdentBuilder::constraint_check(name);
-            ctx.push_function(c_code! {
-                #parent #ident(#parent self) {
-                    #constraint
-                    return self;
-                }
-            });
-            Some(c_code! { #ident })
+            let ident = IdentBuilder::constraint_check(ctx.path(), name);
+            push_constraint_check(parent.c_type(), &ident, constraint, ctx);
+            Some(ident.into())
         } else {
             None
         };
@@ -309,29 +878,57 @@ impl CodeGenStmt for SubtypeItem {
         let ty = Type::subtype(SubtypeType {
             parent,
             constraint_check,
+            bounds,
+            static_bounds,
         });
         ctx.insert(name, Value::Type(TypeValue { ty }))?;
 
-        Ok(c_code!())
+        Ok(Vec::new())
     }
 }
 
 impl Constraint {
-    fn generate(&self, ty: &Type, ctx: &mut Context) -> Result<CCode> {
+    pub(super) fn generate(&self, ty: &Type, ctx: &mut Context) -> Result<c::Stmt> {
         match self {
             Self::Range(constraint) => constraint.generate(ty, ctx),
         }
     }
+
+    /// The first and last value of the constraint on values of type `ty`.
+    pub(super) fn bounds(&self, ty: &Type, ctx: &mut Context) -> Result<(c::Expr, c::Expr)> {
+        match self {
+            Self::Range(constraint) => constraint.bounds(ty, ctx),
+        }
+    }
+
+    /// The first and last value of a static constraint of an integer subtype.
+    fn static_bounds(&self, ctx: &mut Context) -> Option<(i64, i64)> {
+        match self {
+            Self::Range(constraint) => {
+                let range = &constraint.range;
+                static_integer(&range.start, ctx).zip(static_integer(&range.end, ctx))
+            }
+        }
+    }
 }
 
 impl RangeConstraint {
-    fn generate(&self, ty: &Type, ctx: &mut Context) -> Result<CCode> {
-        let start = self.range.start.generate_with_type_and_check(ty, ctx)?;
-        let end = self.range.end.generate_with_type_and_check(ty, ctx)?;
-        Ok(c_code! {
-            if (self < #start || #end < self) {
-                throw_Constraint_Error();
-            }
-        })
+    fn generate(&self, ty: &Type, ctx: &mut Context) -> Result<c::Stmt> {
+        let (start, end) = self.bounds(ty, ctx)?;
+        let self_ = c::Expr::ident("self");
+        Ok(c::Stmt::if_(
+            self_
+                .clone()
+                .binary(c::BinaryOp::Lt, start)
+                .binary(c::BinaryOp::Or, end.binary(c::BinaryOp::Lt, self_)),
+            vec![c::Expr::ident("throw_Constraint_Error").call([]).into()],
+        ))
+    }
+
+    fn bounds(&self, ty: &Type, ctx: &mut Context) -> Result<(c::Expr, c::Expr)> {
+        Ok((
+            self.range.start.generate_with_type_and_check(ty, ctx)?,
+            self.range.end.generate_with_type_and_check(ty, ctx)?,
+        ))
     }
 }
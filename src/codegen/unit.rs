@@ -0,0 +1,216 @@
+use std::{fs, path::PathBuf};
+
+use crate::{
+    parser::{self, CompilationUnit, Item, UnitName},
+    runtime,
+    tokenizer::{Spanned, TokenStream},
+    Result,
+};
+
+use super::{package, pragma::PragmaPlace, CodeGenStmt, Context, Language, UnitPart};
+
+/// Generate the declarations of the bundled package `Standard` at library level.
+pub(super) fn load_standard(ctx: &mut Context) -> Result<()> {
+    let unit = runtime::STANDARD;
+    ctx.mark_unit_loaded("standard".into());
+    ctx.push_runtime_header(runtime::EXCEPTIONS_H);
+    ctx.push_runtime(match ctx.language() {
+        Language::C => runtime::EXCEPTIONS_C,
+        Language::Cxx => runtime::EXCEPTIONS_CXX,
+    });
+    push_runtime_code(&unit, ctx);
+    let source = ctx.target().expand(unit.spec);
+    let input = TokenStream::parse(&source, Some(unit.spec_path()))?;
+    let units: Vec<CompilationUnit> = parser::parse(input, ctx.diagnostics())?;
+    for unit in &units {
+        let Item::Package(package) = &unit.item else {
+            return Err(unit
+                .item
+                .unrecoverable_error("expected package specification"));
+        };
+        package::generate_declarations(package, ctx)?;
+    }
+    Ok(())
+}
+
+/// Add the hand written C code of a runtime unit to the current unit.
+fn push_runtime_code(unit: &runtime::RuntimeUnit, ctx: &mut Context) {
+    if let Some(c_header) = unit.c_header {
+        ctx.push_runtime_header(c_header);
+    }
+    if let Some(c_code) = unit.c_code {
+        ctx.push_runtime(c_code);
+    }
+}
+
+impl CompilationUnit {
+    pub(super) fn generate(&self, ctx: &mut Context) -> Result<()> {
+        let name = self.name();
+        let key = name.map_or_else(|| LIBRARY_ITEMS.into(), unit_key);
+        if let Item::Package(_) = &self.item {
+            ctx.mark_unit_loaded(key.clone());
+        }
+        let is_first = ctx.start_loading_units();
+
+        // a subprogram body is also its specification, unless loaded with a spec file
+        let part = match self.item {
+            Item::PackageBody(_) => UnitPart::Body,
+            Item::Function(_) if ctx.is_unit_loaded(&key) => UnitPart::Body,
+            _ => UnitPart::Spec,
+        };
+        // units of the main source file see all units generated before them
+        let in_main_file = ctx.current_unit().is_none();
+        ctx.in_unit(&key, &file_name(&key), part, |ctx| {
+            if in_main_file {
+                ctx.add_preceding_dependencies();
+            }
+            if let Some(parent) = name.and_then(UnitName::parent) {
+                ctx.add_dependency(&unit_key(&parent), true);
+            }
+            // a package body on its own, like a file of the survey, needs its specification
+            if let (Item::PackageBody(_), Some(name)) = (&self.item, name) {
+                load_spec(name, false, ctx)?;
+            }
+            for name in self.withs() {
+                load_spec(name, true, ctx)?;
+                ctx.add_with(&unit_key(name), name.span());
+            }
+            let withs = self.withs().collect::<Vec<_>>();
+            for pragma in self.pragmas() {
+                pragma.generate_in(PragmaPlace::Context(&withs), ctx)?;
+            }
+
+            // context clauses only apply to this compilation unit
+            let mut sub_ctx = ctx.subscope(None);
+            for use_ in self.uses() {
+                use_.generate(&mut sub_ctx)?;
+            }
+            let code = self.item.generate(&mut sub_ctx)?;
+            sub_ctx.push_main(code);
+            for pragma in &self.trailing_pragmas {
+                pragma.generate_in(PragmaPlace::Unit, &mut sub_ctx)?;
+            }
+
+            let values = sub_ctx.into_values();
+            ctx.extend(values)?;
+            if is_first {
+                load_pending_bodies(ctx)?;
+            }
+            Ok(())
+        })
+    }
+
+    /// Name of the library unit, `None` for other declarations at library level.
+    fn name(&self) -> Option<&UnitName> {
+        Some(match &self.item {
+            Item::Package(item) => &item.name,
+            Item::PackageBody(item) => &item.name,
+            Item::Function(item) => &item.spec.name,
+            Item::FunctionDeclaration(item) => &item.spec.name,
+            Item::Generic(item) => item.name(),
+            Item::Instantiation(item) => &item.name,
+            _ => return None,
+        })
+    }
+}
+
+/// Unit receiving declarations at library level which are not library units.
+const LIBRARY_ITEMS: &str = "annabella_library";
+
+/// GNAT file naming: `Foo.Bar` is found in `foo-bar.ads` and `foo-bar.adb`.
+fn file_name(key: &str) -> String {
+    key.replace('.', "-")
+}
+
+pub(super) fn unit_key(name: &UnitName) -> String {
+    name.to_string()
+}
+
+/// Load the bodies of the library units loaded by a unit of the main source file.
+///
+/// Bodies are loaded after the unit and the specifications of all units they depend on,
+/// so the body of a unit can `with` a unit depending on its specification.
+fn load_pending_bodies(ctx: &mut Context) -> Result<()> {
+    while let Some((path, source)) = ctx.next_pending_body() {
+        generate_source(path, &source, ctx)?;
+    }
+    Ok(())
+}
+
+/// Load the specification of a library unit and all its parents, and the body unless
+/// `with_body` is `false`.
+fn load_spec(name: &UnitName, with_body: bool, ctx: &mut Context) -> Result<()> {
+    if let Some(parent) = name.parent() {
+        load_spec(&parent, true, ctx)?;
+    }
+
+    let key = unit_key(name);
+    if !ctx.mark_unit_loaded(key.clone()) {
+        return Ok(());
+    }
+
+    let [spec, body] = if let Some(unit) = runtime::find(&key) {
+        ctx.in_unit(&key, unit.file_name, UnitPart::Spec, |ctx| {
+            push_runtime_code(&unit, ctx)
+        });
+        [
+            Some((unit.spec_path(), ctx.target().expand(unit.spec))),
+            unit.body
+                .map(|body| (unit.body_path(), ctx.target().expand(body))),
+        ]
+    } else {
+        let file_name = file_name(&key);
+        let mut sources = [None, None];
+        for (source, ext) in sources.iter_mut().zip(["ads", "adb"]) {
+            *source = ctx
+                .search_path()
+                .iter()
+                .map(|dir| dir.join(format!("{file_name}.{ext}")))
+                .find(|path| path.is_file())
+                .map(|path| {
+                    let source = fs::read_to_string(&path).map_err(|err| {
+                        name.unrecoverable_error(format!("failed to read {path:?}: {err}"))
+                    })?;
+                    Ok((path, source))
+                })
+                .transpose()?;
+        }
+        sources
+    };
+    let Some((path, source)) = spec else {
+        return Err(name.unrecoverable_error("library unit not found"));
+    };
+
+    let units = generate_source(path, &source, ctx)?;
+    if let Some((path, source)) = body.filter(|_| with_body) {
+        // instances need the body of a generic unit
+        if units.iter().any(|unit| declares_generic(&unit.item)) {
+            generate_source(path, &source, ctx)?;
+        } else {
+            ctx.defer_body(path, source);
+        }
+    }
+    Ok(())
+}
+
+/// A generic unit, or a package declaring one, possibly in a nested package.
+fn declares_generic(item: &Item) -> bool {
+    match item {
+        Item::Generic(_) => true,
+        Item::Package(package) => package
+            .items
+            .iter()
+            .chain(package.private_items())
+            .any(declares_generic),
+        _ => false,
+    }
+}
+
+fn generate_source(path: PathBuf, source: &str, ctx: &mut Context) -> Result<Vec<CompilationUnit>> {
+    let input = TokenStream::parse(source, Some(path))?;
+    let units: Vec<CompilationUnit> = parser::parse(input, ctx.diagnostics())?;
+    for unit in &units {
+        unit.generate(ctx)?;
+    }
+    Ok(units)
+}
@@ -1,31 +1,92 @@
-use std::collections::{btree_map::Entry, BTreeMap};
+use std::{
+    cell::RefCell,
+    collections::{btree_map::Entry, BTreeMap},
+    rc::Rc,
+};
 
 use crate::{
-    tokenizer::{Ident, Spanned},
-    Result,
+    parser::{Function, GenericDeclaration, PackageBody, UnitName},
+    tokenizer::{Ident, Span, Spanned},
+    Error, Result,
 };
 
-use super::{CCode, ExprValue, Permission, SingleExprValue, Type};
+use super::{
+    c, pragma::Interface, representation::Representation, ExprValue, Frame, FrameSlot, Permission,
+    SingleExprValue, Subprogram, Type,
+};
 
 #[derive(Debug, Default)]
 pub struct Scope<'a> {
     parent: Option<&'a Scope<'a>>,
     values: BTreeMap<Box<str>, Value>,
+    uses: Vec<Rc<PackageValue>>,
+
+    /// Spans of the first declarations, by name.
+    declarations: BTreeMap<Box<str>, Span>,
+
+    /// Interfacing pragmas of the declarative part, by entity name.
+    interfaces: BTreeMap<Box<str>, Interface>,
+
+    /// Representation clauses of the declarative part, by type name.
+    representations: BTreeMap<Box<str>, Representation>,
 }
 
 impl Scope<'_> {
-    pub fn subscope(&mut self) -> Scope {
+    pub fn subscope(&mut self) -> Scope<'_> {
         Scope {
             parent: Some(self),
             values: Default::default(),
+            uses: Default::default(),
+            declarations: Default::default(),
+            interfaces: Default::default(),
+            representations: Default::default(),
         }
     }
 
+    /// The interfacing pragmas for the entity `ident` declared in this scope.
+    pub(super) fn interface(&self, ident: &Ident) -> Option<&Interface> {
+        self.interfaces.get(&ident.name)
+    }
+
+    pub(super) fn set_interface(&mut self, ident: &Ident, interface: Interface) {
+        self.interfaces.insert(ident.name.clone(), interface);
+    }
+
+    /// The representation clauses for the type `ident` declared in this scope.
+    pub(super) fn representation(&self, ident: &Ident) -> Option<&Representation> {
+        self.representations.get(&ident.name)
+    }
+
+    pub(super) fn set_representation(&mut self, ident: &Ident, representation: Representation) {
+        self.representations
+            .insert(ident.name.clone(), representation);
+    }
+
+    /// Whether `ident` is declared in this scope, not in an enclosing one.
+    pub fn declares(&self, ident: &Ident) -> bool {
+        self.values.contains_key(&ident.name)
+    }
+
     pub fn insert(&mut self, ident: &Ident, value: Value) -> Result<()> {
         match self.values.entry(ident.name.clone()) {
-            Entry::Occupied(entry) => entry.into_mut().insert(ident, value),
+            Entry::Occupied(mut entry) if matches!(entry.get(), Value::Poisoned) => {
+                entry.insert(value);
+                self.declarations.insert(ident.name.clone(), ident.span);
+                Ok(())
+            }
+            Entry::Occupied(entry) => {
+                entry.into_mut().insert(ident, value).map_err(|err| {
+                    match self.declarations.get(&ident.name) {
+                        Some(&span) if !span.is_call_site() => {
+                            err.with_label(span, "previous declaration here")
+                        }
+                        _ => err,
+                    }
+                })
+            }
             Entry::Vacant(entry) => {
                 entry.insert(value);
+                self.declarations.insert(ident.name.clone(), ident.span);
                 Ok(())
             }
         }
@@ -35,15 +96,101 @@ impl Scope<'_> {
         self.values.entry(ident.name.clone()).or_insert_with(value)
     }
 
-    pub fn get(&self, ident: &Ident) -> Result<&Value> {
+    /// Make the declarations of `package` use-visible in this scope.
+    pub fn use_package(&mut self, package: Rc<PackageValue>) {
+        if !self.uses.iter().any(|p| Rc::ptr_eq(p, &package)) {
+            self.uses.push(package);
+        }
+    }
+
+    pub fn into_values(self) -> BTreeMap<Box<str>, Value> {
+        self.values
+    }
+
+    pub fn extend(&mut self, values: BTreeMap<Box<str>, Value>) -> Result<()> {
+        for (name, value) in values {
+            let ident = Ident {
+                name,
+                span: Span::call_site(),
+            };
+            self.insert(&ident, value)?;
+        }
+        Ok(())
+    }
+
+    fn root(&self) -> &Self {
+        let mut this = self;
+        while let Some(parent) = this.parent {
+            this = parent;
+        }
+        this
+    }
+
+    /// Lookup a library level package by its full name.
+    pub fn get_library_package(&self, path: &[Ident]) -> Result<Rc<PackageValue>> {
+        let (first, rest) = path.split_first().expect("empty package path");
+        let Some(Value::Package(package)) = self.root().values.get(&first.name) else {
+            return Err(first.unrecoverable_error("library package not loaded"));
+        };
+        rest.iter()
+            .try_fold(package.clone(), |package, ident| package.get_package(ident))
+    }
+
+    /// Lookup a (possibly nested or child) package by its visible name.
+    pub fn get_package(&self, name: &UnitName) -> Result<Rc<PackageValue>> {
+        let mut parts = name.iter();
+        let first = parts.next().unwrap();
+        let Value::Package(package) = self.get(first)? else {
+            return Err(first.unrecoverable_error("not a package name"));
+        };
+        parts.try_fold(package.clone(), |package, ident| package.get_package(ident))
+    }
+
+    fn visible(&self) -> impl Iterator<Item = &BTreeMap<Box<str>, Value>> {
         let mut this = Some(self);
-        while let Some(scope) = this {
-            if let Some(value) = scope.values.get(&ident.name) {
-                return Ok(value);
-            }
+        std::iter::from_fn(move || {
+            let scope = this?;
             this = scope.parent;
+            Some(
+                std::iter::once(&scope.values)
+                    .chain(scope.uses.iter().map(|package| &package.values)),
+            )
+        })
+        .flatten()
+    }
+
+    pub fn get(&self, ident: &Ident) -> Result<&Value> {
+        match self.visible().find_map(|values| values.get(&ident.name)) {
+            Some(Value::Poisoned) => Err(poisoned_error(ident)),
+            Some(value) => Ok(value),
+            None => Err(ident.unrecoverable_error("identifier not in scope")),
+        }
+    }
+
+    /// Lookup an identifier used in an expression.
+    ///
+    /// Function overloads declared in outer scopes or use-visible packages stay visible,
+    /// any other declaration hides the outer declarations. `level` is the nesting level of
+    /// the subprogram containing the expression.
+    pub(super) fn get_expr_value(&self, ident: &Ident, level: usize) -> Result<ExprValue> {
+        let mut overloads = Vec::<&FunctionOverload>::new();
+        for value in self.visible().filter_map(|values| values.get(&ident.name)) {
+            match value {
+                Value::Function(value) => {
+                    for overload in &value.overloads {
+                        if !overloads.iter().any(|ol| ol.is_same(overload)) {
+                            overloads.push(overload);
+                        }
+                    }
+                }
+                _ if overloads.is_empty() => return value.expr_value(ident, level),
+                _ => break,
+            }
         }
-        Err(ident.unrecoverable_error("identifier not in scope"))
+        if overloads.is_empty() {
+            return Err(ident.unrecoverable_error("identifier not in scope"));
+        }
+        Ok(FunctionValue::overloads_expr_value(overloads.into_iter()))
     }
 }
 
@@ -53,6 +200,12 @@ pub enum Value {
     Type(TypeValue),
     Variable(VariableValue),
     Label(LabelValue),
+    Exception(ExceptionValue),
+    Package(Rc<PackageValue>),
+    Generic(Rc<GenericValue>),
+
+    /// A declaration left out after an error.
+    Poisoned,
 }
 
 impl Value {
@@ -63,13 +216,37 @@ impl Value {
         this.insert(ident, other)
     }
 
-    pub(super) fn expr_value(&self) -> ExprValue {
+    /// Clone a reference to a library unit (package or generic).
+    pub fn clone_unit(&self) -> Option<Self> {
         match self {
+            Self::Package(package) => Some(Self::Package(package.clone())),
+            Self::Generic(generic) => Some(Self::Generic(generic.clone())),
+            _ => None,
+        }
+    }
+
+    pub(super) fn expr_value(&self, ident: &Ident, level: usize) -> Result<ExprValue> {
+        Ok(match self {
             Self::Function(value) => value.expr_value(),
-            Self::Type(_) => unreachable!(),
-            Self::Variable(value) => value.expr_value(),
+            Self::Variable(value) => value.expr_value(level),
+            Self::Type(_) => return Err(ident.unrecoverable_error("type name not allowed here")),
+            Self::Package(_) | Self::Generic(_) => {
+                return Err(ident.unrecoverable_error("package name not allowed here"))
+            }
+            Self::Exception(_) => {
+                return Err(ident.unrecoverable_error("exception name not allowed here"))
+            }
             Self::Label(_) => unreachable!(),
-        }
+            Self::Poisoned => return Err(poisoned_error(ident)),
+        })
+    }
+}
+
+/// The error of a use of a declaration left out after an error, which is not reported.
+fn poisoned_error(ident: &Ident) -> Error {
+    Error {
+        cascade: true,
+        ..ident.unrecoverable_error("declaration left out after an error")
     }
 }
 
@@ -79,24 +256,51 @@ pub struct FunctionValue {
 }
 
 impl FunctionValue {
-    pub fn new(name: CCode, ty: Type) -> Self {
+    pub fn new(name: c::Ident, ty: Type) -> Self {
         Self {
-            overloads: vec![FunctionOverload { name, ty }],
+            overloads: vec![FunctionOverload {
+                name,
+                ty,
+                declaration: None,
+            }],
+        }
+    }
+
+    /// Record the source location of the declaration of the subprogram.
+    pub fn with_declaration(mut self, span: Span) -> Self {
+        for overload in &mut self.overloads {
+            overload.declaration = Some(span);
         }
+        self
     }
 
     fn insert(&mut self, ident: &Ident, value: Self) -> Result<()> {
         let _ = ident;
-        self.overloads.extend(value.overloads);
+        for overload in value.overloads {
+            // the body of a subprogram completes its declaration
+            if !self.overloads.iter().any(|ol| ol.is_same(&overload)) {
+                self.overloads.push(overload);
+            }
+        }
         Ok(())
     }
 
     pub(super) fn expr_value(&self) -> ExprValue {
-        ExprValue::new(self.overloads.iter().map(|ol| SingleExprValue {
+        Self::overloads_expr_value(self.overloads.iter())
+    }
+
+    fn overloads_expr_value<'a>(
+        overloads: impl Iterator<Item = &'a FunctionOverload>,
+    ) -> ExprValue {
+        ExprValue::new(overloads.map(|ol| SingleExprValue {
             ty: ol.ty.clone(),
             perm: Permission::Read,
-            code: ol.name.clone(),
+            code: ol.name.clone().into(),
             value: None,
+            subprogram: Some(Subprogram {
+                declaration: ol.declaration,
+                ty: ol.ty.clone(),
+            }),
         }))
         .unwrap()
     }
@@ -104,8 +308,15 @@ impl FunctionValue {
 
 #[derive(Debug)]
 struct FunctionOverload {
-    pub name: CCode,
+    pub name: c::Ident,
     pub ty: Type,
+    pub declaration: Option<Span>,
+}
+
+impl FunctionOverload {
+    fn is_same(&self, other: &Self) -> bool {
+        self.name == other.name
+    }
 }
 
 #[derive(Debug)]
@@ -115,18 +326,30 @@ pub struct TypeValue {
 
 #[derive(Debug)]
 pub struct VariableValue {
-    pub name: CCode,
+    pub name: c::Expr,
     pub ty: Type,
     pub perm: Permission,
+
+    /// Slot in the frame array for objects of subprograms with nested subprograms.
+    pub slot: Option<FrameSlot>,
 }
 
 impl VariableValue {
-    pub(super) fn expr_value(&self) -> ExprValue {
+    /// Objects of enclosing subprograms are accessed through the static link.
+    pub(super) fn expr_value(&self, level: usize) -> ExprValue {
+        let code = match self.slot {
+            Some(slot) if slot.level != level => Frame::link(level, slot.level)
+                .index(c::Expr::int(slot.index as i128))
+                .cast(self.ty.c_type().pointer())
+                .deref(),
+            _ => self.name.clone(),
+        };
         SingleExprValue {
             ty: self.ty.clone(),
             perm: self.perm,
-            code: self.name.clone(),
+            code,
             value: None,
+            subprogram: None,
         }
         .into()
     }
@@ -134,5 +357,68 @@ impl VariableValue {
 
 #[derive(Debug)]
 pub struct LabelValue {
-    pub name: CCode,
+    pub name: c::Ident,
+}
+
+/// An exception, identified at run time by its expanded name like `Ada.Text_IO.End_Error`.
+#[derive(Debug)]
+pub struct ExceptionValue {
+    pub name: Box<str>,
+}
+
+#[derive(Debug)]
+pub struct PackageValue {
+    pub name: Ident,
+    pub values: BTreeMap<Box<str>, Value>,
+
+    /// Child units, either packages or generics.
+    pub children: RefCell<BTreeMap<Box<str>, Value>>,
+}
+
+impl PackageValue {
+    pub fn get(&self, ident: &Ident) -> Result<&Value> {
+        match self.values.get(&ident.name) {
+            Some(Value::Poisoned) => Err(poisoned_error(ident)),
+            Some(value) => Ok(value),
+            None => {
+                Err(ident.unrecoverable_error(format!("not declared in package `{}`", self.name)))
+            }
+        }
+    }
+
+    /// Lookup a nested or child package or generic.
+    pub fn find_unit(&self, ident: &Ident) -> Option<Value> {
+        if let Some(child) = self.children.borrow().get(&ident.name) {
+            return child.clone_unit();
+        }
+        self.values.get(&ident.name)?.clone_unit()
+    }
+
+    pub fn find_package(&self, ident: &Ident) -> Option<Rc<PackageValue>> {
+        match self.find_unit(ident)? {
+            Value::Package(package) => Some(package),
+            _ => None,
+        }
+    }
+
+    pub fn get_package(&self, ident: &Ident) -> Result<Rc<PackageValue>> {
+        self.find_package(ident).ok_or_else(|| {
+            ident.unrecoverable_error(format!("no package `{ident}` in package `{}`", self.name))
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct GenericValue {
+    pub declaration: GenericDeclaration,
+    pub body: RefCell<Option<GenericBody>>,
+
+    /// Full name of the library package the generic is declared in.
+    pub env: Vec<Ident>,
+}
+
+#[derive(Debug, Clone)]
+pub enum GenericBody {
+    Package(PackageBody),
+    Subprogram(Function),
 }
@@ -1,11 +1,202 @@
-use std::borrow::Cow;
+use std::{borrow::Cow, cell::RefCell, rc::Rc};
 
-use crate::tokenizer::Span;
+use crate::{parser::is_reserved_word, tokenizer::Span};
 
 pub type Result<T> = std::result::Result<T, Error>;
 
 pub struct Error {
     pub span: Span,
     pub msg: Cow<'static, str>,
+    pub kind: ErrorKind,
     pub recoverable: bool,
+
+    /// Other source locations with their message, like a previous declaration.
+    pub labels: Vec<(Span, Cow<'static, str>)>,
+
+    /// Explanations without a source location.
+    pub notes: Vec<Cow<'static, str>>,
+
+    /// The kind of the statement or declaration which was left out, like `Stmt::Assign`.
+    pub node: Option<&'static str>,
+
+    /// Caused by an earlier error, like the use of a declaration left out, and not
+    /// reported.
+    pub cascade: bool,
+
+    /// A call with a wrong number of arguments, the weakest explanation of why an
+    /// overload does not match.
+    pub arity: bool,
+}
+
+/// What an error is about, to tell the missing features apart from the invalid programs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// The source is not valid Ada syntax.
+    Syntax,
+
+    /// The program is rejected by name resolution or type checking.
+    Type,
+
+    /// The Ada feature is not yet implemented.
+    Unimplemented,
+
+    /// The units cannot be bound into a program, like an elaboration cycle or a missing
+    /// main subprogram.
+    Bind,
+}
+
+impl ErrorKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Syntax => "syntax",
+            Self::Type => "type",
+            Self::Unimplemented => "unimplemented",
+            Self::Bind => "bind",
+        }
+    }
+}
+
+impl Error {
+    pub fn with_label(mut self, span: Span, msg: impl Into<Cow<'static, str>>) -> Self {
+        self.labels.push((span, msg.into()));
+        self
+    }
+
+    pub fn with_note(mut self, msg: impl Into<Cow<'static, str>>) -> Self {
+        self.notes.push(msg.into());
+        self
+    }
+
+    /// Record the kind of the innermost statement or declaration left out by the error.
+    pub(crate) fn in_node(mut self, node: &'static str) -> Self {
+        self.node.get_or_insert(node);
+        self
+    }
+
+    /// Mark an error of the tokenizer or the parser as a syntax error.
+    pub(crate) fn syntax(mut self) -> Self {
+        if self.kind == ErrorKind::Type {
+            self.kind = ErrorKind::Syntax;
+        }
+        self
+    }
+
+    /// Mark an error of the binder as a bind error.
+    pub(crate) fn bind(mut self) -> Self {
+        self.kind = ErrorKind::Bind;
+        self
+    }
+
+    /// Mark the error of an overload called with a wrong number of arguments.
+    pub(crate) fn arity(mut self) -> Self {
+        self.arity = true;
+        self
+    }
+
+    /// How well the error explains why an overload does not match, the error of the
+    /// best ranked overload is reported when none matches. A cascade error is only
+    /// preferred to a wrong number of arguments, which the overload using the left out
+    /// declaration would have.
+    pub(crate) fn rank(&self) -> u8 {
+        match self.kind {
+            _ if self.arity => 0,
+            _ if self.cascade => 1,
+            ErrorKind::Syntax | ErrorKind::Type | ErrorKind::Bind => 2,
+            ErrorKind::Unimplemented => 3,
+        }
+    }
+
+    /// A code of the kind and the message, the same for messages which only differ in the
+    /// quoted names and the numbers, like `U95aafc` for ``convention `x` not yet implemented``.
+    /// The quoted reserved words and delimiters are kept, so that ``expected `then` `` and
+    /// ``expected `;` `` have distinct codes.
+    ///
+    /// The code is a FNV-1a hash, so it does not change between runs and platforms, but it
+    /// changes with the wording of the message.
+    pub fn code(&self) -> String {
+        let mut hash: u32 = 0x811c_9dc5;
+        let mut add = |byte: u8| {
+            hash ^= byte as u32;
+            hash = hash.wrapping_mul(0x0100_0193);
+        };
+        self.kind.name().bytes().for_each(&mut add);
+        let mut quote = None;
+        let mut quoted = String::new();
+        let mut digits = false;
+        for c in self.msg.chars() {
+            match quote {
+                Some(end) if c == end => {
+                    quote = None;
+                    let lower = quoted.to_lowercase();
+                    if is_reserved_word(&lower) || !quoted.chars().any(char::is_alphanumeric) {
+                        lower.bytes().for_each(&mut add);
+                    }
+                    quoted.clear();
+                }
+                Some(_) => {
+                    quoted.push(c);
+                    continue;
+                }
+                None if c == '`' || c == '"' => quote = Some(c),
+                None if c.is_ascii_digit() => {
+                    if !digits {
+                        add(b'0');
+                    }
+                    digits = true;
+                    continue;
+                }
+                None => {}
+            }
+            digits = false;
+            let mut buf = [0; 4];
+            c.encode_utf8(&mut buf).bytes().for_each(&mut add);
+        }
+        let prefix = match self.kind {
+            ErrorKind::Syntax => 'S',
+            ErrorKind::Type => 'T',
+            ErrorKind::Unimplemented => 'U',
+            ErrorKind::Bind => 'B',
+        };
+        format!("{prefix}{:06x}", hash >> 8)
+    }
+}
+
+/// Errors reported while going on with the rest of the input, shared by the parser and
+/// the code generator so that a run reports all of them.
+#[derive(Clone, Default)]
+pub struct Diagnostics(Rc<RefCell<Vec<Error>>>);
+
+impl Diagnostics {
+    pub fn report(&self, err: Error) {
+        if !err.cascade {
+            self.0.borrow_mut().push(err);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.borrow().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.borrow().is_empty()
+    }
+
+    /// Drop the errors reported after the first `len`, when a syntax is tried again.
+    pub(crate) fn truncate(&self, len: usize) {
+        self.0.borrow_mut().truncate(len);
+    }
+
+    /// Keep the first `len` errors and the later ones for which `f` is true.
+    pub(crate) fn retain_from(&self, len: usize, mut f: impl FnMut(&Error) -> bool) {
+        let mut index = 0;
+        self.0.borrow_mut().retain(|err| {
+            index += 1;
+            index <= len || f(err)
+        });
+    }
+
+    /// Remove the reported errors, in the order of their report.
+    pub fn take(&self) -> Vec<Error> {
+        self.0.take()
+    }
 }
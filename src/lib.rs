@@ -4,6 +4,7 @@ mod macros;
 pub mod codegen;
 mod error;
 pub mod parser;
+mod runtime;
 pub mod tokenizer;
 
-pub use error::{Error, Result};
+pub use error::{Diagnostics, Error, ErrorKind, Result};
@@ -1,37 +1,277 @@
-use std::{env, fs, path::PathBuf};
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
 
-use annabella::{codegen, parser, tokenizer::TokenStream, Error};
-use anyhow::{Context, Result};
+use annabella::{
+    codegen::{self, AssertionPolicy, Language, Options, Output, Target},
+    parser,
+    tokenizer::{Span, TokenStream},
+    Diagnostics, Error,
+};
+use anyhow::{anyhow, Context, Result};
+
+mod survey;
 
 fn main() -> Result<()> {
-    let path: PathBuf = env::args_os().nth(1).context("ada source path")?.into();
+    let mut args = env::args_os().skip(1).peekable();
+    if args.next_if(|arg| arg == "survey").is_some() {
+        return survey::main(args);
+    }
+    let mut path = None;
+    let mut target = Target::default();
+    let mut language = Language::default();
+    let mut output_dir = None;
+    let mut main = None;
+    let mut assertion_policy = AssertionPolicy::default();
+    let mut error_format = ErrorFormat::Human;
+    while let Some(arg) = args.next() {
+        if arg == "--target" {
+            let name = args.next().context("target language")?;
+            language = match name.to_str() {
+                Some("c") => Language::C,
+                Some("c++") => Language::Cxx,
+                _ => {
+                    return Err(anyhow!(
+                        "unknown target language {name:?}, expected c or c++"
+                    ))
+                }
+            };
+        } else if arg == "--target-info" {
+            let file: PathBuf = args.next().context("target information file")?.into();
+            let source = fs::read_to_string(&file)
+                .with_context(|| format!("read target information: {file:?}"))?;
+            target = Target::parse(&source)
+                .map_err(|err| anyhow!("invalid target information {file:?}: {err}"))?;
+        } else if arg == "--main" {
+            let name = args.next().context("main subprogram name")?;
+            main = Some(
+                name.into_string()
+                    .map_err(|name| anyhow!("invalid main subprogram name {name:?}"))?,
+            );
+        } else if arg == "--assertion-policy" {
+            let name = args.next().context("assertion policy")?;
+            assertion_policy = match name.to_str() {
+                Some("check") => AssertionPolicy::Check,
+                Some("ignore") => AssertionPolicy::Ignore,
+                _ => {
+                    return Err(anyhow!(
+                        "unknown assertion policy {name:?}, expected check or ignore"
+                    ))
+                }
+            };
+        } else if let Some(name) = arg
+            .to_str()
+            .and_then(|arg| arg.strip_prefix("--error-format="))
+        {
+            error_format = match name {
+                "human" => ErrorFormat::Human,
+                "json" => ErrorFormat::Json,
+                _ => {
+                    return Err(anyhow!(
+                        "unknown error format {name:?}, expected human or json"
+                    ))
+                }
+            };
+        } else if arg == "--output-dir" {
+            output_dir = Some(PathBuf::from(args.next().context("output directory")?));
+        } else {
+            path = Some(PathBuf::from(arg));
+        }
+    }
+    let path = path.context("ada source path")?;
 
     let source = fs::read_to_string(&path).with_context(|| format!("read source: {path:?}"))?;
 
-    run(source, path).map_err(show_error)
+    let options = Options {
+        search_path: path.parent().map(Path::to_path_buf).into_iter().collect(),
+        target,
+        language,
+        main,
+        no_main: false,
+        assertion_policy,
+    };
+    let diagnostics = Diagnostics::default();
+    let result = run(source, path, options, &diagnostics);
+    let mut errors = diagnostics.take();
+    if let Ok(output) = &result {
+        for warning in output.warnings() {
+            error_format.show("warning", warning);
+        }
+    }
+    let output = match result {
+        Ok(output) if errors.is_empty() => output,
+        result => {
+            errors.extend(result.err().filter(|err| !err.cascade));
+            return Err(show_errors(&errors, error_format));
+        }
+    };
+
+    // a header and a source file per unit, else all units in a single file on stdout
+    if let Some(dir) = output_dir {
+        fs::create_dir_all(&dir).with_context(|| format!("create directory: {dir:?}"))?;
+        for (name, code) in output.files() {
+            let file = dir.join(name);
+            fs::write(&file, code).with_context(|| format!("write output: {file:?}"))?;
+        }
+    } else {
+        println!("{output}");
+    }
+    Ok(())
 }
 
-fn run(source: String, path: PathBuf) -> Result<(), Error> {
+fn run(
+    source: String,
+    path: PathBuf,
+    options: Options,
+    diagnostics: &Diagnostics,
+) -> Result<Output, Error> {
     let input = TokenStream::parse(&source, Some(path))?;
 
-    let items = parser::parse(input)?;
+    let units = parser::parse(input, diagnostics)?;
 
-    let code = codegen::run(items)?;
-    println!("{code}");
+    codegen::run(units, options, diagnostics)
+}
 
-    Ok(())
+/// Print all errors, the failure tells their count.
+fn show_errors(errors: &[Error], error_format: ErrorFormat) -> anyhow::Error {
+    for err in errors {
+        error_format.show("error", err);
+    }
+    match errors.len() {
+        1 => anyhow!("transpiler failed with 1 error"),
+        count => anyhow!("transpiler failed with {count} errors"),
+    }
+}
+
+#[derive(Clone, Copy)]
+enum ErrorFormat {
+    /// The message with the marked source lines.
+    Human,
+
+    /// A JSON object per line, to collect the diagnostics of many runs.
+    Json,
+}
+
+impl ErrorFormat {
+    fn show(self, severity: &str, diagnostic: &Error) {
+        match self {
+            Self::Human => show_diagnostic(severity, diagnostic),
+            Self::Json => eprintln!("{}", diagnostic_json(severity, diagnostic)),
+        }
+    }
 }
 
-fn show_error(err: Error) -> anyhow::Error {
+/// The diagnostic as a JSON object, its labels are objects with a message and a location
+/// like the diagnostic itself.
+fn diagnostic_json(severity: &str, diagnostic: &Error) -> String {
+    let labels: Vec<_> = diagnostic
+        .labels
+        .iter()
+        .map(|(span, msg)| {
+            format!(
+                "{{\"message\":{},{}}}",
+                json_string(msg),
+                location_json(*span)
+            )
+        })
+        .collect();
+    let notes: Vec<_> = diagnostic
+        .notes
+        .iter()
+        .map(|note| json_string(note))
+        .collect();
+    let node = diagnostic.node.map_or("null".into(), json_string);
+    format!(
+        "{{\"severity\":{},\"category\":{},\"code\":{},\"message\":{},{},\"node\":{node},\"labels\":[{}],\"notes\":[{}]}}",
+        json_string(severity),
+        json_string(diagnostic.kind.name()),
+        json_string(&diagnostic.code()),
+        json_string(&diagnostic.msg),
+        location_json(diagnostic.span),
+        labels.join(","),
+        notes.join(","),
+    )
+}
+
+/// The file and the 1-indexed lines and 0-indexed columns of the span, `null` if unknown.
+fn location_json(span: Span) -> String {
+    let file = span
+        .filepath()
+        .map_or("null".into(), |path| json_string(&path.to_string_lossy()));
+    let (start, end) = span_json(span);
+    format!("\"file\":{file},\"start\":{start},\"end\":{end}")
+}
+
+#[cfg(feature = "span-locations")]
+fn span_json(span: Span) -> (String, String) {
+    if span.is_call_site() {
+        return ("null".into(), "null".into());
+    }
+    let json = |pos: annabella::tokenizer::LineColumn| {
+        format!("{{\"line\":{},\"column\":{}}}", pos.line, pos.column)
+    };
+    (json(span.start_line_column()), json(span.end_line_column()))
+}
+
+#[cfg(not(feature = "span-locations"))]
+fn span_json(_: Span) -> (String, String) {
+    ("null".into(), "null".into())
+}
+
+fn json_string(s: &str) -> String {
+    let mut json = String::from('"');
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            c if c < ' ' => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+/// Print the message with the marked source lines of its span, followed by the labelled
+/// spans and the notes.
+fn show_diagnostic(severity: &str, diagnostic: &Error) {
     let Error {
         span,
         msg,
+        kind: _,
         recoverable: _,
-    } = err;
-    eprintln!(
-        "{msg} in {:?}:",
-        span.filepath().as_deref().unwrap_or("<call_site>".as_ref())
-    );
+        labels,
+        notes,
+        node: _,
+        cascade: _,
+        arity: _,
+    } = diagnostic;
+    // errors about the whole program, like a missing main subprogram, have no location
+    if span.is_call_site() {
+        eprintln!("{severity}: {msg}");
+    } else {
+        eprintln!("{severity}: {msg} in {:?}:", file_name(*span));
+        show_lines(*span, msg);
+    }
+    for (label_span, label) in labels {
+        if label_span.filepath() != span.filepath() {
+            eprintln!("  {label} in {:?}:", file_name(*label_span));
+        }
+        show_lines(*label_span, label);
+    }
+    for note in notes {
+        eprintln!("      = note: {note}");
+    }
+}
+
+fn file_name(span: Span) -> PathBuf {
+    span.filepath().unwrap_or_else(|| "<call_site>".into())
+}
+
+/// Print the source lines of the span, marked with `^---` and the message.
+fn show_lines(span: Span, msg: &str) {
     for (line, source, start, end) in span.lines() {
         eprintln!("{line:4}: {source}");
         let mut marker = Vec::new();
@@ -46,5 +286,4 @@ fn show_error(err: Error) -> anyhow::Error {
             eprintln!("      {} {msg}", std::str::from_utf8(&marker).unwrap());
         }
     }
-    anyhow::anyhow!("transpiler failed")
 }
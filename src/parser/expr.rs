@@ -3,7 +3,9 @@ use crate::{
     Result, Token,
 };
 
-use super::{Parenthesized, ParenthesizedOne, Parse, ParseStream, Punctuated, Range};
+use super::{
+    Item, LoopParameter, Parenthesized, ParenthesizedOne, Parse, ParseStream, Punctuated, Range,
+};
 
 parse!({
     enum Expr {
@@ -14,12 +16,30 @@ parse!({
         Unary(ExprUnary),
         Binary(ExprBinary),
         ShortCircuit(ExprShortCircuit),
+        Paren(ParenthesizedOne<Box<Expr>>),
+        If(ExprIf),
+        Case(ExprCase),
+        Quantified(ExprQuantified),
+        Declare(ExprDeclare),
     }
 });
 
 impl Parse for Expr {
+    /// Conditional, quantified and declare expressions need parentheses, but the
+    /// parentheses of a call with a single argument are enough, so they are accepted
+    /// wherever an expression is complete.
     fn parse(input: ParseStream) -> Result<Self> {
-        Self::parse_expression(input)
+        Ok(if let Some(expr) = input.try_parse()? {
+            Self::If(expr)
+        } else if let Some(expr) = input.try_parse()? {
+            Self::Case(expr)
+        } else if let Some(expr) = input.try_parse()? {
+            Self::Quantified(expr)
+        } else if let Some(expr) = input.try_parse()? {
+            Self::Declare(expr)
+        } else {
+            Self::parse_expression(input)?
+        })
     }
 }
 
@@ -102,7 +122,7 @@ impl Expr {
             })? {
                 let _: Option<Token![not]> = not;
                 let _: Token![in] = in_;
-                return Err(in_.unrecoverable_error("`in` not yet implemented"));
+                return Err(in_.unimplemented_error("`in` not yet implemented"));
             }
         }
 
@@ -226,6 +246,16 @@ impl Expr {
     fn parse_primary(input: ParseStream) -> Result<Self> {
         Ok(if let Some(lit) = input.try_parse()? {
             Self::Lit(lit)
+        } else if let Some(expr) = input.try_call(|input| {
+            ParenthesizedOne::try_parse_with(input, |input| {
+                let expr = input.parse()?;
+                if !input.is_empty() {
+                    return Err(input.recoverable_error("not a parenthesized expression"));
+                }
+                Ok(Box::new(expr))
+            })
+        })? {
+            Self::Paren(expr)
         } else if let Some(aggregate) = input.try_parse()? {
             Self::Aggregate(aggregate)
         } else if let Some(name) = input.try_parse()? {
@@ -310,6 +340,16 @@ impl LitChar {
         assert!(chars.next().is_none());
         c
     }
+
+    /// The literal as the name of an enumeration literal, like `'A'`.
+    ///
+    /// Unlike identifiers, character literals are case sensitive.
+    pub fn ident(&self) -> Ident {
+        Ident {
+            name: self.lit.str.clone(),
+            span: self.lit.span,
+        }
+    }
 }
 
 parse!({
@@ -325,6 +365,16 @@ impl LitNumber {
     {
         T::parse(&self.lit.str)
     }
+
+    /// Is it a real literal, with a decimal point?
+    pub fn is_real(&self) -> bool {
+        self.lit.str.contains('.')
+    }
+
+    /// Is it a based literal, like `16#FF#`?
+    pub fn is_based(&self) -> bool {
+        self.lit.str.contains('#')
+    }
 }
 
 pub trait ParseNumber: Sized {
@@ -357,11 +407,21 @@ impl ParseNumber for i64 {
     }
 }
 
+impl ParseNumber for f64 {
+    fn parse(s: &str) -> Self {
+        s.replace('_', "")
+            .parse()
+            .unwrap_or_else(|_| unreachable!("invalid real number: {s}"))
+    }
+}
+
 parse!({
     enum Name {
         Base(BaseName),
         Select(SelectedComponent),
+        Slice(Slice),
         FunctionCall(FunctionCall),
+        Attribute(AttributeReference),
     }
 });
 
@@ -369,17 +429,39 @@ impl Parse for Name {
     fn parse(input: ParseStream) -> Result<Self> {
         let mut name = Self::Base(input.parse()?);
         Ok(loop {
-            name = if let Some(dot) = input.try_parse()? {
+            // the dot may also be the start of a range `..`
+            name = if let Some((dot, selector)) = input.try_call(|input| {
+                let dot = input.parse()?;
+                let selector = input.parse()?;
+                Ok((dot, selector))
+            })? {
                 Self::Select(SelectedComponent {
                     prefix: name.into(),
                     dot,
-                    name: input.parse()?,
+                    name: selector,
+                })
+            } else if let Some(range) =
+                input.try_call(|input| ParenthesizedOne::try_parse_with(input, Range::parse))?
+            {
+                Self::Slice(Slice {
+                    prefix: name.into(),
+                    range: range.into(),
                 })
             } else if let Some(args) = input.try_parse()? {
                 Self::FunctionCall(FunctionCall {
                     name: name.into(),
                     args,
                 })
+            } else if let Some((tick, designator)) = input.try_call(|input| {
+                let tick = input.parse()?;
+                let designator = AttributeReference::parse_designator(input)?;
+                Ok((tick, designator))
+            })? {
+                Self::Attribute(AttributeReference {
+                    prefix: name.into(),
+                    tick,
+                    designator,
+                })
             } else {
                 break name;
             };
@@ -425,6 +507,34 @@ parse!({
     }
 });
 
+parse!({
+    struct Slice {
+        prefix: Box<Name>,
+        range: Box<ParenthesizedOne<Range>>,
+    }
+});
+
+parse!({
+    struct AttributeReference {
+        prefix: Box<Name>,
+        tick: Token![tick],
+        designator: Ident,
+    }
+});
+
+impl AttributeReference {
+    /// Attribute designators may be reserved words (e.g. `'Range` or `'Access`).
+    fn parse_designator(input: ParseStream) -> Result<Ident> {
+        input.step(|cursor| {
+            if let Some((ident, rest)) = cursor.ident() {
+                Ok((ident.clone(), rest))
+            } else {
+                Err(cursor.recoverable_error("expected attribute designator"))
+            }
+        })
+    }
+}
+
 parse!({
     struct QualifiedExpr {
         mark: Name,
@@ -660,6 +770,173 @@ parse!({
     }
 });
 
+parse!({
+    struct ExprIf {
+        if_: Token![if],
+        cond: Box<Expr>,
+        then: Token![then],
+        expr: Box<Expr>,
+        elsifs: Vec<ExprElsIf>,
+        else_: Option<(Token![else], Box<Expr>)>,
+    }
+});
+
+impl Parse for ExprIf {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let if_ = input.parse()?;
+        input.unrecoverable(|input| {
+            let cond = input.parse()?;
+            let then = input.parse()?;
+            let expr = input.parse()?;
+            let mut elsifs = Vec::new();
+            while let Some(elsif) = input.try_parse()? {
+                elsifs.push(ExprElsIf {
+                    elsif,
+                    cond: input.parse()?,
+                    then: input.parse()?,
+                    expr: input.parse()?,
+                });
+            }
+            let else_ = input.try_call(|input| {
+                let else_ = input.parse()?;
+                input.unrecoverable(|input| Ok((else_, input.parse()?)))
+            })?;
+            Ok(Self {
+                if_,
+                cond,
+                then,
+                expr,
+                elsifs,
+                else_,
+            })
+        })
+    }
+}
+
+parse!({
+    struct ExprElsIf {
+        elsif: Token![elsif],
+        cond: Expr,
+        then: Token![then],
+        expr: Expr,
+    }
+});
+
+parse!({
+    struct ExprCase {
+        case: Token![case],
+        expr: Box<Expr>,
+        is_: Token![is],
+        alternatives: Punctuated<ExprCaseAlternative>,
+    }
+});
+
+impl Parse for ExprCase {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let case = input.parse()?;
+        input.unrecoverable(|input| {
+            Ok(Self {
+                case,
+                expr: input.parse()?,
+                is_: input.parse()?,
+                alternatives: input.call(Punctuated::parse_while)?,
+            })
+        })
+    }
+}
+
+parse!({
+    struct ExprCaseAlternative {
+        when: Token![when],
+        choices: Punctuated<DiscreteChoice, Token![|]>,
+        arrow: Token![=>],
+        expr: Expr,
+    }
+});
+
+impl Parse for ExprCaseAlternative {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let when = input.parse()?;
+        input.unrecoverable(|input| {
+            Ok(Self {
+                when,
+                choices: input.call(Punctuated::parse_while)?,
+                arrow: input.parse()?,
+                expr: input.parse()?,
+            })
+        })
+    }
+}
+
+parse!({
+    struct ExprQuantified {
+        for_: Token![for],
+        quantifier: Quantifier,
+        param: Box<LoopParameter>,
+        arrow: Token![=>],
+        predicate: Box<Expr>,
+    }
+});
+
+impl Parse for ExprQuantified {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let for_ = input.parse()?;
+        // without a quantifier it is the `for` of a loop or a representation clause
+        let quantifier = input.parse()?;
+        input.unrecoverable(|input| {
+            Ok(Self {
+                for_,
+                quantifier,
+                param: input.parse()?,
+                arrow: input.parse()?,
+                predicate: input.parse()?,
+            })
+        })
+    }
+}
+
+parse!({
+    enum Quantifier {
+        All(Token![all]),
+        Some(Token![some]),
+    }
+});
+
+impl Parse for Quantifier {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(if let Some(all) = input.try_parse()? {
+            Self::All(all)
+        } else if let Some(some) = input.try_parse()? {
+            Self::Some(some)
+        } else {
+            return Err(input.recoverable_error("expected quantifier"));
+        })
+    }
+}
+
+parse!({
+    struct ExprDeclare {
+        declare: Token![declare],
+        items: Vec<Item>,
+        begin: Token![begin],
+        expr: Box<Expr>,
+    }
+});
+
+impl Parse for ExprDeclare {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let declare = input.parse()?;
+        input.unrecoverable(|input| {
+            Ok(Self {
+                declare,
+                items: input.parse_until_peeked(Token![begin])?,
+                begin: input.parse()?,
+                expr: input.parse()?,
+            })
+        })
+    }
+}
+
 parse!({
     enum DiscreteChoice {
         Others(Token![others]),
@@ -3,48 +3,148 @@ use crate::{
     Result, Token,
 };
 
-use super::{DiscreteChoice, Expr, Parenthesized, Parse, ParseStream, Punctuated, Stmt};
+use super::{
+    stmt::parse_handled_stmts, DiscreteChoice, ExceptionHandlers, Expr, ExprLit,
+    GenericDeclaration, GenericInstantiation, GenericUnit, InstantiationKind, LitChar, Name,
+    PackageBody, PackageItem, Parenthesized, Parse, ParseStream, Pragma, Punctuated,
+    RepresentationClause, Stmt, UnitName, UseClause,
+};
 
 parse!({
     enum Item {
         Function(Function),
+        FunctionDeclaration(FunctionDeclaration),
         Type(TypeItem),
         Subtype(SubtypeItem),
         Variable(Variable),
+        Number(NumberDeclaration),
+        Exception(ExceptionDeclaration),
+        Package(PackageItem),
+        PackageBody(PackageBody),
+        Instantiation(GenericInstantiation),
+        Generic(GenericDeclaration),
+        Use(UseClause),
+        Pragma(Pragma),
+        Representation(RepresentationClause),
+        Skipped(SkippedItem),
     }
 });
 
 impl Parse for Item {
     fn parse(input: ParseStream) -> Result<Self> {
         Ok(if let Some(item) = input.try_parse()? {
+            Self::Pragma(item)
+        } else if let Some(item) = input.try_parse()? {
+            Self::Instantiation(item)
+        } else if let Some(item) = input.try_parse()? {
+            Self::FunctionDeclaration(item)
+        } else if let Some(item) = input.try_parse()? {
             Self::Function(item)
         } else if let Some(item) = input.try_parse()? {
             Self::Type(item)
         } else if let Some(item) = input.try_parse()? {
             Self::Subtype(item)
+        } else if let Some(item) = input.try_parse()? {
+            Self::PackageBody(item)
+        } else if let Some(item) = input.try_parse()? {
+            Self::Package(item)
+        } else if let Some(item) = input.try_parse()? {
+            Self::Generic(item)
+        } else if let Some(item) = input.try_parse()? {
+            Self::Use(item)
+        } else if let Some(item) = input.try_parse()? {
+            Self::Representation(item)
+        } else if let Some(item) = input.try_parse()? {
+            Self::Number(item)
+        } else if let Some(item) = input.try_parse()? {
+            Self::Exception(item)
         } else if let Some(item) = input.try_parse()? {
             Self::Variable(item)
         } else {
             return Err(input.unrecoverable_error("expected item"));
         })
     }
+
+    fn skipped(input: ParseStream) -> Option<Self> {
+        input.parse().ok().map(Self::Skipped)
+    }
 }
+
+impl Item {
+    /// The name declared by the item, unless it is a subprogram which may be overloaded.
+    pub fn defining_name(&self) -> Option<&Ident> {
+        fn unit_name(name: &UnitName) -> Option<&Ident> {
+            (name.iter().len() == 1).then(|| name.last())
+        }
+        match self {
+            Self::Type(TypeItem::Full(item)) => Some(&item.name),
+            Self::Subtype(item) => Some(&item.name),
+            Self::Variable(item) => Some(&item.name),
+            Self::Number(item) => Some(&item.name),
+            Self::Exception(item) => Some(&item.name),
+            Self::Skipped(item) => Some(&item.name),
+            Self::Package(item) => unit_name(&item.name),
+            Self::Instantiation(item) => match item.kind {
+                InstantiationKind::Package(_) => unit_name(&item.name),
+                InstantiationKind::Subprogram(_) => None,
+            },
+            Self::Generic(item) => match &item.unit {
+                GenericUnit::Package(package) => unit_name(&package.name),
+                GenericUnit::Subprogram(_) => None,
+            },
+            Self::Function(_)
+            | Self::FunctionDeclaration(_)
+            | Self::PackageBody(_)
+            | Self::Use(_)
+            | Self::Pragma(_)
+            | Self::Representation(_) => None,
+        }
+    }
+}
+
+// The name of a declaration skipped after a syntax error, like `X` of `X : Integer := ;`.
 parse!({
-    struct Function {
-        kind: FunctionKind,
+    struct SkippedItem {
         name: Ident,
+    }
+});
+
+impl Parse for SkippedItem {
+    fn parse(input: ParseStream) -> Result<Self> {
+        // the name follows the keyword of a type, subtype or package, or precedes the
+        // colon of an object, number or exception
+        if input.peek(Token![type]) {
+            input.parse::<Token![type]>()?;
+        } else if input.peek(Token![subtype]) {
+            input.parse::<Token![subtype]>()?;
+        } else if input.peek(Token![package]) {
+            input.parse::<Token![package]>()?;
+        } else {
+            let name = input.parse()?;
+            input.parse::<Token![:]>()?;
+            return Ok(Self { name });
+        }
+        Ok(Self {
+            name: input.parse()?,
+        })
+    }
+}
+
+parse!({
+    struct FunctionSpec {
+        kind: FunctionKind,
+        name: UnitName,
         args: Option<Parenthesized<Param, Token![;]>>,
         return_type: Option<(Token![return], Ident)>,
-        is_: Token![is],
-        items: Vec<Item>,
-        begin: Token![begin],
-        stmts: Vec<Stmt>,
-        end: Token![end],
-        semi: Token![;],
     }
 });
 
-impl Function {
+impl FunctionSpec {
+    /// The defining name, without the parent unit name of a child subprogram.
+    pub fn ident(&self) -> &Ident {
+        self.name.last()
+    }
+
     pub fn args(&self) -> impl Iterator<Item = &Param> {
         self.args.iter().flat_map(|args| args.iter())
     }
@@ -54,7 +154,7 @@ impl Function {
     }
 }
 
-impl Parse for Function {
+impl Parse for FunctionSpec {
     fn parse(input: ParseStream) -> Result<Self> {
         let kind = input.parse()?;
         input.unrecoverable(|input| {
@@ -68,20 +168,76 @@ impl Parse for Function {
                     Some((keyword, ty))
                 }
             };
-            let is_ = input.parse()?;
-            let (items, begin) = input.parse_until(Token![begin])?;
-            let (stmts, end) = input.parse_until_end()?;
-            input.try_parse_ident(&name)?;
-            let semi = input.parse()?;
             Ok(Self {
                 kind,
                 name,
                 args,
                 return_type,
+            })
+        })
+    }
+}
+
+parse!({
+    struct FunctionDeclaration {
+        spec: FunctionSpec,
+        aspects: Option<AspectSpecification>,
+        semi: Token![;],
+    }
+});
+
+impl Parse for FunctionDeclaration {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self {
+            spec: input.parse()?,
+            aspects: input.try_parse()?,
+            semi: input.parse()?,
+        })
+    }
+}
+
+parse!({
+    struct Function {
+        spec: FunctionSpec,
+        aspects: Option<AspectSpecification>,
+        is_: Token![is],
+        items: Vec<Item>,
+        begin: Token![begin],
+        stmts: Vec<Stmt>,
+        handlers: Option<ExceptionHandlers>,
+        end: Token![end],
+        semi: Token![;],
+    }
+});
+
+impl Function {
+    pub fn args(&self) -> impl Iterator<Item = &Param> {
+        self.spec.args()
+    }
+
+    pub fn return_type(&self) -> Option<&Ident> {
+        self.spec.return_type()
+    }
+}
+
+impl Parse for Function {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let spec: FunctionSpec = input.parse()?;
+        let aspects = input.try_parse()?;
+        input.unrecoverable(|input| {
+            let is_ = input.parse()?;
+            let (items, begin) = input.parse_until(Token![begin])?;
+            let (stmts, handlers, end) = parse_handled_stmts(input)?;
+            spec.name.parse_end(input)?;
+            let semi = input.parse()?;
+            Ok(Self {
+                spec,
+                aspects,
                 is_,
                 items,
                 begin,
                 stmts,
+                handlers,
                 end,
                 semi,
             })
@@ -89,6 +245,54 @@ impl Parse for Function {
     }
 }
 
+// Aspects of a declaration, like `with Pre => X > 0, Inline`.
+parse!({
+    struct AspectSpecification {
+        with: Token![with],
+        aspects: Punctuated<Aspect>,
+    }
+});
+
+impl AspectSpecification {
+    pub fn iter(&self) -> impl Iterator<Item = &Aspect> {
+        self.aspects.iter()
+    }
+}
+
+impl Parse for AspectSpecification {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let with = input.parse()?;
+        input.unrecoverable(|input| {
+            Ok(Self {
+                with,
+                aspects: input.call(Punctuated::parse_while)?,
+            })
+        })
+    }
+}
+
+parse!({
+    struct Aspect {
+        name: Ident,
+        value: Option<(Token![=>], Expr)>,
+    }
+});
+
+impl Aspect {
+    pub fn value(&self) -> Option<&Expr> {
+        self.value.as_ref().map(|(_, value)| value)
+    }
+}
+
+impl Parse for Aspect {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self {
+            name: input.parse()?,
+            value: input.try_call(|input| Ok((input.parse()?, input.parse()?)))?,
+        })
+    }
+}
+
 parse!({
     enum FunctionKind {
         Procedure(Token![procedure]),
@@ -173,6 +377,7 @@ parse!({
         name: Ident,
         is_: Token![is],
         definition: TypeDefinition,
+        aspects: Option<AspectSpecification>,
         semi: Token![;],
     }
 });
@@ -186,6 +391,7 @@ impl Parse for FullTypeItem {
                 name: input.parse()?,
                 is_: input.parse()?,
                 definition: input.parse()?,
+                aspects: input.try_parse()?,
                 semi: input.parse()?,
             })
         })
@@ -197,7 +403,10 @@ parse!({
         Enum(EnumTypeDefinition),
         Signed(SignedTypeDefinition),
         Modular(ModularTypeDefinition),
+        Float(FloatTypeDefinition),
+        Fixed(FixedTypeDefinition),
         Record(RecordTypeDefinition),
+        Private(PrivateTypeDefinition),
     }
 });
 
@@ -207,10 +416,16 @@ impl Parse for TypeDefinition {
             Self::Signed(td)
         } else if let Some(td) = input.try_parse()? {
             Self::Modular(td)
+        } else if let Some(td) = input.try_parse()? {
+            Self::Float(td)
+        } else if let Some(td) = input.try_parse()? {
+            Self::Fixed(td)
         } else if let Some(td) = input.try_parse()? {
             Self::Record(td)
         } else if let Some(td) = input.try_parse()? {
             Self::Enum(td)
+        } else if let Some(td) = input.try_parse()? {
+            Self::Private(td)
         } else {
             return Err(input.recoverable_error("expected type definition"));
         })
@@ -219,7 +434,7 @@ impl Parse for TypeDefinition {
 
 parse!({
     struct EnumTypeDefinition {
-        values: Parenthesized<Ident>,
+        values: Parenthesized<EnumLiteral>,
     }
 });
 
@@ -231,6 +446,51 @@ impl Parse for EnumTypeDefinition {
     }
 }
 
+parse!({
+    enum EnumLiteral {
+        Ident(Ident),
+        Char(LitChar),
+    }
+});
+
+impl EnumLiteral {
+    /// The name of the literal, like `'A'` for a character literal.
+    pub fn ident(&self) -> Ident {
+        match self {
+            Self::Ident(ident) => ident.clone(),
+            Self::Char(lit) => lit.ident(),
+        }
+    }
+}
+
+impl Parse for EnumLiteral {
+    fn parse(input: ParseStream) -> Result<Self> {
+        if let Some(ident) = input.try_parse()? {
+            return Ok(Self::Ident(ident));
+        }
+        match input.parse()? {
+            ExprLit::Char(lit) => Ok(Self::Char(lit)),
+            lit => Err(lit.unrecoverable_error("expected enumeration literal")),
+        }
+    }
+}
+
+parse!({
+    struct PrivateTypeDefinition {
+        limited: Option<Token![limited]>,
+        private: Token![private],
+    }
+});
+
+impl Parse for PrivateTypeDefinition {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self {
+            limited: input.try_parse()?,
+            private: input.parse()?,
+        })
+    }
+}
+
 parse!({
     struct SignedTypeDefinition {
         range_keyword: Token![range],
@@ -269,6 +529,49 @@ impl Parse for ModularTypeDefinition {
     }
 }
 
+parse!({
+    struct FloatTypeDefinition {
+        digits_keyword: Token![digits],
+        digits: Expr,
+        range: Option<RangeConstraint>,
+    }
+});
+
+impl Parse for FloatTypeDefinition {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let digits_keyword = input.parse()?;
+        input.unrecoverable(|input| {
+            Ok(Self {
+                digits_keyword,
+                digits: input.parse()?,
+                range: input.try_parse()?,
+            })
+        })
+    }
+}
+
+// An ordinary fixed point type definition, decimal fixed point types are not supported.
+parse!({
+    struct FixedTypeDefinition {
+        delta_keyword: Token![delta],
+        delta: Expr,
+        range: RangeConstraint,
+    }
+});
+
+impl Parse for FixedTypeDefinition {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let delta_keyword = input.parse()?;
+        input.unrecoverable(|input| {
+            Ok(Self {
+                delta_keyword,
+                delta: input.parse()?,
+                range: input.parse()?,
+            })
+        })
+    }
+}
+
 parse!({
     struct RecordTypeDefinition {
         record: Token![record],
@@ -296,6 +599,7 @@ impl Parse for RecordTypeDefinition {
 parse!({
     struct RecordComponentList {
         components: Vec<Variable>,
+        pragmas: Vec<Pragma>,
         variant: Option<RecordVariant>,
         null: Option<Token![null]>,
     }
@@ -303,13 +607,23 @@ parse!({
 
 impl Parse for RecordComponentList {
     fn parse(input: ParseStream) -> Result<Self> {
+        let mut pragmas = Vec::new();
+        while let Some(pragma) = input.try_parse()? {
+            pragmas.push(pragma);
+        }
         let null = input.try_parse()?;
         let mut components = Vec::new();
         let variant = if null.is_some() {
             None
         } else {
-            while let Some(component) = input.try_parse()? {
-                components.push(component);
+            loop {
+                if let Some(component) = input.try_parse()? {
+                    components.push(component);
+                } else if let Some(pragma) = input.try_parse()? {
+                    pragmas.push(pragma);
+                } else {
+                    break;
+                }
             }
 
             if components.is_empty() {
@@ -320,6 +634,7 @@ impl Parse for RecordComponentList {
         };
         Ok(Self {
             components,
+            pragmas,
             variant,
             null,
         })
@@ -465,22 +780,123 @@ impl Parse for Range {
     }
 }
 
+parse!({
+    enum DiscreteSubtypeDefinition {
+        Range(Range),
+        Subtype(SubtypeIndication),
+    }
+});
+
+impl Parse for DiscreteSubtypeDefinition {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(if let Some(range) = input.try_parse()? {
+            Self::Range(range)
+        } else {
+            Self::Subtype(input.parse()?)
+        })
+    }
+}
+
+parse!({
+    struct SubtypeIndication {
+        mark: Name,
+        constraint: Option<Constraint>,
+    }
+});
+
+impl Parse for SubtypeIndication {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self {
+            mark: input.parse()?,
+            constraint: input.try_parse()?,
+        })
+    }
+}
+
 parse!({
     struct Variable {
         name: Ident,
         colon: Token![:],
-        ty: Ident,
+        constant: Option<Token![constant]>,
+        ty: UnitName,
+        init: Option<(Token![:=], Expr)>,
         semi: Token![;],
     }
 });
 
+impl Variable {
+    pub fn init(&self) -> Option<&Expr> {
+        self.init.as_ref().map(|(_, expr)| expr)
+    }
+}
+
 impl Parse for Variable {
     fn parse(input: ParseStream) -> Result<Self> {
         Ok(Self {
             name: input.parse()?,
             colon: input.parse()?,
+            constant: input.try_parse()?,
             ty: input.parse()?,
+            init: input.try_call(|input| {
+                let assign = input.parse()?;
+                input.unrecoverable(|input| Ok((assign, input.parse()?)))
+            })?,
             semi: input.parse()?,
         })
     }
 }
+
+parse!({
+    struct NumberDeclaration {
+        name: Ident,
+        colon: Token![:],
+        constant: Token![constant],
+        assign: Token![:=],
+        expr: Expr,
+        semi: Token![;],
+    }
+});
+
+impl Parse for NumberDeclaration {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let name = input.parse()?;
+        let colon = input.parse()?;
+        let constant = input.parse()?;
+        let assign = input.parse()?;
+        input.unrecoverable(|input| {
+            Ok(Self {
+                name,
+                colon,
+                constant,
+                assign,
+                expr: input.parse()?,
+                semi: input.parse()?,
+            })
+        })
+    }
+}
+
+parse!({
+    struct ExceptionDeclaration {
+        name: Ident,
+        colon: Token![:],
+        exception: Token![exception],
+        semi: Token![;],
+    }
+});
+
+impl Parse for ExceptionDeclaration {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let name = input.parse()?;
+        let colon = input.parse()?;
+        let exception = input.parse()?;
+        input.unrecoverable(|input| {
+            Ok(Self {
+                name,
+                colon,
+                exception,
+                semi: input.parse()?,
+            })
+        })
+    }
+}
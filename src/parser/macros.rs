@@ -16,6 +16,16 @@ macro_rules! parse {
                 }
             }
         }
+
+        impl $ident {
+            /// The name of the enum and the variant, like `Stmt::Assign`.
+            #[allow(dead_code)]
+            pub fn kind(&self) -> &'static str {
+                match self {
+                    $(Self::$var(_) => concat!(stringify!($ident), "::", stringify!($var)),)*
+                }
+            }
+        }
     };
     ({
         struct $ident:ident {
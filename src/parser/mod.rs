@@ -1,6 +1,6 @@
 use crate::{
     tokenizer::{Group, Ident, Literal, Punct, Span, Spanned, TokenStream, TokenTree},
-    Result, Token,
+    Diagnostics, Error, Result, Token,
 };
 
 #[macro_use]
@@ -8,23 +8,42 @@ mod macros;
 
 mod expr;
 mod item;
+mod package;
 mod parenthesized;
+mod pragma;
+mod representation;
 mod stmt;
 pub mod token;
+mod unit;
 
 pub use expr::*;
 pub use item::*;
+pub use package::*;
 pub use parenthesized::*;
+pub use pragma::*;
+pub use representation::*;
 pub use stmt::*;
 use token::{Token, TokenFn};
+pub use unit::*;
 
 pub trait Parse: Sized {
     fn parse(input: ParseStream) -> crate::Result<Self>;
+
+    /// A placeholder for the tokens of an element skipped after a syntax error.
+    fn skipped(input: ParseStream) -> Option<Self> {
+        let _ = input;
+        None
+    }
 }
 
 fn is_keyword(ident: &Ident) -> bool {
+    is_reserved_word(&ident.name)
+}
+
+/// Whether `name`, in lower case, is a reserved word of Ada.
+pub(crate) fn is_reserved_word(name: &str) -> bool {
     matches!(
-        &*ident.name,
+        name,
         "abort"
             | "abs"
             | "abstract"
@@ -82,6 +101,7 @@ fn is_keyword(ident: &Ident) -> bool {
             | "reverse"
             | "select"
             | "separate"
+            | "some"
             | "subtype"
             | "tagged"
             | "task"
@@ -140,6 +160,7 @@ pub type ParseStream<'a, 'b> = &'a mut ParseBuffer<'b>;
 
 pub struct ParseBuffer<'a> {
     inner: &'a [TokenTree],
+    diagnostics: &'a Diagnostics,
 }
 
 impl Spanned for ParseBuffer<'_> {
@@ -155,6 +176,13 @@ impl ParseBuffer<'_> {
         Ok(value)
     }
 
+    fn fork(&self) -> Self {
+        Self {
+            inner: self.inner,
+            diagnostics: self.diagnostics,
+        }
+    }
+
     pub fn is_empty(&self) -> bool {
         self.inner.is_empty()
     }
@@ -163,7 +191,7 @@ impl ParseBuffer<'_> {
     where
         T: Token,
     {
-        let mut clone = Self { inner: self.inner };
+        let mut clone = self.fork();
         T::parse(&mut clone).is_ok()
     }
 
@@ -192,7 +220,7 @@ impl ParseBuffer<'_> {
     }
 
     fn call<T>(&mut self, parse: impl FnOnce(ParseStream) -> Result<T>) -> Result<T> {
-        let mut clone = Self { inner: self.inner };
+        let mut clone = self.fork();
         let value = parse(&mut clone)?;
         *self = clone;
         Ok(value)
@@ -206,13 +234,17 @@ impl ParseBuffer<'_> {
     }
 
     fn try_call<T>(&mut self, parse: impl FnOnce(ParseStream) -> Result<T>) -> Result<Option<T>> {
-        let mut clone = Self { inner: self.inner };
+        let mut clone = self.fork();
+        let reported = self.diagnostics.len();
         match parse(&mut clone) {
             Ok(value) => {
                 *self = clone;
                 Ok(Some(value))
             }
-            Err(err) if err.recoverable => Ok(None),
+            Err(err) if err.recoverable => {
+                self.diagnostics.truncate(reported);
+                Ok(None)
+            }
             Err(err) => Err(err),
         }
     }
@@ -247,12 +279,111 @@ impl ParseBuffer<'_> {
         self.call(|input| {
             let mut vec = Vec::new();
             while !input.peek(token) {
-                vec.push(input.parse()?);
+                vec.extend(input.parse_or_skip()?);
             }
             Ok(vec)
         })
     }
 
+    /// Parse a statement or a declaration of a list, after an error the element is
+    /// skipped and the error is reported so that the following elements are still
+    /// parsed. The skipped element is replaced by its placeholder, if any.
+    ///
+    /// The error is returned when no token can be skipped. The errors already reported
+    /// by the nested elements in the skipped tokens are kept, the ones after them are
+    /// found again when the following elements are parsed.
+    fn parse_or_skip<T>(&mut self) -> Result<Option<T>>
+    where
+        T: Parse,
+    {
+        let reported = self.diagnostics.len();
+        let err = match self.parse() {
+            Ok(value) => return Ok(Some(value)),
+            Err(err) => err,
+        };
+        let mut rest = self.fork();
+        rest.skip_element();
+        if rest.inner.len() == self.inner.len() {
+            return Err(err);
+        }
+        let skipped = |span: Span| rest.is_empty() || span.starts_before(rest.span());
+        self.diagnostics
+            .retain_from(reported, |nested| skipped(nested.span));
+        if self.diagnostics.len() == reported || skipped(err.span) {
+            let node = std::any::type_name::<T>().rsplit("::").next().unwrap();
+            self.diagnostics.report(err.syntax().in_node(node));
+        }
+        let placeholder = T::skipped(&mut self.fork());
+        *self = rest;
+        Ok(placeholder)
+    }
+
+    /// Skip the tokens of an element up to its `;`, the constructs nested in the
+    /// element are skipped up to their `end [X];`. An `end` which is not nested is
+    /// the end of the list and is not skipped.
+    fn skip_element(&mut self) {
+        // Open constructs, `true` for a `declare` or the declarations of a unit which are
+        // closed by the `end` of their `begin`.
+        let mut open = Vec::new();
+        let mut null = false;
+        let mut unit = false;
+        while !self.is_empty() {
+            if self.peek(Token![end]) {
+                if open.pop().is_none() {
+                    return;
+                }
+                while !self.is_empty() && !self.peek(Token![;]) {
+                    self.inner = &self.inner[1..];
+                }
+                if open.is_empty() {
+                    break;
+                }
+            } else if self.peek(Token![;]) && open.is_empty() {
+                break;
+            } else if self.peek(Token![begin]) {
+                if open.last() == Some(&true) {
+                    open.pop();
+                }
+                open.push(false);
+            } else if self.peek(Token![declare]) {
+                open.push(true);
+            } else if self.peek(Token![procedure])
+                || self.peek(Token![function])
+                || self.peek(Token![package])
+            {
+                unit = true;
+            } else if self.peek(Token![;]) {
+                unit = false;
+            } else if self.peek(Token![is]) && unit {
+                unit = false;
+                let mut next = self.fork();
+                next.inner = &next.inner[1..];
+                if !(next.peek(Token![new])
+                    || next.peek(Token![abstract])
+                    || next.peek(Token![null])
+                    || next.peek(Token![separate])
+                    || next.peek(Token![<>])
+                    || matches!(next.inner.first(), Some(TokenTree::Group(_))))
+                {
+                    open.push(true);
+                }
+            } else if self.peek(Token![if])
+                || self.peek(Token![case])
+                || self.peek(Token![loop])
+                || self.peek(Token![select])
+                || self.peek(Token![do])
+                || self.peek(Token![record]) && !null
+            {
+                open.push(false);
+            }
+            null = self.peek(Token![null]);
+            self.inner = &self.inner[1..];
+        }
+        if !self.is_empty() {
+            self.inner = &self.inner[1..];
+        }
+    }
+
     fn parse_until<T, F>(&mut self, token: F) -> Result<(Vec<T>, F::Token)>
     where
         T: Parse,
@@ -316,19 +447,37 @@ impl<'a> Cursor<'a> {
     }
 }
 
-pub fn parse<T>(input: TokenStream) -> Result<T>
+/// Parse the tokens, the errors of the statements and declarations which are skipped are
+/// reported to `diagnostics`.
+pub fn parse<T>(input: TokenStream, diagnostics: &Diagnostics) -> Result<T>
 where
     T: Parse,
 {
-    parse_with(input, T::parse)
+    // an error at the end of the input is shown at the last token
+    let last = input.last().map(Spanned::span);
+    parse_with(input, diagnostics, T::parse).map_err(|mut err| {
+        if let Some(last) = last.filter(|_| err.span.is_call_site()) {
+            err.span = last;
+        }
+        err
+    })
 }
 
-fn parse_with<T>(input: TokenStream, parse: impl FnOnce(ParseStream) -> Result<T>) -> Result<T> {
-    let mut input = ParseBuffer { inner: &input };
-    let value = parse(&mut input)?;
+fn parse_with<T>(
+    input: TokenStream,
+    diagnostics: &Diagnostics,
+    parse: impl FnOnce(ParseStream) -> Result<T>,
+) -> Result<T> {
+    let mut input = ParseBuffer {
+        inner: &input,
+        diagnostics,
+    };
+    let value = parse(&mut input).map_err(Error::syntax)?;
     if input.inner.is_empty() {
         Ok(value)
     } else {
-        Err(input.unrecoverable_error("unexpected trailing tokens"))
+        Err(input
+            .unrecoverable_error("unexpected trailing tokens")
+            .syntax())
     }
 }
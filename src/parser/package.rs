@@ -0,0 +1,338 @@
+use crate::{
+    tokenizer::{Ident, Span, Spanned},
+    Result, Token,
+};
+
+use super::{
+    stmt::parse_handled_stmts, ExceptionHandlers, FunctionDeclaration, FunctionKind, Item,
+    Parenthesized, ParenthesizedOne, Parse, ParseStream, Pragma, PrivateTypeDefinition, Stmt,
+    UnitName,
+};
+
+parse!({
+    struct PackageItem {
+        package: Token![package],
+        name: UnitName,
+        is_: Token![is],
+        items: Vec<Item>,
+        private: Option<(Token![private], Vec<Item>)>,
+        end: Token![end],
+        semi: Token![;],
+    }
+});
+
+impl PackageItem {
+    pub fn private_items(&self) -> &[Item] {
+        self.private
+            .as_ref()
+            .map_or(Default::default(), |(_, items)| items.as_slice())
+    }
+}
+
+impl Parse for PackageItem {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let package = input.parse()?;
+        input.unrecoverable(|input| {
+            let name: UnitName = input.parse()?;
+            let is_ = input.parse()?;
+            let items = parse_items_until_private_or_end(input)?;
+            let private = input.try_call(|input| {
+                let private = input.parse()?;
+                input.unrecoverable(|input| Ok((private, parse_items_until_private_or_end(input)?)))
+            })?;
+            let end = input.parse()?;
+            name.parse_end(input)?;
+            let semi = input.parse()?;
+            Ok(Self {
+                package,
+                name,
+                is_,
+                items,
+                private,
+                end,
+                semi,
+            })
+        })
+    }
+}
+
+fn parse_items_until_private_or_end(input: ParseStream) -> Result<Vec<Item>> {
+    let mut items = Vec::new();
+    while !input.peek(Token![private]) && !input.peek(Token![end]) {
+        items.extend(input.parse_or_skip()?);
+    }
+    Ok(items)
+}
+
+parse!({
+    struct PackageBody {
+        package: Token![package],
+        body: Token![body],
+        name: UnitName,
+        is_: Token![is],
+        items: Vec<Item>,
+        begin: Option<(Token![begin], Vec<Stmt>)>,
+        handlers: Option<ExceptionHandlers>,
+        end: Token![end],
+        semi: Token![;],
+    }
+});
+
+impl PackageBody {
+    pub fn stmts(&self) -> &[Stmt] {
+        self.begin
+            .as_ref()
+            .map_or(Default::default(), |(_, stmts)| stmts.as_slice())
+    }
+}
+
+impl Parse for PackageBody {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let package = input.parse()?;
+        let body = input.parse()?;
+        input.unrecoverable(|input| {
+            let name: UnitName = input.parse()?;
+            let is_ = input.parse()?;
+            let mut items = Vec::new();
+            while !input.peek(Token![begin]) && !input.peek(Token![end]) {
+                items.extend(input.parse_or_skip()?);
+            }
+            let (begin, handlers, end) = match input.try_parse()? {
+                Some(begin) => {
+                    let (stmts, handlers, end) = parse_handled_stmts(input)?;
+                    (Some((begin, stmts)), handlers, end)
+                }
+                None => (None, None, input.parse()?),
+            };
+            name.parse_end(input)?;
+            let semi = input.parse()?;
+            Ok(Self {
+                package,
+                body,
+                name,
+                is_,
+                items,
+                begin,
+                handlers,
+                end,
+                semi,
+            })
+        })
+    }
+}
+
+parse!({
+    struct GenericDeclaration {
+        generic: Token![generic],
+        formals: Vec<GenericFormal>,
+        pragmas: Vec<Pragma>,
+        unit: GenericUnit,
+    }
+});
+
+impl GenericDeclaration {
+    pub fn name(&self) -> &UnitName {
+        match &self.unit {
+            GenericUnit::Package(package) => &package.name,
+            GenericUnit::Subprogram(subprogram) => &subprogram.spec.name,
+        }
+    }
+}
+
+impl Parse for GenericDeclaration {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let generic = input.parse()?;
+        input.unrecoverable(|input| {
+            let mut formals = Vec::new();
+            let mut pragmas = Vec::new();
+            while !input.peek(Token![package])
+                && !input.peek(Token![procedure])
+                && !input.peek(Token![function])
+            {
+                if let Some(pragma) = input.try_parse()? {
+                    pragmas.push(pragma);
+                } else {
+                    formals.push(input.parse()?);
+                }
+            }
+            Ok(Self {
+                generic,
+                formals,
+                pragmas,
+                unit: input.parse()?,
+            })
+        })
+    }
+}
+
+parse!({
+    enum GenericUnit {
+        Package(PackageItem),
+        Subprogram(FunctionDeclaration),
+    }
+});
+
+impl Parse for GenericUnit {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(if let Some(unit) = input.try_parse()? {
+            Self::Package(unit)
+        } else if let Some(unit) = input.try_parse()? {
+            Self::Subprogram(unit)
+        } else {
+            return Err(input.unrecoverable_error("expected generic package or subprogram"));
+        })
+    }
+}
+
+parse!({
+    enum GenericFormal {
+        Type(GenericFormalType),
+    }
+});
+
+impl GenericFormal {
+    pub fn name(&self) -> &Ident {
+        match self {
+            Self::Type(formal) => &formal.name,
+        }
+    }
+}
+
+impl Parse for GenericFormal {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(if let Some(formal) = input.try_parse()? {
+            Self::Type(formal)
+        } else {
+            return Err(input.unrecoverable_error("expected generic formal parameter"));
+        })
+    }
+}
+
+parse!({
+    struct GenericFormalType {
+        type_: Token![type],
+        name: Ident,
+        discriminants: Option<ParenthesizedOne<Token![<>]>>,
+        is_: Token![is],
+        definition: FormalTypeDefinition,
+        semi: Token![;],
+    }
+});
+
+impl Parse for GenericFormalType {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let type_ = input.parse()?;
+        input.unrecoverable(|input| {
+            Ok(Self {
+                type_,
+                name: input.parse()?,
+                discriminants: input.try_parse()?,
+                is_: input.parse()?,
+                definition: input.parse()?,
+                semi: input.parse()?,
+            })
+        })
+    }
+}
+
+parse!({
+    enum FormalTypeDefinition {
+        Discrete(ParenthesizedOne<Token![<>]>),
+        Signed((Token![range], Token![<>])),
+        Modular((Token![mod], Token![<>])),
+        Private(PrivateTypeDefinition),
+    }
+});
+
+impl Parse for FormalTypeDefinition {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(if let Some(definition) = input.try_parse()? {
+            Self::Discrete(definition)
+        } else if let Some(range) = input.try_parse()? {
+            Self::Signed((range, input.parse()?))
+        } else if let Some(mod_) = input.try_parse()? {
+            Self::Modular((mod_, input.parse()?))
+        } else if let Some(definition) = input.try_parse()? {
+            Self::Private(definition)
+        } else {
+            return Err(input.unrecoverable_error("expected formal type definition"));
+        })
+    }
+}
+
+parse!({
+    struct GenericInstantiation {
+        kind: InstantiationKind,
+        name: UnitName,
+        is_: Token![is],
+        new: Token![new],
+        generic_name: UnitName,
+        actuals: Option<Parenthesized<GenericActual>>,
+        semi: Token![;],
+    }
+});
+
+impl GenericInstantiation {
+    pub fn actuals(&self) -> impl Iterator<Item = &GenericActual> {
+        self.actuals.iter().flat_map(|actuals| actuals.iter())
+    }
+}
+
+impl Parse for GenericInstantiation {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let kind = input.parse()?;
+        let name = input.parse()?;
+        let is_ = input.parse()?;
+        let new = input.parse()?;
+        input.unrecoverable(|input| {
+            Ok(Self {
+                kind,
+                name,
+                is_,
+                new,
+                generic_name: input.parse()?,
+                actuals: input.try_parse()?,
+                semi: input.parse()?,
+            })
+        })
+    }
+}
+
+parse!({
+    enum InstantiationKind {
+        Package(Token![package]),
+        Subprogram(FunctionKind),
+    }
+});
+
+impl Parse for InstantiationKind {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(if let Some(kind) = input.try_parse()? {
+            Self::Package(kind)
+        } else if let Some(kind) = input.try_parse()? {
+            Self::Subprogram(kind)
+        } else {
+            return Err(input.recoverable_error("expected `package`, `procedure` or `function`"));
+        })
+    }
+}
+
+parse!({
+    struct GenericActual {
+        formal: Option<(Ident, Token![=>])>,
+        ty: Ident,
+    }
+});
+
+impl Parse for GenericActual {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self {
+            formal: input.try_call(|input| {
+                let formal = input.parse()?;
+                let arrow = input.parse()?;
+                Ok((formal, arrow))
+            })?,
+            ty: input.parse()?,
+        })
+    }
+}
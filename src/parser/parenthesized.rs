@@ -39,7 +39,7 @@ impl<T> ParenthesizedOne<T> {
         parse: impl FnOnce(ParseStream) -> Result<T>,
     ) -> Result<Self> {
         let (paren, inner) = Paren::parse_inner(input)?;
-        match super::parse_with(inner, |input| input.unrecoverable(parse)) {
+        match super::parse_with(inner, input.diagnostics, |input| input.unrecoverable(parse)) {
             Ok(inner) => Ok(Self { paren, inner }),
             Err(mut err) => {
                 if err.span.is_call_site() {
@@ -51,6 +51,19 @@ impl<T> ParenthesizedOne<T> {
     }
 }
 
+impl<T> ParenthesizedOne<T> {
+    /// Parse the parenthesized content, a recoverable error inside the parentheses
+    /// allows to try another syntax with the same parentheses.
+    pub(super) fn try_parse_with(
+        input: ParseStream,
+        parse: impl FnOnce(ParseStream) -> Result<T>,
+    ) -> Result<Self> {
+        let (paren, inner) = Paren::parse_inner(input)?;
+        let inner = super::parse_with(inner, input.diagnostics, parse)?;
+        Ok(Self { paren, inner })
+    }
+}
+
 impl<T> Parse for ParenthesizedOne<T>
 where
     T: Parse,
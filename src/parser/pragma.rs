@@ -0,0 +1,78 @@
+use crate::{
+    tokenizer::{Ident, Span, Spanned},
+    Result, Token,
+};
+
+use super::{BaseName, Expr, Name, Parenthesized, Parse, ParseStream, SelectorName, UnitName};
+
+parse!({
+    struct Pragma {
+        pragma: Token![pragma],
+        name: Ident,
+        args: Option<Parenthesized<PragmaArgument>>,
+        semi: Token![;],
+    }
+});
+
+impl Pragma {
+    pub fn args(&self) -> impl Iterator<Item = &PragmaArgument> {
+        self.args.iter().flat_map(|args| args.iter())
+    }
+}
+
+impl Parse for Pragma {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let pragma = input.parse()?;
+        input.unrecoverable(|input| {
+            Ok(Self {
+                pragma,
+                name: input.parse()?,
+                args: input.try_parse()?,
+                semi: input.parse()?,
+            })
+        })
+    }
+}
+
+parse!({
+    struct PragmaArgument {
+        name: Option<(Ident, Token![=>])>,
+        value: Expr,
+    }
+});
+
+impl PragmaArgument {
+    /// The argument as the name of a library unit, like `Ada.Text_IO`.
+    pub fn unit_name(&self) -> Option<UnitName> {
+        fn parts(name: &Name, idents: &mut Vec<Ident>) -> Option<()> {
+            match name {
+                Name::Base(BaseName::Ident(ident)) => idents.push(ident.clone()),
+                Name::Select(select) => {
+                    parts(&select.prefix, idents)?;
+                    let SelectorName::Ident(ident) = &select.name;
+                    idents.push(ident.clone());
+                }
+                _ => return None,
+            }
+            Some(())
+        }
+
+        let Expr::Name(name) = &self.value else {
+            return None;
+        };
+        let mut idents = Vec::new();
+        parts(name, &mut idents)?;
+        Some(UnitName {
+            parts: idents.into_iter().collect(),
+        })
+    }
+}
+
+impl Parse for PragmaArgument {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self {
+            name: input.try_call(|input| Ok((input.parse()?, input.parse()?)))?,
+            value: input.parse()?,
+        })
+    }
+}
@@ -0,0 +1,185 @@
+use crate::{
+    tokenizer::{Ident, Span, Spanned},
+    Result, Token,
+};
+
+use super::{AggregateExpr, Expr, Parse, ParseStream, Range};
+
+parse!({
+    enum RepresentationClause {
+        Attribute(AttributeDefinitionClause),
+        Record(RecordRepresentationClause),
+        Enum(EnumRepresentationClause),
+    }
+});
+
+impl RepresentationClause {
+    /// The name of the entity the clause applies to.
+    pub fn local_name(&self) -> &Ident {
+        match self {
+            Self::Attribute(clause) => &clause.name,
+            Self::Record(clause) => &clause.name,
+            Self::Enum(clause) => &clause.name,
+        }
+    }
+}
+
+impl Parse for RepresentationClause {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(if let Some(clause) = input.try_parse()? {
+            Self::Attribute(clause)
+        } else if let Some(clause) = input.try_parse()? {
+            Self::Record(clause)
+        } else if let Some(clause) = input.try_parse()? {
+            Self::Enum(clause)
+        } else {
+            return Err(input.recoverable_error("expected representation clause"));
+        })
+    }
+}
+
+parse!({
+    struct AttributeDefinitionClause {
+        for_: Token![for],
+        name: Ident,
+        tick: Token![tick],
+        designator: Ident,
+        use_: Token![use],
+        expr: Expr,
+        semi: Token![;],
+    }
+});
+
+impl Parse for AttributeDefinitionClause {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let for_ = input.parse()?;
+        let name = input.parse()?;
+        let tick = input.parse()?;
+        input.unrecoverable(|input| {
+            Ok(Self {
+                for_,
+                name,
+                tick,
+                designator: input.parse()?,
+                use_: input.parse()?,
+                expr: input.parse()?,
+                semi: input.parse()?,
+            })
+        })
+    }
+}
+
+parse!({
+    struct EnumRepresentationClause {
+        for_: Token![for],
+        name: Ident,
+        use_: Token![use],
+        aggregate: AggregateExpr,
+        semi: Token![;],
+    }
+});
+
+impl Parse for EnumRepresentationClause {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let for_ = input.parse()?;
+        let name = input.parse()?;
+        let use_ = input.parse()?;
+        input.unrecoverable(|input| {
+            Ok(Self {
+                for_,
+                name,
+                use_,
+                aggregate: input.parse()?,
+                semi: input.parse()?,
+            })
+        })
+    }
+}
+
+parse!({
+    struct RecordRepresentationClause {
+        for_: Token![for],
+        name: Ident,
+        use_: Token![use],
+        record: Token![record],
+        alignment: Option<ModClause>,
+        components: Vec<ComponentClause>,
+        end: Token![end],
+        semi: Token![;],
+    }
+});
+
+impl Parse for RecordRepresentationClause {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let for_ = input.parse()?;
+        let name = input.parse()?;
+        let use_ = input.parse()?;
+        let record = input.parse()?;
+        input.unrecoverable(|input| {
+            let alignment = input.try_parse()?;
+            let (components, end) = input.parse_until_end()?;
+            let _: Token![record] = input.parse()?;
+            Ok(Self {
+                for_,
+                name,
+                use_,
+                record,
+                alignment,
+                components,
+                end,
+                semi: input.parse()?,
+            })
+        })
+    }
+}
+
+parse!({
+    struct ModClause {
+        at: Token![at],
+        mod_: Token![mod],
+        expr: Expr,
+        semi: Token![;],
+    }
+});
+
+impl Parse for ModClause {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let at = input.parse()?;
+        let mod_ = input.parse()?;
+        input.unrecoverable(|input| {
+            Ok(Self {
+                at,
+                mod_,
+                expr: input.parse()?,
+                semi: input.parse()?,
+            })
+        })
+    }
+}
+
+parse!({
+    struct ComponentClause {
+        name: Ident,
+        at: Token![at],
+        position: Expr,
+        range_token: Token![range],
+        range: Range,
+        semi: Token![;],
+    }
+});
+
+impl Parse for ComponentClause {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let name = input.parse()?;
+        input.unrecoverable(|input| {
+            Ok(Self {
+                name,
+                at: input.parse()?,
+                position: input.call(Expr::parse_simple_expression)?,
+                range_token: input.parse()?,
+                range: input.parse()?,
+                semi: input.parse()?,
+            })
+        })
+    }
+}
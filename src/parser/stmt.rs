@@ -3,7 +3,10 @@ use crate::{
     Result, Token,
 };
 
-use super::{DiscreteChoice, Expr, Item, Name, Parse, ParseStream, Punctuated, Range};
+use super::{
+    DiscreteChoice, DiscreteSubtypeDefinition, Expr, Item, Name, Parse, ParseStream, Pragma,
+    Punctuated, UnitName,
+};
 
 parse!({
     enum Stmt {
@@ -11,19 +14,30 @@ parse!({
         Expr(ExprStmt),
         Assign(AssignStmt),
         Return(ReturnStmt),
+        ExtendedReturn(ExtendedReturnStmt),
         If(IfStmt),
         Block(BlockStmt),
         Goto(GotoStmt),
         Loop(LoopStmt),
         Exit(ExitStmt),
         Case(CaseStmt),
+        Null(NullStmt),
+        Raise(RaiseStmt),
+        Delay(DelayStmt),
+        Abort(AbortStmt),
+        Requeue(RequeueStmt),
+        Pragma(Pragma),
     }
 });
 
 impl Parse for Stmt {
     fn parse(input: ParseStream) -> Result<Self> {
         Ok(if let Some(stmt) = input.try_parse()? {
+            Self::Pragma(stmt)
+        } else if let Some(stmt) = input.try_parse()? {
             Self::Label(stmt)
+        } else if let Some(stmt) = input.try_parse()? {
+            Self::ExtendedReturn(stmt)
         } else if let Some(stmt) = input.try_parse()? {
             Self::Return(stmt)
         } else if let Some(stmt) = input.try_parse()? {
@@ -34,6 +48,16 @@ impl Parse for Stmt {
             Self::Exit(stmt)
         } else if let Some(stmt) = input.try_parse()? {
             Self::Case(stmt)
+        } else if let Some(stmt) = input.try_parse()? {
+            Self::Null(stmt)
+        } else if let Some(stmt) = input.try_parse()? {
+            Self::Raise(stmt)
+        } else if let Some(stmt) = input.try_parse()? {
+            Self::Delay(stmt)
+        } else if let Some(stmt) = input.try_parse()? {
+            Self::Abort(stmt)
+        } else if let Some(stmt) = input.try_parse()? {
+            Self::Requeue(stmt)
         } else if let Some(stmt) = input.try_parse()? {
             Self::Loop(stmt)
         } else if let Some(stmt) = input.try_parse()? {
@@ -110,7 +134,7 @@ impl Parse for AssignStmt {
 parse!({
     struct ReturnStmt {
         return_: Token![return],
-        expr: Expr,
+        expr: Option<Expr>,
         semi: Token![;],
     }
 });
@@ -119,15 +143,89 @@ impl Parse for ReturnStmt {
     fn parse(input: ParseStream) -> Result<Self> {
         let return_ = input.parse()?;
         input.unrecoverable(|input| {
+            if let Some(semi) = input.try_parse()? {
+                return Ok(Self {
+                    return_,
+                    expr: None,
+                    semi,
+                });
+            }
             Ok(Self {
                 return_,
-                expr: input.parse()?,
+                expr: Some(input.parse()?),
+                semi: input.parse()?,
+            })
+        })
+    }
+}
+
+parse!({
+    struct ExtendedReturnStmt {
+        return_: Token![return],
+        name: Ident,
+        colon: Token![:],
+        constant: Option<Token![constant]>,
+        ty: UnitName,
+        init: Option<(Token![:=], Expr)>,
+        body: Option<ExtendedReturnBody>,
+        semi: Token![;],
+    }
+});
+
+impl ExtendedReturnStmt {
+    pub fn init(&self) -> Option<&Expr> {
+        self.init.as_ref().map(|(_, expr)| expr)
+    }
+}
+
+impl Parse for ExtendedReturnStmt {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let return_ = input.parse()?;
+        // without `Name :` it is a simple return statement
+        let name = input.parse()?;
+        let colon = input.parse()?;
+        input.unrecoverable(|input| {
+            Ok(Self {
+                return_,
+                name,
+                colon,
+                constant: input.try_parse()?,
+                ty: input.parse()?,
+                init: input.try_call(|input| {
+                    let assign = input.parse()?;
+                    input.unrecoverable(|input| Ok((assign, input.parse()?)))
+                })?,
+                body: input.try_parse()?,
                 semi: input.parse()?,
             })
         })
     }
 }
 
+parse!({
+    struct ExtendedReturnBody {
+        do_: Token![do],
+        stmts: Vec<Stmt>,
+        end: Token![end],
+        return_: Token![return],
+    }
+});
+
+impl Parse for ExtendedReturnBody {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let do_ = input.parse()?;
+        input.unrecoverable(|input| {
+            let (stmts, end) = input.parse_until_end()?;
+            Ok(Self {
+                do_,
+                stmts,
+                end,
+                return_: input.parse()?,
+            })
+        })
+    }
+}
+
 parse!({
     struct IfStmt {
         if_: Token![if],
@@ -148,7 +246,7 @@ impl Parse for IfStmt {
             let cond = input.parse()?;
             let then = input.parse()?;
 
-            let mut stmts = vec![input.parse()?];
+            let mut stmts: Vec<_> = input.parse_or_skip()?.into_iter().collect();
             let mut elsifs = Vec::new();
             let mut else_ = None;
 
@@ -177,7 +275,7 @@ impl Parse for IfStmt {
                             vec = &mut else_.as_mut().unwrap().stmts
                         }
                     }
-                    vec.push(input.parse()?);
+                    vec.extend(input.parse_or_skip()?);
                 }
             }
 
@@ -220,6 +318,7 @@ parse!({
         declare: Option<(Token![declare], Vec<Item>)>,
         begin: Token![begin],
         stmts: Vec<Stmt>,
+        handlers: Option<ExceptionHandlers>,
         end: Token![end],
         semi: Token![;],
     }
@@ -249,7 +348,7 @@ impl Parse for BlockStmt {
         let parse = |input: ParseStream| {
             let begin = input.parse()?;
             input.unrecoverable(|input| {
-                let (stmts, end) = input.parse_until_end()?;
+                let (stmts, handlers, end) = parse_handled_stmts(input)?;
                 if let Some((ident, _)) = &ident {
                     input.parse_ident(ident)?;
                 }
@@ -259,6 +358,7 @@ impl Parse for BlockStmt {
                     declare,
                     begin,
                     stmts,
+                    handlers,
                     end,
                     semi,
                 })
@@ -385,29 +485,201 @@ impl Parse for WhileLoopScheme {
 parse!({
     struct ForLoopScheme {
         for_: Token![for],
+        param: LoopParameter,
+    }
+});
+
+impl Parse for ForLoopScheme {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let for_ = input.parse()?;
+        input.unrecoverable(|input| {
+            Ok(Self {
+                for_,
+                param: input.parse()?,
+            })
+        })
+    }
+}
+
+parse!({
+    enum LoopParameter {
+        Spec(LoopParameterSpecification),
+        Iterator(IteratorSpecification),
+    }
+});
+
+impl Parse for LoopParameter {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let ident = input.parse()?;
+        Ok(if let Some(in_) = input.try_parse()? {
+            Self::Spec(LoopParameterSpecification {
+                ident,
+                in_,
+                reverse: input.try_parse()?,
+                range: input.parse()?,
+            })
+        } else {
+            Self::Iterator(IteratorSpecification {
+                ident,
+                of: input.parse()?,
+                reverse: input.try_parse()?,
+                name: input.parse()?,
+            })
+        })
+    }
+}
+
+parse!({
+    struct LoopParameterSpecification {
         ident: Ident,
         in_: Token![in],
         reverse: Option<Token![reverse]>,
-        range: Range,
+        range: DiscreteSubtypeDefinition,
     }
 });
 
-impl ForLoopScheme {
+impl LoopParameterSpecification {
     pub fn reverse(&self) -> bool {
         self.reverse.is_some()
     }
 }
 
-impl Parse for ForLoopScheme {
+parse!({
+    struct IteratorSpecification {
+        ident: Ident,
+        of: Token![of],
+        reverse: Option<Token![reverse]>,
+        name: Name,
+    }
+});
+
+impl IteratorSpecification {
+    pub fn reverse(&self) -> bool {
+        self.reverse.is_some()
+    }
+}
+
+parse!({
+    struct NullStmt {
+        null: Token![null],
+        semi: Token![;],
+    }
+});
+
+impl Parse for NullStmt {
     fn parse(input: ParseStream) -> Result<Self> {
-        let for_ = input.parse()?;
+        let null = input.parse()?;
         input.unrecoverable(|input| {
             Ok(Self {
-                for_,
-                ident: input.parse()?,
-                in_: input.parse()?,
-                reverse: input.try_parse()?,
-                range: input.parse()?,
+                null,
+                semi: input.parse()?,
+            })
+        })
+    }
+}
+
+parse!({
+    struct RaiseStmt {
+        raise: Token![raise],
+        name: Option<Name>,
+        message: Option<(Token![with], Expr)>,
+        semi: Token![;],
+    }
+});
+
+impl RaiseStmt {
+    pub fn message(&self) -> Option<&Expr> {
+        self.message.as_ref().map(|(_, expr)| expr)
+    }
+}
+
+impl Parse for RaiseStmt {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let raise = input.parse()?;
+        input.unrecoverable(|input| {
+            let name = input.try_parse()?;
+            let message = match name {
+                Some(_) => input.try_call(|input| {
+                    let with = input.parse()?;
+                    input.unrecoverable(|input| Ok((with, input.parse()?)))
+                })?,
+                None => None,
+            };
+            Ok(Self {
+                raise,
+                name,
+                message,
+                semi: input.parse()?,
+            })
+        })
+    }
+}
+
+parse!({
+    struct DelayStmt {
+        delay: Token![delay],
+        until: Option<Token![until]>,
+        expr: Expr,
+        semi: Token![;],
+    }
+});
+
+impl Parse for DelayStmt {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let delay = input.parse()?;
+        input.unrecoverable(|input| {
+            Ok(Self {
+                delay,
+                until: input.try_parse()?,
+                expr: input.parse()?,
+                semi: input.parse()?,
+            })
+        })
+    }
+}
+
+parse!({
+    struct AbortStmt {
+        abort: Token![abort],
+        names: Punctuated<Name>,
+        semi: Token![;],
+    }
+});
+
+impl Parse for AbortStmt {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let abort = input.parse()?;
+        input.unrecoverable(|input| {
+            Ok(Self {
+                abort,
+                names: input.call(Punctuated::parse_while)?,
+                semi: input.parse()?,
+            })
+        })
+    }
+}
+
+parse!({
+    struct RequeueStmt {
+        requeue: Token![requeue],
+        name: Name,
+        with_abort: Option<(Token![with], Token![abort])>,
+        semi: Token![;],
+    }
+});
+
+impl Parse for RequeueStmt {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let requeue = input.parse()?;
+        input.unrecoverable(|input| {
+            Ok(Self {
+                requeue,
+                name: input.parse()?,
+                with_abort: input.try_call(|input| {
+                    let with = input.parse()?;
+                    input.unrecoverable(|input| Ok((with, input.parse()?)))
+                })?,
+                semi: input.parse()?,
             })
         })
     }
@@ -450,6 +722,7 @@ parse!({
         case: Token![case],
         expr: Expr,
         is_: Token![is],
+        pragmas: Vec<Pragma>,
         alternatives: Vec<CaseStmtAlternative>,
         end: Token![end],
         semi: Token![;],
@@ -462,6 +735,10 @@ impl Parse for CaseStmt {
         input.unrecoverable(|input| {
             let expr = input.parse()?;
             let is_ = input.parse()?;
+            let mut pragmas = Vec::new();
+            while let Some(pragma) = input.try_parse()? {
+                pragmas.push(pragma);
+            }
             let mut alternatives: Vec<CaseStmtAlternative> = vec![input.parse()?];
             {
                 let mut vec = &mut alternatives.last_mut().unwrap().stmts;
@@ -471,7 +748,7 @@ impl Parse for CaseStmt {
                         alternatives.push(alt);
                         vec = &mut alternatives.last_mut().unwrap().stmts;
                     } else {
-                        vec.push(input.parse()?);
+                        vec.extend(input.parse_or_skip()?);
                     }
                 }
             }
@@ -482,6 +759,7 @@ impl Parse for CaseStmt {
                 case,
                 expr,
                 is_,
+                pragmas,
                 alternatives,
                 end,
                 semi,
@@ -512,6 +790,91 @@ impl Parse for CaseStmtAlternative {
     }
 }
 
+// The exception handlers at the end of a body or a block, like
+// `exception when Constraint_Error => Put_Line ("overflow");`.
+parse!({
+    struct ExceptionHandlers {
+        exception: Token![exception],
+        handlers: Vec<ExceptionHandler>,
+    }
+});
+
+impl Parse for ExceptionHandlers {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let exception = input.parse()?;
+        input.unrecoverable(|input| {
+            let mut handlers: Vec<ExceptionHandler> = vec![input.parse()?];
+            while !input.peek(Token![end]) {
+                if let Some(handler) = input.try_parse()? {
+                    handlers.push(handler);
+                } else {
+                    let stmts = &mut handlers.last_mut().unwrap().stmts;
+                    stmts.extend(input.parse_or_skip()?);
+                }
+            }
+            Ok(Self {
+                exception,
+                handlers,
+            })
+        })
+    }
+}
+
+parse!({
+    struct ExceptionHandler {
+        when: Token![when],
+        param: Option<(Ident, Token![:])>,
+        choices: Punctuated<ExceptionChoice, Token![|]>,
+        arrow: Token![=>],
+        stmts: Vec<Stmt>,
+    }
+});
+
+impl Parse for ExceptionHandler {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let when = input.parse()?;
+        input.unrecoverable(|input| {
+            Ok(Self {
+                when,
+                param: input.try_call(parse_ident_colon)?,
+                choices: input.call(Punctuated::parse_while)?,
+                arrow: input.parse()?,
+                stmts: vec![input.parse()?],
+            })
+        })
+    }
+}
+
+parse!({
+    enum ExceptionChoice {
+        Others(Token![others]),
+        Name(Name),
+    }
+});
+
+impl Parse for ExceptionChoice {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(if let Some(choice) = input.try_parse()? {
+            Self::Others(choice)
+        } else if let Some(choice) = input.try_parse()? {
+            Self::Name(choice)
+        } else {
+            return Err(input.recoverable_error("expected exception choice"));
+        })
+    }
+}
+
+/// The statements of a body or a block up to its `end`, and its exception handlers.
+pub(super) fn parse_handled_stmts(
+    input: ParseStream,
+) -> Result<(Vec<Stmt>, Option<ExceptionHandlers>, Token![end])> {
+    let mut stmts = Vec::new();
+    while !input.peek(Token![end]) && !input.peek(Token![exception]) {
+        stmts.extend(input.parse_or_skip()?);
+    }
+    Ok((stmts, input.try_parse()?, input.parse()?))
+}
+
 fn parse_ident_colon(input: ParseStream) -> Result<(Ident, Token![:])> {
     let ident = input.parse()?;
     let colon = input.parse()?;
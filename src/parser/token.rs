@@ -250,6 +250,9 @@ macro_rules! Token {
     (separate) => {
         $crate::parser::token::Separate
     };
+    (some) => {
+        $crate::parser::token::Some_
+    };
     (subtype) => {
         $crate::parser::token::Subtype
     };
@@ -522,6 +525,7 @@ keyword_token!("return", Return);
 keyword_token!("reverse", Reverse);
 keyword_token!("select", Select);
 keyword_token!("separate", Separate);
+keyword_token!("some", Some_);
 keyword_token!("subtype", Subtype);
 keyword_token!("tagged", Tagged);
 keyword_token!("task", Task);
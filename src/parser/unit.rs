@@ -0,0 +1,200 @@
+use std::fmt;
+
+use crate::{
+    tokenizer::{Ident, Span, Spanned},
+    Result, Token,
+};
+
+use super::{Item, Parse, ParseStream, Pragma, Punctuated};
+
+parse!({
+    struct CompilationUnit {
+        context: Vec<ContextItem>,
+        item: Item,
+        trailing_pragmas: Vec<Pragma>,
+    }
+});
+
+impl Parse for CompilationUnit {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut context = Vec::new();
+        while let Some(item) = input.try_parse()? {
+            context.push(item);
+        }
+        let item = input.parse()?;
+
+        // the pragmas after the last unit apply to it, the ones before another unit are
+        // in its context clause
+        let mut rest = input.fork();
+        let mut trailing_pragmas = Vec::new();
+        while let Some(pragma) = rest.try_parse()? {
+            trailing_pragmas.push(pragma);
+        }
+        if rest.is_empty() {
+            *input = rest;
+        } else {
+            trailing_pragmas.clear();
+        }
+        Ok(Self {
+            context,
+            item,
+            trailing_pragmas,
+        })
+    }
+}
+
+impl CompilationUnit {
+    pub fn withs(&self) -> impl Iterator<Item = &UnitName> {
+        self.context
+            .iter()
+            .flat_map(|item| match item {
+                ContextItem::With(with) => Some(with.names.iter()),
+                ContextItem::Use(_) | ContextItem::Pragma(_) => None,
+            })
+            .flatten()
+    }
+
+    pub fn uses(&self) -> impl Iterator<Item = &UseClause> {
+        self.context.iter().filter_map(|item| match item {
+            ContextItem::Use(use_) => Some(use_),
+            ContextItem::With(_) | ContextItem::Pragma(_) => None,
+        })
+    }
+
+    pub fn pragmas(&self) -> impl Iterator<Item = &Pragma> {
+        self.context.iter().filter_map(|item| match item {
+            ContextItem::Pragma(pragma) => Some(pragma),
+            ContextItem::With(_) | ContextItem::Use(_) => None,
+        })
+    }
+}
+
+parse!({
+    enum ContextItem {
+        With(WithClause),
+        Use(UseClause),
+        Pragma(Pragma),
+    }
+});
+
+impl Parse for ContextItem {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(if let Some(item) = input.try_parse()? {
+            Self::With(item)
+        } else if let Some(item) = input.try_parse()? {
+            Self::Use(item)
+        } else if let Some(item) = input.try_parse()? {
+            Self::Pragma(item)
+        } else {
+            return Err(input.recoverable_error("expected context item"));
+        })
+    }
+}
+
+parse!({
+    struct WithClause {
+        with: Token![with],
+        names: Punctuated<UnitName>,
+        semi: Token![;],
+    }
+});
+
+impl Parse for WithClause {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let with = input.parse()?;
+        input.unrecoverable(|input| {
+            Ok(Self {
+                with,
+                names: input.call(Punctuated::parse_while)?,
+                semi: input.parse()?,
+            })
+        })
+    }
+}
+
+parse!({
+    struct UseClause {
+        use_: Token![use],
+        names: Punctuated<UnitName>,
+        semi: Token![;],
+    }
+});
+
+impl Parse for UseClause {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let use_ = input.parse()?;
+        input.unrecoverable(|input| {
+            Ok(Self {
+                use_,
+                names: input.call(Punctuated::parse_while)?,
+                semi: input.parse()?,
+            })
+        })
+    }
+}
+
+// A dotted name like `Ada.Text_IO`, as used for library units and packages.
+parse!({
+    struct UnitName {
+        parts: Punctuated<Ident, Token![.]>,
+    }
+});
+
+impl Parse for UnitName {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self {
+            parts: input.call(Punctuated::parse_while)?,
+        })
+    }
+}
+
+impl UnitName {
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = &Ident> + Clone {
+        self.parts.iter()
+    }
+
+    pub fn last(&self) -> &Ident {
+        self.parts.iter().last().unwrap()
+    }
+
+    pub fn parent(&self) -> Option<Self> {
+        let len = self.parts.len();
+        if len > 1 {
+            Some(Self {
+                parts: self.parts.iter().take(len - 1).cloned().collect(),
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Parse the optional repetition of the name after `end`.
+    pub(super) fn parse_end(&self, input: ParseStream) -> Result<()> {
+        if let Some(name) = input.try_parse::<Self>()? {
+            if name != *self {
+                return Err(name.unrecoverable_error(format!("expected `{self}`")));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl From<Ident> for UnitName {
+    fn from(ident: Ident) -> Self {
+        Self {
+            parts: [ident].into_iter().collect(),
+        }
+    }
+}
+
+impl fmt::Display for UnitName {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, ident) in self.parts.iter().enumerate() {
+            if i != 0 {
+                f.write_str(".")?;
+            }
+            ident.fmt(f)?;
+        }
+        Ok(())
+    }
+}
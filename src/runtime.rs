@@ -0,0 +1,131 @@
+//! Ada library units bundled with the transpiler.
+//!
+//! Subprograms declared without an Ada body are implemented in C,
+//! under the same name the codegen uses for the declaration.
+//!
+//! Target dependent values are written as `$Name` placeholders, see [`Target`].
+//!
+//! [`Target`]: crate::codegen::Target
+
+use std::path::PathBuf;
+
+pub struct RuntimeUnit {
+    pub file_name: &'static str,
+    pub spec: &'static str,
+    pub body: Option<&'static str>,
+
+    /// Types and prototypes of the C implementation, part of the header of the unit.
+    pub c_header: Option<&'static str>,
+    pub c_code: Option<&'static str>,
+}
+
+impl RuntimeUnit {
+    pub fn spec_path(&self) -> PathBuf {
+        format!("<runtime>/{}.ads", self.file_name).into()
+    }
+
+    pub fn body_path(&self) -> PathBuf {
+        format!("<runtime>/{}.adb", self.file_name).into()
+    }
+}
+
+/// The predefined package `Standard`, always loaded before any other unit.
+pub const STANDARD: RuntimeUnit = RuntimeUnit {
+    file_name: "standard",
+    spec: include_str!("../runtime/standard.ads"),
+    body: None,
+    c_header: Some(include_str!("../runtime/standard.h")),
+    c_code: Some(include_str!("../runtime/standard.c")),
+};
+
+/// Declaration of the raising of exceptions, for C and C++.
+pub const EXCEPTIONS_H: &str = include_str!("../runtime/exceptions.h");
+
+/// Raising of exceptions in the generated C code.
+pub const EXCEPTIONS_C: &str = include_str!("../runtime/exceptions.c");
+
+/// Raising of exceptions in the generated C++ code.
+pub const EXCEPTIONS_CXX: &str = include_str!("../runtime/exceptions.cpp");
+
+pub fn find(name: &str) -> Option<RuntimeUnit> {
+    Some(match name {
+        "ada" => RuntimeUnit {
+            file_name: "ada",
+            spec: include_str!("../runtime/ada.ads"),
+            body: None,
+            c_header: None,
+            c_code: None,
+        },
+        "ada.text_io" => RuntimeUnit {
+            file_name: "a-textio",
+            spec: include_str!("../runtime/a-textio.ads"),
+            body: Some(include_str!("../runtime/a-textio.adb")),
+            c_header: None,
+            c_code: Some(include_str!("../runtime/a-textio.c")),
+        },
+        "ada.command_line" => RuntimeUnit {
+            file_name: "a-comlin",
+            spec: include_str!("../runtime/a-comlin.ads"),
+            body: None,
+            c_header: None,
+            c_code: Some(include_str!("../runtime/a-comlin.c")),
+        },
+        "ada.environment_variables" => RuntimeUnit {
+            file_name: "a-envvar",
+            spec: include_str!("../runtime/a-envvar.ads"),
+            body: None,
+            c_header: None,
+            c_code: Some(include_str!("../runtime/a-envvar.c")),
+        },
+        "ada.integer_text_io" => RuntimeUnit {
+            file_name: "a-inteio",
+            spec: include_str!("../runtime/a-inteio.ads"),
+            body: None,
+            c_header: None,
+            c_code: None,
+        },
+        "ada.unchecked_conversion" => RuntimeUnit {
+            file_name: "a-unccon",
+            spec: include_str!("../runtime/a-unccon.ads"),
+            body: None,
+            c_header: Some(include_str!("../runtime/a-unccon.h")),
+            c_code: None,
+        },
+        "interfaces" => RuntimeUnit {
+            file_name: "interfac",
+            spec: include_str!("../runtime/interfac.ads"),
+            body: None,
+            c_header: None,
+            c_code: None,
+        },
+        "interfaces.c" => RuntimeUnit {
+            file_name: "i-c",
+            spec: include_str!("../runtime/i-c.ads"),
+            body: None,
+            c_header: None,
+            c_code: None,
+        },
+        "system" => RuntimeUnit {
+            file_name: "system",
+            spec: include_str!("../runtime/system.ads"),
+            body: None,
+            c_header: None,
+            c_code: None,
+        },
+        "system.os_lib" => RuntimeUnit {
+            file_name: "s-os_lib",
+            spec: include_str!("../runtime/s-os_lib.ads"),
+            body: None,
+            c_header: None,
+            c_code: Some(include_str!("../runtime/s-os_lib.c")),
+        },
+        "system.storage_elements" => RuntimeUnit {
+            file_name: "s-stoele",
+            spec: include_str!("../runtime/s-stoele.ads"),
+            body: Some(include_str!("../runtime/s-stoele.adb")),
+            c_header: None,
+            c_code: None,
+        },
+        _ => return None,
+    })
+}
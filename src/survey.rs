@@ -0,0 +1,267 @@
+//! `annabella survey DIR` transpiles every Ada source file below a directory, going on
+//! after failures, and ranks the errors to find the missing features blocking most units.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    ffi::OsString,
+    fs,
+    panic::{self, AssertUnwindSafe},
+    path::{Path, PathBuf},
+};
+
+use annabella::{codegen::Options, Diagnostics, Error};
+use anyhow::{Context, Result};
+
+use crate::{diagnostic_json, json_string, run};
+
+pub fn main(mut args: impl Iterator<Item = OsString>) -> Result<()> {
+    let mut dir = None;
+    let mut json = None;
+    while let Some(arg) = args.next() {
+        if arg == "--json" {
+            json = Some(PathBuf::from(args.next().context("JSON output file")?));
+        } else {
+            dir = Some(PathBuf::from(arg));
+        }
+    }
+    let dir = dir.context("source directory")?;
+
+    let mut paths = Vec::new();
+    collect_sources(&dir, &mut paths)?;
+
+    // a panic of an unfinished part of the transpiler aborts only its file
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let files: Vec<_> = paths
+        .into_iter()
+        .map(|path| {
+            let file = survey_file(path);
+            println!("{file}");
+            file
+        })
+        .collect();
+    panic::set_hook(hook);
+
+    let groups = group_failures(&files);
+    print_summary(&files, &groups);
+    if let Some(json) = json {
+        fs::write(&json, survey_json(&files, &groups))
+            .with_context(|| format!("write survey: {json:?}"))?;
+    }
+    Ok(())
+}
+
+/// The `.ads` and `.adb` files below `dir`, sorted by path.
+fn collect_sources(dir: &Path, paths: &mut Vec<PathBuf>) -> Result<()> {
+    let mut entries = fs::read_dir(dir)
+        .with_context(|| format!("read directory: {dir:?}"))?
+        .map(|entry| Ok(entry?.path()))
+        .collect::<std::io::Result<Vec<_>>>()
+        .with_context(|| format!("read directory: {dir:?}"))?;
+    entries.sort();
+    for path in entries {
+        if path.is_dir() {
+            collect_sources(&path, paths)?;
+        } else if path
+            .extension()
+            .is_some_and(|ext| ext == "ads" || ext == "adb")
+        {
+            paths.push(path);
+        }
+    }
+    Ok(())
+}
+
+struct FileSurvey {
+    path: PathBuf,
+    errors: Vec<Error>,
+
+    /// The message of a panic or of a read error, which stopped the file.
+    abort: Option<String>,
+}
+
+impl FileSurvey {
+    fn status(&self) -> &'static str {
+        if self.abort.is_some() {
+            "aborted"
+        } else if !self.errors.is_empty() {
+            "failed"
+        } else {
+            "ok"
+        }
+    }
+}
+
+impl std::fmt::Display for FileSurvey {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{:<8} {}", self.status(), self.path.display())?;
+        match (&self.abort, self.errors.len()) {
+            (Some(abort), _) => write!(f, ": {abort}"),
+            (None, 0) => Ok(()),
+            (None, 1) => write!(f, ": 1 error"),
+            (None, count) => write!(f, ": {count} errors"),
+        }
+    }
+}
+
+/// Transpile a file on its own, like a library unit without a main subprogram.
+fn survey_file(path: PathBuf) -> FileSurvey {
+    let mut file = FileSurvey {
+        path,
+        errors: Vec::new(),
+        abort: None,
+    };
+    let source = match fs::read_to_string(&file.path) {
+        Ok(source) => source,
+        Err(err) => {
+            file.abort = Some(format!("read source: {err}"));
+            return file;
+        }
+    };
+    let options = Options {
+        search_path: file
+            .path
+            .parent()
+            .map(Path::to_path_buf)
+            .into_iter()
+            .collect(),
+        no_main: true,
+        ..Default::default()
+    };
+    let diagnostics = Diagnostics::default();
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        run(source, file.path.clone(), options, &diagnostics)
+    }));
+    file.errors = diagnostics.take();
+    match result {
+        Ok(Ok(_)) => {}
+        Ok(Err(err)) if !err.cascade => file.errors.push(err),
+        Ok(Err(_)) => {}
+        Err(payload) => {
+            let msg = payload
+                .downcast_ref::<&str>()
+                .map(|msg| msg.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "unknown panic payload".into());
+            file.abort = Some(format!("panic: {msg}"));
+        }
+    }
+    file
+}
+
+/// Failures with the same code and syntax node kind, or aborts with the same message.
+struct Group {
+    code: String,
+    category: &'static str,
+    node: Option<&'static str>,
+
+    /// The message of the first failure.
+    message: String,
+    count: usize,
+    files: BTreeSet<usize>,
+}
+
+/// The groups of the failures, ranked by the number of files they affect.
+fn group_failures(files: &[FileSurvey]) -> Vec<Group> {
+    let mut groups = BTreeMap::new();
+    for (index, file) in files.iter().enumerate() {
+        let errors = file.errors.iter().map(|err| {
+            let key = (err.code(), err.node, String::new());
+            (key, err.kind.name(), err.msg.to_string())
+        });
+        let aborts = file.abort.iter().map(|abort| {
+            let key = ("-".to_string(), None, abort.clone());
+            (key, "abort", abort.clone())
+        });
+        for (key, category, message) in errors.chain(aborts) {
+            let group = groups.entry(key.clone()).or_insert_with(|| Group {
+                code: key.0,
+                category,
+                node: key.1,
+                message,
+                count: 0,
+                files: BTreeSet::new(),
+            });
+            group.count += 1;
+            group.files.insert(index);
+        }
+    }
+    let mut groups: Vec<_> = groups.into_values().collect();
+    groups.sort_by(|a, b| {
+        (b.files.len(), b.count)
+            .cmp(&(a.files.len(), a.count))
+            .then_with(|| a.code.cmp(&b.code))
+    });
+    groups
+}
+
+fn print_summary(files: &[FileSurvey], groups: &[Group]) {
+    let count = |status| files.iter().filter(|file| file.status() == status).count();
+    println!();
+    println!(
+        "{} files: {} ok, {} failed, {} aborted",
+        files.len(),
+        count("ok"),
+        count("failed"),
+        count("aborted"),
+    );
+    if groups.is_empty() {
+        return;
+    }
+    println!();
+    println!(
+        "{:>6} {:>6}  {:<8} {:<13} {:<28} message",
+        "files", "errors", "code", "category", "node"
+    );
+    for group in groups {
+        println!(
+            "{:>6} {:>6}  {:<8} {:<13} {:<28} {}",
+            group.files.len(),
+            group.count,
+            group.code,
+            group.category,
+            group.node.unwrap_or("-"),
+            group.message,
+        );
+    }
+}
+
+/// The files with their status and diagnostics, and the ranked groups.
+fn survey_json(files: &[FileSurvey], groups: &[Group]) -> String {
+    let files: Vec<_> = files
+        .iter()
+        .map(|file| {
+            let errors: Vec<_> = file
+                .errors
+                .iter()
+                .map(|err| diagnostic_json("error", err))
+                .collect();
+            format!(
+                "{{\"path\":{},\"status\":{},\"abort\":{},\"errors\":[{}]}}",
+                json_string(&file.path.to_string_lossy()),
+                json_string(file.status()),
+                file.abort.as_deref().map_or("null".into(), json_string),
+                errors.join(","),
+            )
+        })
+        .collect();
+    let groups: Vec<_> = groups
+        .iter()
+        .map(|group| {
+            format!(
+                "{{\"code\":{},\"category\":{},\"node\":{},\"message\":{},\"files\":{},\"errors\":{}}}",
+                json_string(&group.code),
+                json_string(group.category),
+                group.node.map_or("null".into(), json_string),
+                json_string(&group.message),
+                group.files.len(),
+                group.count,
+            )
+        })
+        .collect();
+    format!(
+        "{{\"files\":[\n{}\n],\"groups\":[\n{}\n]}}\n",
+        files.join(",\n"),
+        groups.join(",\n"),
+    )
+}
@@ -7,8 +7,10 @@ use std::{
 
 mod span;
 
-use crate::Result;
+use crate::{Error, Result};
 
+#[cfg(feature = "span-locations")]
+pub use self::span::LineColumn;
 use self::span::SpanOffset;
 pub use self::span::{Span, Spanned};
 
@@ -38,6 +40,7 @@ impl TokenStream {
             str: source,
             offset: span.start,
         })
+        .map_err(Error::syntax)
     }
 
     fn parse_cursor(mut input: Cursor) -> Result<Self> {
@@ -551,9 +554,9 @@ impl Literal {
                                 ));
                             };
                             match input.clone().advance_char() {
-                                Some(('+' | '-' | '0'..='9', _)) => {
-                                    return Err(input.unrecoverable_error(
-                                        "based literal with exponent not implemented",
+                                Some(('E' | 'e', _)) => {
+                                    return Err(input.unimplemented_error(
+                                        "based literal with exponent not yet implemented",
                                     ))
                                 }
                                 _ => input,
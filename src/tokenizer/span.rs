@@ -3,7 +3,7 @@ use std::{
     path::PathBuf, ptr::NonNull,
 };
 
-use crate::Error;
+use crate::{Error, ErrorKind};
 
 #[cfg(feature = "span-locations")]
 pub(super) type SpanOffset = u32;
@@ -30,7 +30,13 @@ pub trait Spanned {
         Error {
             span: self.span(),
             msg: msg.into(),
+            kind: ErrorKind::Type,
             recoverable: true,
+            labels: Vec::new(),
+            notes: Vec::new(),
+            node: None,
+            cascade: false,
+            arity: false,
         }
     }
 
@@ -38,7 +44,28 @@ pub trait Spanned {
         Error {
             span: self.span(),
             msg: msg.into(),
+            kind: ErrorKind::Type,
             recoverable: false,
+            labels: Vec::new(),
+            notes: Vec::new(),
+            node: None,
+            cascade: false,
+            arity: false,
+        }
+    }
+
+    /// An error about an Ada feature which is not yet implemented.
+    fn unimplemented_error(&self, msg: impl Into<Cow<'static, str>>) -> Error {
+        Error {
+            span: self.span(),
+            msg: msg.into(),
+            kind: ErrorKind::Unimplemented,
+            recoverable: false,
+            labels: Vec::new(),
+            notes: Vec::new(),
+            node: None,
+            cascade: false,
+            arity: false,
         }
     }
 }
@@ -55,6 +82,12 @@ impl<T: Spanned> Spanned for &mut T {
     }
 }
 
+impl<T: Spanned + ?Sized> Spanned for Box<T> {
+    fn span(&self) -> Span {
+        T::span(self)
+    }
+}
+
 impl<T: Spanned> Spanned for Option<T> {
     fn span(&self) -> Span {
         self.as_ref().map_or(Span::call_site(), T::span)
@@ -175,6 +208,20 @@ impl Span {
         self.start == ZERO_INDEX && self.end == ZERO_INDEX
     }
 
+    /// Whether the span starts before `other`, always true without locations.
+    pub fn starts_before(self, other: Self) -> bool {
+        #[cfg(feature = "span-locations")]
+        {
+            self.start < other.start
+        }
+
+        #[cfg(not(feature = "span-locations"))]
+        {
+            let _ = other;
+            true
+        }
+    }
+
     pub fn source(self) -> Option<String> {
         #[cfg(feature = "span-locations")]
         if !self.is_call_site() {
@@ -363,7 +410,8 @@ impl FileInfo {
                 .insert(start_char_offset, start_byte_index);
         };
 
-        let end_char_offset = span.end;
+        // `span.end` is inclusive
+        let end_char_offset = span.end + 1 - self.span.start;
         let end_byte_index = self.char_offset_to_byte_index_with_last(
             start_char_offset,
             start_byte_index,
@@ -393,10 +441,10 @@ impl FileInfo {
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct LineColumn {
     /// The 1-indexec line in the source file.
-    line: usize,
+    pub line: usize,
 
     /// The 0-indexed column (in characters) in the source file.
-    column: u32,
+    pub column: u32,
 }
 
 pub fn offset_add(offset: SpanOffset, num: impl Fn() -> usize) -> SpanOffset {
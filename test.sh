@@ -11,9 +11,7 @@ echo
 echo --------------------------------------------------------------------------------
 echo
 
-target/debug/annabella-rs "$name" \
-| clang-format \
-> "$out.c"
+target/debug/annabella-rs "$name" > "$out.c"
 
 echo
 echo --------------------------------------------------------------------------------
@@ -0,0 +1,21 @@
+use std::process::Command;
+
+/// A package body surveyed on its own loads its specification.
+#[test]
+fn spec_and_body() {
+    let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/survey");
+    let output = Command::new(env!("CARGO_BIN_EXE_annabella"))
+        .args(["survey", dir])
+        .output()
+        .expect("run survey");
+    let stdout = String::from_utf8(output.stdout).expect("UTF-8 output");
+    assert!(output.status.success(), "{stdout}");
+    for file in ["pkg_s.adb", "pkg_s.ads"] {
+        assert!(
+            stdout
+                .lines()
+                .any(|line| line.starts_with("ok ") && line.ends_with(file)),
+            "{file} is not ok:\n{stdout}"
+        );
+    }
+}
@@ -0,0 +1,6 @@
+package body Pkg_S is
+   procedure P is
+   begin
+      X := X + 1;
+   end P;
+end Pkg_S;